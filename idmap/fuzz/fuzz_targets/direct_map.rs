@@ -0,0 +1,8 @@
+#![no_main]
+use idmap::DirectIdMap;
+use idmap_fuzz::{check_map, Input, MapOp};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: Input<DirectIdMap<u16, u32>, MapOp<u16>>| {
+    check_map(input);
+});
@@ -0,0 +1,8 @@
+#![no_main]
+use idmap::DirectIdSet;
+use idmap_fuzz::{check_set, Input, SetOp};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: Input<DirectIdSet<u16>, SetOp<u16>>| {
+    check_set(input);
+});
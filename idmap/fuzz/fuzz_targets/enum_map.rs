@@ -0,0 +1,8 @@
+#![no_main]
+use idmap::EnumMap;
+use idmap_fuzz::{check_map, Color, Input, MapOp};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: Input<EnumMap<Color, u32>, MapOp<Color>>| {
+    check_map(input);
+});
@@ -0,0 +1,8 @@
+#![no_main]
+use idmap::EnumSet;
+use idmap_fuzz::{check_set, Color, Input, SetOp};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: Input<EnumSet<Color>, SetOp<Color>>| {
+    check_set(input);
+});
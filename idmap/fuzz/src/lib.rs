@@ -0,0 +1,258 @@
+//! Shared logic for the fuzz targets,
+//! which cross-check the idmap collections against a [`BTreeMap`] or [`BTreeSet`] model.
+#![allow(missing_docs)]
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Debug;
+
+use arbitrary::Arbitrary;
+use idmap::{DirectIdMap, DirectIdSet, EnumMap, EnumSet};
+use intid::IntegerId;
+
+/// A C-like enum with gaps in its discriminants,
+/// spanning more than one limb of an [`EnumSet`].
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
+    Arbitrary,
+    intid::IntegerId,
+    intid::EnumId,
+)]
+#[repr(u8)]
+pub enum Color {
+    Red,
+    Green,
+    Blue = 5,
+    Cyan,
+    Magenta = 70,
+    Yellow,
+    Black = 127,
+}
+
+/// The map operations that are checked against the model.
+#[derive(Debug, Arbitrary)]
+pub enum MapOp<K> {
+    Insert(K, u32),
+    Remove(K),
+    Get(K),
+    /// Retain the entries where `value & mask == 0`.
+    Retain(u32),
+    Clear,
+}
+
+/// The set operations that are checked against the model.
+#[derive(Debug, Arbitrary)]
+pub enum SetOp<T> {
+    Insert(T),
+    Remove(T),
+    Contains(T),
+    /// Retain the entries where `id & mask == 0`.
+    Retain(u32),
+    Clear,
+}
+
+#[derive(Debug, Arbitrary)]
+pub struct Input<C, Op> {
+    pub initial: C,
+    pub ops: Vec<Op>,
+}
+
+/// The common interface of [`DirectIdMap`] and [`EnumMap`].
+pub trait IdMapLike<K: IntegerId>: Debug {
+    fn insert(&mut self, key: K, value: u32) -> Option<u32>;
+    fn remove(&mut self, key: K) -> Option<u32>;
+    fn get(&self, key: K) -> Option<u32>;
+    fn retain(&mut self, func: impl FnMut(K, &mut u32) -> bool);
+    fn clear(&mut self);
+    fn len(&self) -> usize;
+    fn entries(&self) -> Vec<(K, u32)>;
+    fn entries_rev(&self) -> Vec<(K, u32)>;
+}
+
+/// The common interface of [`DirectIdSet`] and [`EnumSet`].
+pub trait IdSetLike<T: IntegerId>: Debug {
+    fn insert(&mut self, value: T) -> bool;
+    fn remove(&mut self, value: T) -> bool;
+    fn contains(&self, value: T) -> bool;
+    fn retain(&mut self, func: impl FnMut(T) -> bool);
+    fn clear(&mut self);
+    fn len(&self) -> usize;
+    fn values(&self) -> Vec<T>;
+    fn values_rev(&self) -> Vec<T>;
+}
+
+macro_rules! impl_map_like {
+    ($target:ident<K: $bound:ident>) => {
+        impl<K: intid::$bound> IdMapLike<K> for $target<K, u32> {
+            fn insert(&mut self, key: K, value: u32) -> Option<u32> {
+                $target::insert(self, key, value)
+            }
+            fn remove(&mut self, key: K) -> Option<u32> {
+                $target::remove(self, key)
+            }
+            fn get(&self, key: K) -> Option<u32> {
+                $target::get(self, key).copied()
+            }
+            fn retain(&mut self, func: impl FnMut(K, &mut u32) -> bool) {
+                $target::retain(self, func);
+            }
+            fn clear(&mut self) {
+                $target::clear(self);
+            }
+            fn len(&self) -> usize {
+                $target::len(self)
+            }
+            fn entries(&self) -> Vec<(K, u32)> {
+                self.iter().map(|(k, &v)| (k, v)).collect()
+            }
+            fn entries_rev(&self) -> Vec<(K, u32)> {
+                self.iter().rev().map(|(k, &v)| (k, v)).collect()
+            }
+        }
+    };
+}
+impl_map_like!(DirectIdMap<K: IntegerId>);
+impl_map_like!(EnumMap<K: EnumId>);
+
+macro_rules! impl_set_like {
+    ($target:ident<T: $bound:ident>) => {
+        impl<T: intid::$bound> IdSetLike<T> for $target<T> {
+            fn insert(&mut self, value: T) -> bool {
+                $target::insert(self, value)
+            }
+            fn remove(&mut self, value: T) -> bool {
+                $target::remove(self, value)
+            }
+            fn contains(&self, value: T) -> bool {
+                $target::contains(self, value)
+            }
+            fn retain(&mut self, func: impl FnMut(T) -> bool) {
+                $target::retain(self, func);
+            }
+            fn clear(&mut self) {
+                $target::clear(self);
+            }
+            fn len(&self) -> usize {
+                $target::len(self)
+            }
+            fn values(&self) -> Vec<T> {
+                self.iter().collect()
+            }
+            fn values_rev(&self) -> Vec<T> {
+                self.iter().rev().collect()
+            }
+        }
+    };
+}
+impl_set_like!(DirectIdSet<T: IntegerId>);
+impl_set_like!(EnumSet<T: EnumId>);
+
+fn id_bits<T: IntegerId>(id: T) -> u32 {
+    intid::primint::wrapping_cast(id.to_int())
+}
+
+fn verify_map<K, M>(map: &M, model: &BTreeMap<K, u32>)
+where
+    K: IntegerId + Ord,
+    M: IdMapLike<K>,
+{
+    assert_eq!(map.len(), model.len(), "{map:?}");
+    let expected = model.iter().map(|(&k, &v)| (k, v)).collect::<Vec<_>>();
+    assert_eq!(map.entries(), expected);
+    let mut expected_rev = expected;
+    expected_rev.reverse();
+    assert_eq!(map.entries_rev(), expected_rev);
+}
+
+/// Apply the operations to both the map and a [`BTreeMap`] model,
+/// checking that they stay consistent.
+pub fn check_map<K, M>(input: Input<M, MapOp<K>>)
+where
+    K: IntegerId + Ord,
+    M: IdMapLike<K>,
+{
+    let Input {
+        initial: mut map,
+        ops,
+    } = input;
+    let mut model = map.entries().into_iter().collect::<BTreeMap<K, u32>>();
+    verify_map(&map, &model);
+    for op in ops {
+        match op {
+            MapOp::Insert(key, value) => {
+                assert_eq!(map.insert(key, value), model.insert(key, value));
+            }
+            MapOp::Remove(key) => {
+                assert_eq!(map.remove(key), model.remove(&key));
+            }
+            MapOp::Get(key) => {
+                assert_eq!(map.get(key), model.get(&key).copied());
+            }
+            MapOp::Retain(mask) => {
+                map.retain(|_, value| *value & mask == 0);
+                model.retain(|_, value| *value & mask == 0);
+            }
+            MapOp::Clear => {
+                map.clear();
+                model.clear();
+            }
+        }
+        verify_map(&map, &model);
+    }
+}
+
+fn verify_set<T, S>(set: &S, model: &BTreeSet<T>)
+where
+    T: IntegerId + Ord,
+    S: IdSetLike<T>,
+{
+    assert_eq!(set.len(), model.len(), "{set:?}");
+    let expected = model.iter().copied().collect::<Vec<_>>();
+    assert_eq!(set.values(), expected);
+    let mut expected_rev = expected;
+    expected_rev.reverse();
+    assert_eq!(set.values_rev(), expected_rev);
+}
+
+/// Apply the operations to both the set and a [`BTreeSet`] model,
+/// checking that they stay consistent.
+pub fn check_set<T, S>(input: Input<S, SetOp<T>>)
+where
+    T: IntegerId + Ord,
+    S: IdSetLike<T>,
+{
+    let Input {
+        initial: mut set,
+        ops,
+    } = input;
+    let mut model = set.values().into_iter().collect::<BTreeSet<T>>();
+    verify_set(&set, &model);
+    for op in ops {
+        match op {
+            SetOp::Insert(value) => {
+                assert_eq!(set.insert(value), model.insert(value));
+            }
+            SetOp::Remove(value) => {
+                assert_eq!(set.remove(value), model.remove(&value));
+            }
+            SetOp::Contains(value) => {
+                assert_eq!(set.contains(value), model.contains(&value));
+            }
+            SetOp::Retain(mask) => {
+                set.retain(|value| id_bits(value) & mask == 0);
+                model.retain(|&value| id_bits(value) & mask == 0);
+            }
+            SetOp::Clear => {
+                set.clear();
+                model.clear();
+            }
+        }
+        verify_set(&set, &model);
+    }
+}
@@ -2,6 +2,8 @@
 //!
 //! This is roughly equivalent to a `Vec<Option<T>>` for the map and bitset for the set.
 
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
 pub(crate) mod macros;
 pub mod map;
 #[cfg(feature = "serde")]
@@ -16,7 +18,7 @@ use intid::primint;
 #[inline(never)]
 #[track_caller]
 #[cold]
-fn oom_id(id: impl primint::UnsignedPrimInt) -> ! {
+pub(crate) fn oom_id(id: impl primint::UnsignedPrimInt) -> ! {
     panic!(
         "Storing id would exhaust memory: {}",
         primint::fmt::debug_desc(id),
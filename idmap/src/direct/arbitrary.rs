@@ -0,0 +1,46 @@
+//! Implements [`arbitrary::Arbitrary`] for [`DirectIdMap`] and [`DirectIdSet`].
+//!
+//! Generated keys are limited to the first [`MAX_ARBITRARY_IDS`] integers after `MIN_ID_INT`,
+//! so fuzzing never requires allocating huge tables.
+
+use arbitrary::{Arbitrary, Unstructured};
+use intid::IntegerId;
+
+use super::{DirectIdMap, DirectIdSet};
+use crate::utils::arbitrary::{arbitrary_id, MAX_ARBITRARY_IDS};
+
+/// Generated keys are limited to the first 4096 integers after [`IntegerId::MIN_ID_INT`],
+/// to avoid allocating a huge table.
+impl<'a, K, V> Arbitrary<'a> for DirectIdMap<K, V>
+where
+    K: IntegerId,
+    V: Arbitrary<'a>,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let len = u.arbitrary_len::<(u32, V)>()?;
+        let mut result = DirectIdMap::new();
+        for _ in 0..len {
+            let key = arbitrary_id::<K>(u, MAX_ARBITRARY_IDS)?;
+            let value = V::arbitrary(u)?;
+            if let Some(key) = key {
+                result.insert(key, value);
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Generated values are limited to the first 4096 integers after [`IntegerId::MIN_ID_INT`],
+/// to avoid allocating a huge bitset.
+impl<'a, T: IntegerId> Arbitrary<'a> for DirectIdSet<T> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let len = u.arbitrary_len::<u32>()?;
+        let mut result = DirectIdSet::new();
+        for _ in 0..len {
+            if let Some(value) = arbitrary_id::<T>(u, MAX_ARBITRARY_IDS)? {
+                result.insert(value);
+            }
+        }
+        Ok(result)
+    }
+}
@@ -37,6 +37,20 @@ impl<K: IntegerId, V> DirectIdMap<K, V> {
             marker: PhantomData,
         }
     }
+
+    /// Initialize the map with the given capacity
+    ///
+    /// Since this is a direct map,
+    /// this hints at the maximum valid id and not the length.
+    #[inline]
+    pub fn with_capacity(max_id: usize) -> Self {
+        DirectIdMap {
+            values: Vec::with_capacity(max_id),
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+
     /// The number of entries in the map.
     #[inline]
     pub fn len(&self) -> usize {
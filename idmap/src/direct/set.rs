@@ -148,7 +148,7 @@ impl<T: IntegerId> DirectIdSet<T> {
     pub fn retain<F: FnMut(T) -> bool>(&mut self, mut func: F) {
         for (word_index, word) in self.handle.as_mut_slice().iter_mut().enumerate() {
             let (updated_word, word_removed) = retain_word(*word, |bit| {
-                let id = (word_index * 32) + (bit as usize);
+                let id = (word_index * Word::BITS as usize) + (bit as usize);
                 // Safety: If present in the map, it is known to be valid
                 let key = unsafe { T::from_int_unchecked(primint::from_usize_wrapping(id)) };
                 func(key)
@@ -6,6 +6,8 @@
 //!
 //! [`EnumId`]: intid::EnumId
 
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
 pub mod map;
 #[cfg(feature = "serde")]
 mod serde;
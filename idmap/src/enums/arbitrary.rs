@@ -0,0 +1,40 @@
+//! Implements [`arbitrary::Arbitrary`] for [`EnumMap`] and [`EnumSet`].
+
+use arbitrary::{Arbitrary, Unstructured};
+use intid::EnumId;
+
+use super::{EnumMap, EnumSet};
+use crate::utils::arbitrary::arbitrary_id;
+
+impl<'a, K, V> Arbitrary<'a> for EnumMap<K, V>
+where
+    K: EnumId,
+    V: Arbitrary<'a>,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let len = u.arbitrary_len::<(u32, V)>()?;
+        let mut result = EnumMap::new();
+        for _ in 0..len {
+            // EnumId guarantees all ids fit in a u32, so there is no need for a limit
+            let key = arbitrary_id::<K>(u, u32::MAX)?;
+            let value = V::arbitrary(u)?;
+            if let Some(key) = key {
+                result.insert(key, value);
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl<'a, T: EnumId> Arbitrary<'a> for EnumSet<T> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let len = u.arbitrary_len::<u32>()?;
+        let mut result = EnumSet::new();
+        for _ in 0..len {
+            if let Some(value) = arbitrary_id::<T>(u, u32::MAX)? {
+                result.insert(value);
+            }
+        }
+        Ok(result)
+    }
+}
@@ -154,6 +154,9 @@ impl<T: EnumId> EnumSet<T> {
         let mask = bitmask_for(bit_index);
         let was_present = (mask & *word) != 0;
         *word |= mask;
+        if !was_present {
+            self.len += 1;
+        }
         !was_present
     }
 
@@ -172,6 +175,9 @@ impl<T: EnumId> EnumSet<T> {
         let mask = bitmask_for(bit_index);
         let was_present = (mask & *word) != 0;
         *word &= !mask;
+        if was_present {
+            self.len -= 1;
+        }
         was_present
     }
 
@@ -225,7 +231,7 @@ impl<T: EnumId> EnumSet<T> {
     pub fn retain<F: FnMut(T) -> bool>(&mut self, mut func: F) {
         for (word_index, word) in self.limbs.as_mut().iter_mut().enumerate() {
             let (updated_word, word_removed) = retain_word(*word, |bit| {
-                let id = (word_index * 32) + (bit as usize);
+                let id = (word_index * BitsetLimb::BITS as usize) + (bit as usize);
                 // Safety: If present in the map, it is known to be valid
                 let key = unsafe { T::from_int_unchecked(primint::from_usize_wrapping(id)) };
                 func(key)
@@ -0,0 +1,265 @@
+//! An arena keyed by [`Generational`] ids, which rejects stale keys.
+//!
+//! See [`GenArena`] for details.
+
+use alloc::vec::Vec;
+use core::fmt::{self, Debug, Formatter};
+use core::iter::FusedIterator;
+use core::ops::{Index, IndexMut};
+use core::slice;
+
+use intid::utils::Generational;
+use intid::IntegerIdCounter;
+use primint::UnsignedPrimInt;
+
+use crate::direct::oom_id;
+
+/// An arena of values keyed by [`Generational`] ids.
+///
+/// Values are stored densely in a table indexed by the index part of the key,
+/// just like a [`DirectIdMap`](crate::DirectIdMap).
+/// The table begins at [`K::START`](IntegerIdCounter::START),
+/// so a counter which starts far from zero does not waste space.
+/// Indexes of removed values are reused by later insertions,
+/// but each removal bumps the generation of the index,
+/// so lookups with a stale key return `None` instead of aliasing the new value.
+///
+/// Once the generation of an index reaches the maximum value of `G`,
+/// removing its value retires the index permanently instead of wrapping around.
+///
+/// # Example
+/// ```
+/// use idmap::GenArena;
+/// intid::define_newtype_counter! {
+///     struct NodeId(u32);
+/// }
+/// let mut nodes = GenArena::<NodeId, &str>::new();
+/// let a = nodes.insert("a");
+/// assert_eq!(nodes.remove(a), Some("a"));
+/// let b = nodes.insert("b");
+/// assert_eq!(a.index(), b.index());
+/// assert_eq!(nodes.get(a), None);
+/// assert_eq!(nodes.get(b), Some(&"b"));
+/// ```
+pub struct GenArena<K: IntegerIdCounter, V, G: UnsignedPrimInt = u32> {
+    entries: Vec<Entry<V, G>>,
+    /// Removed indexes available for reuse
+    free: Vec<K>,
+    /// The next index which has never been used,
+    /// or `None` if they have been exhausted.
+    next_index: Option<K>,
+    len: usize,
+}
+#[derive(Clone)]
+struct Entry<V, G> {
+    generation: G,
+    value: Option<V>,
+}
+impl<K: IntegerIdCounter, V, G: UnsignedPrimInt> GenArena<K, V, G> {
+    /// Create a new arena with no values.
+    #[inline]
+    pub const fn new() -> Self {
+        GenArena {
+            entries: Vec::new(),
+            free: Vec::new(),
+            next_index: Some(K::START),
+            len: 0,
+        }
+    }
+
+    /// The number of values in the arena.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return true if the arena is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Insert a value, returning its newly allocated key.
+    ///
+    /// Indexes of removed values are reused where possible.
+    ///
+    /// # Panics
+    /// If all indexes of `K` have been used or retired.
+    #[track_caller]
+    pub fn insert(&mut self, value: V) -> Generational<K, G> {
+        let index = if let Some(index) = self.free.pop() {
+            index
+        } else {
+            let index = self
+                .next_index
+                .unwrap_or_else(|| panic!("Ran out of ids for {}", core::any::type_name::<K>()));
+            self.next_index = IntegerIdCounter::checked_add(index, primint::one());
+            index
+        };
+        let pos = entry_pos(index).unwrap_or_else(|| oom_id(index.to_int()));
+        if pos >= self.entries.len() {
+            self.entries.resize_with(pos + 1, || Entry {
+                generation: primint::zero(),
+                value: None,
+            });
+        }
+        let entry = &mut self.entries[pos];
+        debug_assert!(entry.value.is_none());
+        entry.value = Some(value);
+        self.len += 1;
+        Generational::new(index, entry.generation)
+    }
+
+    /// Check if the specified key refers to a value in the arena.
+    ///
+    /// Returns `false` for stale keys.
+    #[inline]
+    pub fn contains_key(&self, key: Generational<K, G>) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Get the value associated with the specified key,
+    /// or `None` if it has been removed.
+    ///
+    /// Stale keys from a previous generation of the index always return `None`.
+    #[inline]
+    pub fn get(&self, key: Generational<K, G>) -> Option<&V> {
+        let entry = self.entries.get(entry_pos(key.index())?)?;
+        if entry.generation == key.generation() {
+            entry.value.as_ref()
+        } else {
+            None
+        }
+    }
+
+    /// Get a mutable reference to the value associated with the specified key,
+    /// or `None` if it has been removed.
+    ///
+    /// Stale keys from a previous generation of the index always return `None`.
+    #[inline]
+    pub fn get_mut(&mut self, key: Generational<K, G>) -> Option<&mut V> {
+        let entry = self.entries.get_mut(entry_pos(key.index())?)?;
+        if entry.generation == key.generation() {
+            entry.value.as_mut()
+        } else {
+            None
+        }
+    }
+
+    /// Remove the value associated with the specified key,
+    /// bumping the generation of its index so it can be reused.
+    ///
+    /// Returns `None` and leaves the arena unchanged if the key is stale.
+    pub fn remove(&mut self, key: Generational<K, G>) -> Option<V> {
+        let entry = self.entries.get_mut(entry_pos(key.index())?)?;
+        if entry.generation != key.generation() {
+            return None;
+        }
+        let value = entry.value.take()?;
+        self.len -= 1;
+        // retire the index once its generation is exhausted
+        if let Some(next) = primint::checked_add(entry.generation, primint::one()) {
+            entry.generation = next;
+            self.free.push(key.index());
+        }
+        Some(value)
+    }
+
+    /// Iterate over the keys and values in the arena,
+    /// in order of the integer value of the index.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V, G> {
+        Iter {
+            source: self.entries.iter().enumerate(),
+            len: self.len,
+            marker: core::marker::PhantomData,
+        }
+    }
+}
+impl<K: IntegerIdCounter, V, G: UnsignedPrimInt> Default for GenArena<K, V, G> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<K: IntegerIdCounter, V: Clone, G: UnsignedPrimInt> Clone for GenArena<K, V, G> {
+    fn clone(&self) -> Self {
+        GenArena {
+            entries: self.entries.clone(),
+            free: self.free.clone(),
+            next_index: self.next_index,
+            len: self.len,
+        }
+    }
+}
+impl<K: IntegerIdCounter, V, G: UnsignedPrimInt> Index<Generational<K, G>> for GenArena<K, V, G> {
+    type Output = V;
+
+    #[inline]
+    #[track_caller]
+    fn index(&self, key: Generational<K, G>) -> &V {
+        self.get(key).expect("missing or stale key")
+    }
+}
+impl<K: IntegerIdCounter, V, G: UnsignedPrimInt> IndexMut<Generational<K, G>>
+    for GenArena<K, V, G>
+{
+    #[inline]
+    #[track_caller]
+    fn index_mut(&mut self, key: Generational<K, G>) -> &mut V {
+        self.get_mut(key).expect("missing or stale key")
+    }
+}
+impl<K: IntegerIdCounter, V: Debug, G: UnsignedPrimInt> Debug for GenArena<K, V, G> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+impl<'a, K: IntegerIdCounter, V, G: UnsignedPrimInt> IntoIterator for &'a GenArena<K, V, G> {
+    type Item = (Generational<K, G>, &'a V);
+    type IntoIter = Iter<'a, K, V, G>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// The position of the index in the table of entries,
+/// or `None` if it is before [`K::START`](IntegerIdCounter::START) or does not fit in a `usize`.
+#[inline]
+fn entry_pos<K: IntegerIdCounter>(index: K) -> Option<usize> {
+    primint::checked_sub(index.to_int(), K::START_INT).and_then(primint::to_usize_checked)
+}
+
+/// An iterator over the keys and values in a [`GenArena`].
+pub struct Iter<'a, K: IntegerIdCounter, V, G: UnsignedPrimInt> {
+    source: core::iter::Enumerate<slice::Iter<'a, Entry<V, G>>>,
+    len: usize,
+    marker: core::marker::PhantomData<K>,
+}
+impl<'a, K: IntegerIdCounter, V, G: UnsignedPrimInt> Iterator for Iter<'a, K, V, G> {
+    type Item = (Generational<K, G>, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        for (pos, entry) in self.source.by_ref() {
+            if let Some(ref value) = entry.value {
+                self.len -= 1;
+                let int = primint::from_usize_wrapping::<K::Int>(pos) + K::START_INT;
+                // SAFETY: The entry is only occupied if the index was allocated,
+                // so the position is its offset from the start and the integer came from `K::to_int`
+                let index = unsafe { K::from_int_unchecked(int) };
+                return Some((Generational::new(index, entry.generation), value));
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+impl<K: IntegerIdCounter, V, G: UnsignedPrimInt> ExactSizeIterator for Iter<'_, K, V, G> {}
+impl<K: IntegerIdCounter, V, G: UnsignedPrimInt> FusedIterator for Iter<'_, K, V, G> {}
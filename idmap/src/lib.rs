@@ -17,9 +17,15 @@ extern crate alloc;
 
 pub mod direct;
 pub mod enums;
+pub mod gen_arena;
+pub mod packed;
+pub mod slab;
 mod utils;
 
 pub extern crate intid;
 
 pub use self::direct::{DirectIdMap, DirectIdSet};
 pub use self::enums::{EnumMap, EnumSet};
+pub use self::gen_arena::GenArena;
+pub use self::packed::PackedIdVec;
+pub use self::slab::IdSlab;
@@ -0,0 +1,220 @@
+//! A vector of ids stored using a narrower integer type.
+//!
+//! See [`PackedIdVec`] for details.
+
+use alloc::vec::Vec;
+use core::fmt::{self, Debug, Formatter};
+use core::hash::{Hash, Hasher};
+use core::iter::FusedIterator;
+use core::slice;
+
+use intid::primint::UnsignedPrimInt;
+use intid::utils::{Narrow, NarrowError};
+use intid::IntegerId;
+
+/// A vector of ids, where each id is stored using the integer type `N`.
+///
+/// This is useful when ids are declared with a wide integer type like `u64`,
+/// but the actual values fit in a narrower type like `u32` or `u16`.
+/// Storing a `Vec<T>` would waste the upper bytes of every element.
+///
+/// Pushing an id which does not fit in `N` fails with a [`NarrowError`],
+/// leaving the vector unchanged.
+/// Ids are converted back to `T` when they are accessed.
+///
+/// # Example
+/// ```
+/// use idmap::PackedIdVec;
+/// intid::define_newtype_id! {
+///     struct NodeId(u64);
+/// }
+/// let mut nodes = PackedIdVec::<NodeId, u16>::new();
+/// nodes.push(NodeId(7)).unwrap();
+/// nodes.push(NodeId(300)).unwrap();
+/// assert!(nodes.push(NodeId(1 << 20)).is_err());
+/// assert_eq!(nodes.iter().collect::<Vec<_>>(), [NodeId(7), NodeId(300)]);
+/// ```
+pub struct PackedIdVec<T: IntegerId, N: UnsignedPrimInt = u32> {
+    values: Vec<Narrow<T, N>>,
+}
+impl<T: IntegerId, N: UnsignedPrimInt> PackedIdVec<T, N> {
+    /// Create a new empty vector.
+    #[inline]
+    pub const fn new() -> Self {
+        PackedIdVec { values: Vec::new() }
+    }
+
+    /// Create a new empty vector with space for the specified number of ids.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        PackedIdVec {
+            values: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Append an id to the end of the vector.
+    ///
+    /// # Errors
+    /// Fails if the integer value of the id does not fit in `N`.
+    /// The vector is left unchanged.
+    #[inline]
+    pub fn push(&mut self, id: T) -> Result<(), NarrowError<T, N>> {
+        self.values.push(Narrow::try_new(id)?);
+        Ok(())
+    }
+
+    /// Remove the last id from the vector, returning `None` if it is empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        self.values.pop().map(Narrow::get)
+    }
+
+    /// Get the id at the specified index, returning `None` if out of bounds.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<T> {
+        self.values.get(index).copied().map(Narrow::get)
+    }
+
+    /// Replace the id at the specified index, returning the old value.
+    ///
+    /// # Errors
+    /// Fails if the integer value of the id does not fit in `N`.
+    /// The vector is left unchanged.
+    ///
+    /// # Panics
+    /// If the index is out of bounds.
+    #[inline]
+    pub fn set(&mut self, index: usize, id: T) -> Result<T, NarrowError<T, N>> {
+        let narrow = Narrow::try_new(id)?;
+        Ok(core::mem::replace(&mut self.values[index], narrow).get())
+    }
+
+    /// Check if the vector contains the specified id.
+    #[inline]
+    pub fn contains(&self, id: T) -> bool {
+        Narrow::new(id).is_some_and(|narrow| self.values.contains(&narrow))
+    }
+
+    /// The number of ids in the vector.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Check if the vector is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// The number of ids the vector can hold without reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.values.capacity()
+    }
+
+    /// Shorten the vector to the specified length,
+    /// doing nothing if it is already shorter.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        self.values.truncate(len);
+    }
+
+    /// Remove all ids from the vector.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+
+    /// Shrink the capacity of the vector as much as possible.
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        self.values.shrink_to_fit();
+    }
+
+    /// View the narrowed ids as a slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[Narrow<T, N>] {
+        &self.values
+    }
+
+    /// Iterate over the ids in the vector, converting them back to `T`.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T, N> {
+        Iter {
+            source: self.values.iter(),
+        }
+    }
+}
+impl<T: IntegerId, N: UnsignedPrimInt> Default for PackedIdVec<T, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T: IntegerId, N: UnsignedPrimInt> Clone for PackedIdVec<T, N> {
+    #[inline]
+    fn clone(&self) -> Self {
+        PackedIdVec {
+            values: self.values.clone(),
+        }
+    }
+}
+impl<T: IntegerId, N: UnsignedPrimInt> Eq for PackedIdVec<T, N> {}
+impl<T: IntegerId, N: UnsignedPrimInt> PartialEq for PackedIdVec<T, N> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.values == other.values
+    }
+}
+impl<T: IntegerId, N: UnsignedPrimInt> Hash for PackedIdVec<T, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.values.hash(state);
+    }
+}
+impl<T: IntegerId, N: UnsignedPrimInt> Debug for PackedIdVec<T, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+impl<'a, T: IntegerId, N: UnsignedPrimInt> IntoIterator for &'a PackedIdVec<T, N> {
+    type Item = T;
+    type IntoIter = Iter<'a, T, N>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the ids in a [`PackedIdVec`].
+#[derive(Clone)]
+pub struct Iter<'a, T: IntegerId, N: UnsignedPrimInt> {
+    source: slice::Iter<'a, Narrow<T, N>>,
+}
+impl<T: IntegerId, N: UnsignedPrimInt> Iterator for Iter<'_, T, N> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.source.next().copied().map(Narrow::get)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.source.size_hint()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<T> {
+        self.source.nth(n).copied().map(Narrow::get)
+    }
+}
+impl<T: IntegerId, N: UnsignedPrimInt> DoubleEndedIterator for Iter<'_, T, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        self.source.next_back().copied().map(Narrow::get)
+    }
+}
+impl<T: IntegerId, N: UnsignedPrimInt> ExactSizeIterator for Iter<'_, T, N> {}
+impl<T: IntegerId, N: UnsignedPrimInt> FusedIterator for Iter<'_, T, N> {}
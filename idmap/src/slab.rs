@@ -0,0 +1,242 @@
+//! A slab which allocates ids and stores values in a single structure.
+//!
+//! See [`IdSlab`] for details.
+
+use alloc::collections::BinaryHeap;
+use core::cmp::Reverse;
+use core::fmt::{self, Debug, Formatter};
+use core::ops::{Index, IndexMut};
+
+use intid::utils::OrderByInt;
+use intid::{EquivalentId, IntegerIdCounter};
+
+use crate::direct::map::{Iter, IterMut};
+use crate::DirectIdMap;
+
+/// A [`DirectIdMap`] which allocates the key for each inserted value.
+///
+/// This combines an id allocator with the storage for the values,
+/// so that the two can't drift out of sync.
+/// Removing a value frees its id for reuse,
+/// and the smallest freed id is reused first,
+/// just like the `IdAllocator` in the `intid-allocator` crate.
+///
+/// # Example
+/// ```
+/// use idmap::IdSlab;
+/// intid::define_newtype_counter! {
+///     struct NodeId(u32);
+/// }
+/// let mut nodes = IdSlab::<NodeId, &str>::new();
+/// let a = nodes.insert("a");
+/// let b = nodes.insert("b");
+/// assert_eq!((a, b), (NodeId(0), NodeId(1)));
+/// assert_eq!(nodes.remove(a), Some("a"));
+/// assert_eq!(nodes.insert("c"), NodeId(0));
+/// assert_eq!(nodes[a], "c");
+/// ```
+pub struct IdSlab<K: IntegerIdCounter, V> {
+    map: DirectIdMap<K, V>,
+    /// Removed ids available for reuse, preferring smaller ids
+    free: BinaryHeap<Reverse<OrderByInt<K>>>,
+    /// The next id which has never been used,
+    /// or `None` if they have been exhausted.
+    next_id: Option<K>,
+}
+impl<K: IntegerIdCounter, V> IdSlab<K, V> {
+    /// Create a new slab with no values.
+    #[inline]
+    pub fn new() -> Self {
+        IdSlab {
+            map: DirectIdMap::new(),
+            free: BinaryHeap::new(),
+            next_id: Some(K::START),
+        }
+    }
+
+    /// The number of values in the slab.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Return true if the slab is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Remove all values from the slab, resetting the allocated ids.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.free.clear();
+        self.next_id = Some(K::START);
+    }
+
+    /// Insert a value, returning its newly allocated id.
+    ///
+    /// # Panics
+    /// If all ids of `K` are currently in use.
+    #[inline]
+    #[track_caller]
+    pub fn insert(&mut self, value: V) -> K {
+        let entry = self.vacant_entry();
+        let key = entry.key();
+        entry.insert(value);
+        key
+    }
+
+    /// Reserve the id for the next inserted value,
+    /// without inserting anything yet.
+    ///
+    /// This is useful for values which need to contain their own id.
+    /// Dropping the entry without inserting leaves the slab unchanged.
+    ///
+    /// # Panics
+    /// If all ids of `K` are currently in use.
+    #[track_caller]
+    pub fn vacant_entry(&mut self) -> VacantEntry<'_, K, V> {
+        let key = match self.free.peek() {
+            Some(Reverse(OrderByInt(key))) => *key,
+            None => self
+                .next_id
+                .unwrap_or_else(|| panic!("Ran out of ids for {}", core::any::type_name::<K>())),
+        };
+        VacantEntry { slab: self, key }
+    }
+
+    /// Check if the specified id is present in the slab.
+    #[inline]
+    pub fn contains_key(&self, id: impl EquivalentId<K>) -> bool {
+        self.map.contains_key(id)
+    }
+
+    /// Get the value associated with the specified id, or `None` if missing.
+    #[inline]
+    pub fn get(&self, id: impl EquivalentId<K>) -> Option<&V> {
+        self.map.get(id)
+    }
+
+    /// Get a mutable reference to the value associated with the specified id,
+    /// or `None` if missing.
+    #[inline]
+    pub fn get_mut(&mut self, id: impl EquivalentId<K>) -> Option<&mut V> {
+        self.map.get_mut(id)
+    }
+
+    /// Remove the value associated with the specified id,
+    /// freeing the id for reuse.
+    ///
+    /// Returns `None` and leaves the slab unchanged if the id is missing.
+    #[inline]
+    pub fn remove(&mut self, id: impl EquivalentId<K>) -> Option<V> {
+        let id = id.as_id();
+        let value = self.map.remove(id)?;
+        self.free.push(Reverse(OrderByInt(id)));
+        Some(value)
+    }
+
+    /// Iterate over the ids and values in the slab.
+    ///
+    /// Guaranteed to be sorted by the integer value of the id.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        self.map.iter()
+    }
+
+    /// Mutably iterate over the ids and values in the slab.
+    ///
+    /// Guaranteed to be sorted by the integer value of the id.
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        self.map.iter_mut()
+    }
+
+    /// View the underlying map of ids to values.
+    #[inline]
+    pub fn as_map(&self) -> &DirectIdMap<K, V> {
+        &self.map
+    }
+}
+impl<K: IntegerIdCounter, V> Default for IdSlab<K, V> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<K: IntegerIdCounter, V: Clone> Clone for IdSlab<K, V> {
+    fn clone(&self) -> Self {
+        IdSlab {
+            map: self.map.clone(),
+            free: self.free.clone(),
+            next_id: self.next_id,
+        }
+    }
+}
+impl<K: IntegerIdCounter, V> Index<K> for IdSlab<K, V> {
+    type Output = V;
+
+    #[inline]
+    #[track_caller]
+    fn index(&self, id: K) -> &V {
+        self.get(id).expect("index out of bounds")
+    }
+}
+impl<K: IntegerIdCounter, V> IndexMut<K> for IdSlab<K, V> {
+    #[inline]
+    #[track_caller]
+    fn index_mut(&mut self, id: K) -> &mut V {
+        self.get_mut(id).expect("index out of bounds")
+    }
+}
+impl<K: IntegerIdCounter, V: Debug> Debug for IdSlab<K, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.map, f)
+    }
+}
+impl<'a, K: IntegerIdCounter, V> IntoIterator for &'a IdSlab<K, V> {
+    type Item = (K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+impl<'a, K: IntegerIdCounter, V> IntoIterator for &'a mut IdSlab<K, V> {
+    type Item = (K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// A reserved id in an [`IdSlab`], which has not been inserted yet.
+///
+/// Created by [`IdSlab::vacant_entry`].
+pub struct VacantEntry<'a, K: IntegerIdCounter, V> {
+    slab: &'a mut IdSlab<K, V>,
+    key: K,
+}
+impl<'a, K: IntegerIdCounter, V> VacantEntry<'a, K, V> {
+    /// The id the value will have once inserted.
+    #[inline]
+    pub fn key(&self) -> K {
+        self.key
+    }
+
+    /// Insert the value, returning a reference to it.
+    #[inline]
+    pub fn insert(self, value: V) -> &'a mut V {
+        let slab = self.slab;
+        if slab.free.pop().is_none() {
+            slab.next_id = IntegerIdCounter::checked_add(self.key, primint::one());
+        }
+        let old = slab.map.insert(self.key, value);
+        debug_assert!(old.is_none(), "id {:?} was already used", self.key);
+        &mut slab.map[self.key]
+    }
+}
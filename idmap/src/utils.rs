@@ -2,6 +2,8 @@ use alloc::boxed::Box;
 use core::alloc::Layout;
 use core::mem::MaybeUninit;
 
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
 pub mod bitsets;
 
 macro_rules! box_uninit_alloc_impl {
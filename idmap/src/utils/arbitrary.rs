@@ -0,0 +1,60 @@
+//! Helpers to implement [`arbitrary::Arbitrary`] for the collections.
+
+use arbitrary::Unstructured;
+use intid::IntegerId;
+
+/// The maximum number of integer ids that will be generated for a [`crate::DirectIdMap`],
+/// counting upwards from [`IntegerId::MIN_ID_INT`].
+///
+/// Without this limit, generating a single large id like `u32::MAX`
+/// would require allocating gigabytes of memory.
+pub const MAX_ARBITRARY_IDS: u32 = 4096;
+
+/// Generate an arbitrary id from the first `limit` integers in the range `MIN_ID_INT..=MAX_ID_INT`.
+///
+/// Returns `None` if the type is uninhabited, the integer is not a valid id, or `limit` is zero.
+pub fn arbitrary_id<K: IntegerId>(
+    u: &mut Unstructured<'_>,
+    limit: u32,
+) -> arbitrary::Result<Option<K>> {
+    let (Some(min), Some(max)) = (K::MIN_ID_INT, K::MAX_ID_INT) else {
+        return Ok(None);
+    };
+    // the largest offset from the minimum, which is inclusive
+    let Some(max_offset) = limit.checked_sub(1) else {
+        return Ok(None);
+    };
+    let span = primint::checked_sub(max, min)
+        .and_then(primint::checked_cast::<K::Int, u32>)
+        .map_or(max_offset, |span| span.min(max_offset));
+    let offset = u.int_in_range(0..=span)?;
+    Ok(primint::checked_cast::<u32, K::Int>(offset)
+        .and_then(|offset| primint::checked_add(min, offset))
+        .and_then(K::from_int_checked))
+}
+
+#[cfg(test)]
+mod test {
+    use super::arbitrary_id;
+    use arbitrary::Unstructured;
+    use intid::IntegerId;
+
+    /// The largest id generated from any single byte of input.
+    fn max_generated<K: IntegerId + Ord>(limit: u32) -> Option<K> {
+        (0..=u8::MAX)
+            .filter_map(|byte| arbitrary_id::<K>(&mut Unstructured::new(&[byte]), limit).unwrap())
+            .max()
+    }
+
+    #[test]
+    fn limit_is_exclusive() {
+        // the span of a u16 fits in a u32, so it is clamped to the limit
+        assert_eq!(max_generated::<u16>(4), Some(3));
+        // the span of a u64 does not fit, so the limit is used directly
+        assert_eq!(max_generated::<u64>(4), Some(3));
+        assert_eq!(max_generated::<u8>(1), Some(0));
+        assert_eq!(max_generated::<u8>(0), None);
+        // a span smaller than the limit generates every id
+        assert_eq!(max_generated::<u8>(1000), Some(u8::MAX));
+    }
+}
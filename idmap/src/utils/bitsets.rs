@@ -34,6 +34,6 @@ pub fn retain_word<W: BitsetWord, F: FnMut(u32) -> bool>(
         }
         remaining &= !mask;
     }
-    debug_assert!(removed <= 32);
+    debug_assert!(removed <= primint::bits::<W>());
     (result, removed)
 }
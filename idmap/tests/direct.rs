@@ -213,6 +213,25 @@ fn struct_wrapper() {
     assert_eq!(data.get(ExampleStructWrapper::new(76)), None);
 }
 
+#[test]
+fn option_id_values() {
+    use core::num::NonZeroU16;
+    use intid::utils::OptionId;
+    let one = NonZeroU16::MIN;
+    let three = NonZeroU16::new(3).unwrap();
+    let mut parents: DirectIdMap<ExampleWrapper, OptionId<NonZeroU16>> = direct_idmap! {
+        ExampleWrapper(1) => OptionId::NONE,
+        ExampleWrapper(2) => OptionId::some(one),
+    };
+    assert_eq!(parents[ExampleWrapper(2)].get(), Some(one));
+    assert_eq!(parents[ExampleWrapper(1)].get(), None);
+    parents[ExampleWrapper(1)].set(Some(three));
+    assert_eq!(parents[ExampleWrapper(1)].take(), Some(three));
+    assert!(parents
+        .iter()
+        .all(|(_, parent)| parent.get() != Some(three)));
+}
+
 #[derive(IntegerId, Copy, Clone, Debug, Eq, PartialEq)]
 struct ExampleWrapper(u16);
 #[derive(IntegerId, Copy, Clone, Debug, Eq, PartialEq)]
@@ -187,3 +187,27 @@ fn serde() {
     const EXPECTED_TOKENS: &[Token] = state_tokens!(3, Arizona, California, NewYork);
     assert_tokens(&important_states(), EXPECTED_TOKENS);
 }
+
+#[derive(IntegerId, intid_derive::EnumId, Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u8)]
+enum Wide {
+    First,
+    Second,
+    Last = 100,
+}
+
+#[test]
+fn enum_set_len() {
+    let mut set = idmap::EnumSet::<Wide>::new();
+    assert!(set.insert(Wide::First));
+    assert!(set.insert(Wide::Last));
+    assert!(!set.insert(Wide::Last));
+    assert_eq!(set.len(), 2);
+    assert!(set.remove(Wide::First));
+    assert!(!set.remove(Wide::Second));
+    assert_eq!(set.len(), 1);
+    set.insert(Wide::Second);
+    set.retain(|x| x != Wide::Last);
+    assert_eq!(set.iter().collect_vec(), vec![Wide::Second]);
+    assert_eq!(set.len(), 1);
+}
@@ -0,0 +1,116 @@
+#![allow(missing_docs)]
+use idmap::GenArena;
+use intid::utils::Generational;
+
+intid::define_newtype_counter! {
+    struct NodeId(u32);
+}
+
+#[test]
+fn stale_keys_are_rejected() {
+    let mut arena = GenArena::<NodeId, String>::new();
+    let a = arena.insert("a".into());
+    let b = arena.insert("b".into());
+    assert_eq!(arena.len(), 2);
+    assert_eq!(arena.remove(a).as_deref(), Some("a"));
+    assert_eq!(arena.remove(a), None);
+    let c = arena.insert("c".into());
+    assert_eq!(c.index(), a.index());
+    assert_ne!(c.generation(), a.generation());
+    assert_eq!(arena.get(a), None);
+    assert!(!arena.contains_key(a));
+    assert_eq!(arena.get_mut(a), None);
+    arena[c].push('!');
+    assert_eq!(arena[c], "c!");
+    assert_eq!(arena[b], "b");
+    assert_eq!(arena.len(), 2);
+    // a forged key from a future generation is also rejected
+    assert_eq!(arena.get(Generational::new(c.index(), 7)), None);
+}
+
+#[test]
+fn iterate() {
+    let mut arena = GenArena::<NodeId, u32>::new();
+    let keys = (0..4).map(|i| arena.insert(i * 10)).collect::<Vec<_>>();
+    arena.remove(keys[1]);
+    let iter = arena.iter();
+    assert_eq!(iter.len(), 3);
+    assert_eq!(
+        iter.map(|(key, &value)| (key.index(), value))
+            .collect::<Vec<_>>(),
+        [(NodeId(0), 0), (NodeId(2), 20), (NodeId(3), 30)]
+    );
+    assert_eq!(
+        format!("{:?}", arena.iter().next().unwrap()),
+        "(Generational { index: NodeId(0), generation: 0 }, 0)"
+    );
+}
+
+#[test]
+fn exhausted_generation_retires_index() {
+    let mut arena = GenArena::<NodeId, (), u8>::new();
+    let mut key = arena.insert(());
+    for _ in 0..u8::MAX {
+        arena.remove(key).unwrap();
+        key = arena.insert(());
+        assert_eq!(key.index(), NodeId(0));
+    }
+    assert_eq!(key.generation(), u8::MAX);
+    arena.remove(key).unwrap();
+    assert_eq!(arena.insert(()).index(), NodeId(1));
+}
+
+/// An id whose first value is far from zero.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+struct HighId(u64);
+impl intid::IntegerId for HighId {
+    type Int = u64;
+    const MIN_ID: Option<Self> = Some(HighId(HighId::FIRST));
+    const MAX_ID: Option<Self> = Some(HighId(u64::MAX));
+    const MIN_ID_INT: Option<u64> = Some(HighId::FIRST);
+    const MAX_ID_INT: Option<u64> = Some(u64::MAX);
+
+    fn from_int_checked(id: u64) -> Option<Self> {
+        (id >= HighId::FIRST).then_some(HighId(id))
+    }
+
+    fn to_int(self) -> u64 {
+        self.0
+    }
+}
+impl intid::IntegerIdContiguous for HighId {}
+impl intid::IntegerIdCounter for HighId {
+    const START: Self = HighId(HighId::FIRST);
+    const START_INT: u64 = HighId::FIRST;
+}
+impl HighId {
+    const FIRST: u64 = 1 << 40;
+}
+
+#[test]
+fn large_start() {
+    // the table of entries starts from the first index, not from zero
+    let mut arena = GenArena::<HighId, &str>::new();
+    let a = arena.insert("a");
+    let b = arena.insert("b");
+    assert_eq!(
+        (a.index(), b.index()),
+        (HighId(HighId::FIRST), HighId(HighId::FIRST + 1))
+    );
+    assert_eq!(arena[b], "b");
+    assert_eq!(arena.remove(a), Some("a"));
+    let c = arena.insert("c");
+    assert_eq!((c.index(), c.generation()), (a.index(), 1));
+    assert_eq!(
+        arena
+            .iter()
+            .map(|(key, &value)| (key.index(), value))
+            .collect::<Vec<_>>(),
+        [
+            (HighId(HighId::FIRST), "c"),
+            (HighId(HighId::FIRST + 1), "b")
+        ]
+    );
+    // an index before the start is never present
+    assert_eq!(arena.get(Generational::new(HighId(0), 0)), None);
+}
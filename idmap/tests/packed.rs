@@ -0,0 +1,83 @@
+#![allow(missing_docs)]
+use idmap::{DirectIdMap, PackedIdVec};
+use intid::utils::Narrow;
+
+intid::define_newtype_counter! {
+    struct NodeId(u64);
+}
+
+#[test]
+fn push_and_iterate() {
+    let mut nodes = PackedIdVec::<NodeId, u16>::with_capacity(3);
+    nodes.push(NodeId(1)).unwrap();
+    nodes.push(NodeId(65_535)).unwrap();
+    let err = nodes.push(NodeId(65_536)).unwrap_err();
+    assert_eq!(err.id(), NodeId(65_536));
+    assert_eq!(nodes.len(), 2);
+    assert_eq!(
+        nodes.iter().collect::<Vec<_>>(),
+        [NodeId(1), NodeId(65_535)]
+    );
+    assert_eq!(
+        nodes.iter().rev().collect::<Vec<_>>(),
+        [NodeId(65_535), NodeId(1)]
+    );
+    assert_eq!(nodes.iter().len(), 2);
+    assert_eq!(format!("{nodes:?}"), "[NodeId(1), NodeId(65535)]");
+    assert_eq!(core::mem::size_of_val(nodes.as_slice()), 4);
+}
+
+#[test]
+fn modify() {
+    let mut nodes = PackedIdVec::<NodeId, u8>::new();
+    assert_eq!(nodes.pop(), None);
+    for i in 0..10 {
+        nodes.push(NodeId(i)).unwrap();
+    }
+    assert!(nodes.contains(NodeId(3)));
+    assert!(!nodes.contains(NodeId(1000)));
+    assert_eq!(nodes.set(3, NodeId(30)), Ok(NodeId(3)));
+    assert!(nodes.set(3, NodeId(300)).is_err());
+    assert_eq!(nodes.get(3), Some(NodeId(30)));
+    assert_eq!(nodes.get(10), None);
+    nodes.truncate(4);
+    assert_eq!(nodes.pop(), Some(NodeId(30)));
+    assert_eq!(nodes.clone(), nodes);
+    nodes.clear();
+    assert!(nodes.is_empty());
+}
+
+#[test]
+fn narrow_keys() {
+    let mut map = DirectIdMap::<Narrow<NodeId, u16>, &str>::new();
+    map.insert(Narrow::new(NodeId(3)).unwrap(), "three");
+    assert_eq!(map[Narrow::new(NodeId(3)).unwrap()], "three");
+    assert_eq!(
+        map.iter().map(|(key, _)| key.get()).collect::<Vec<_>>(),
+        [NodeId(3)]
+    );
+}
+
+#[test]
+fn packed_pair_keys() {
+    use intid::utils::Packed;
+    intid::define_newtype_counter! {
+        struct LocalIdx(u8);
+    }
+    type Key = Packed<NodeId, LocalIdx, 8, u32>;
+    let key = |a, b| Key::new(NodeId(a), LocalIdx(b)).unwrap();
+    let mut map = DirectIdMap::new();
+    map.insert(key(2, 0), "c");
+    map.insert(key(0, 7), "a");
+    map.insert(key(1, 255), "b");
+    assert_eq!(
+        map.iter()
+            .map(|(key, &value)| (key.first(), key.second(), value))
+            .collect::<Vec<_>>(),
+        [
+            (NodeId(0), LocalIdx(7), "a"),
+            (NodeId(1), LocalIdx(255), "b"),
+            (NodeId(2), LocalIdx(0), "c"),
+        ]
+    );
+}
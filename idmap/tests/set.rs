@@ -101,6 +101,20 @@ fn retain() {
     check_missing(TINY_STATES, &set);
 }
 
+#[test]
+fn retain_wide_words() {
+    // ids past the first 32 bits of each word must map back to the right key
+    let mut set = (0u32..200).collect::<IdSet<u32>>();
+    let mut seen = Vec::new();
+    set.retain(|id| {
+        seen.push(id);
+        id % 3 == 0
+    });
+    assert_eq!(seen, (0..200).collect_vec());
+    assert_eq!(set.len(), 67);
+    assert_eq!(set.iter().collect_vec(), (0..200).step_by(3).collect_vec());
+}
+
 /// List the biggest cities in each state except for `NewMexico` and `NorthDakota`,
 /// intentionally excluding them to provide a better test case.
 fn important_states() -> IdSet<KnownState> {
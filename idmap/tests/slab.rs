@@ -0,0 +1,76 @@
+#![allow(missing_docs)]
+use idmap::IdSlab;
+
+intid::define_newtype_counter! {
+    struct NodeId(u32);
+}
+
+#[test]
+fn reuses_lowest_ids() {
+    let mut slab = IdSlab::<NodeId, &str>::new();
+    let ids = ["a", "b", "c", "d"].map(|value| slab.insert(value));
+    assert_eq!(ids, [NodeId(0), NodeId(1), NodeId(2), NodeId(3)]);
+    assert_eq!(slab.remove(NodeId(2)), Some("c"));
+    assert_eq!(slab.remove(NodeId(0)), Some("a"));
+    // removing twice must not free the id twice
+    assert_eq!(slab.remove(NodeId(0)), None);
+    assert_eq!(slab.len(), 2);
+    assert_eq!(slab.insert("e"), NodeId(0));
+    assert_eq!(slab.insert("f"), NodeId(2));
+    assert_eq!(slab.insert("g"), NodeId(4));
+    assert_eq!(
+        slab.iter().collect::<Vec<_>>(),
+        [
+            (NodeId(0), &"e"),
+            (NodeId(1), &"b"),
+            (NodeId(2), &"f"),
+            (NodeId(3), &"d"),
+            (NodeId(4), &"g"),
+        ]
+    );
+}
+
+#[test]
+fn vacant_entry() {
+    #[derive(Debug, PartialEq)]
+    struct Node {
+        id: NodeId,
+        parent: Option<NodeId>,
+    }
+    let mut slab = IdSlab::<NodeId, Node>::new();
+    let entry = slab.vacant_entry();
+    let root = entry.key();
+    entry.insert(Node {
+        id: root,
+        parent: None,
+    });
+    // dropping an entry without inserting doesn't reserve anything
+    assert_eq!(slab.vacant_entry().key(), NodeId(1));
+    let entry = slab.vacant_entry();
+    let child = entry.key();
+    let node = entry.insert(Node {
+        id: child,
+        parent: Some(root),
+    });
+    assert_eq!(node.id, NodeId(1));
+    assert_eq!(slab[child].parent, Some(root));
+    slab.remove(root);
+    assert_eq!(slab.vacant_entry().key(), root);
+    slab.get_mut(child).unwrap().parent = None;
+    assert_eq!(
+        slab.get(child),
+        Some(&Node {
+            id: child,
+            parent: None
+        })
+    );
+    slab.clear();
+    assert!(slab.is_empty());
+    assert_eq!(
+        slab.insert(Node {
+            id: root,
+            parent: None
+        }),
+        root
+    );
+}
@@ -0,0 +1,56 @@
+//! Benchmarks allocation from [`UniqueIdAllocatorAtomic`] under contention.
+//!
+//! Compares the `fetch_add` fast path against the CAS loop,
+//! which is used once the counter nears the maximum id.
+#![allow(missing_docs)]
+use core::hint::black_box;
+use core::time::Duration;
+use std::time::Instant;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use intid_allocator::UniqueIdAllocatorAtomic;
+
+const IDS_PER_THREAD: u64 = 10_000;
+
+fn contended(threads: u64, start: u64) -> Duration {
+    let alloc = UniqueIdAllocatorAtomic::<u64>::with_start(start);
+    let begin = Instant::now();
+    std::thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                for _ in 0..IDS_PER_THREAD {
+                    black_box(alloc.alloc());
+                }
+            });
+        }
+    });
+    begin.elapsed()
+}
+
+fn bench_contention(c: &mut Criterion) {
+    let mut group = c.benchmark_group("unique_atomic_alloc");
+    for threads in [1, 2, 4, 8] {
+        group.throughput(Throughput::Elements(threads * IDS_PER_THREAD));
+        group.bench_with_input(
+            BenchmarkId::new("fetch_add", threads),
+            &threads,
+            |b, &threads| b.iter_custom(|iters| (0..iters).map(|_| contended(threads, 0)).sum()),
+        );
+        // counter values near the maximum always use the CAS loop
+        group.bench_with_input(
+            BenchmarkId::new("cas_loop", threads),
+            &threads,
+            |b, &threads| {
+                b.iter_custom(|iters| {
+                    (0..iters)
+                        .map(|_| contended(threads, u64::MAX - (1 << 40)))
+                        .sum()
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_contention);
+criterion_main!(benches);
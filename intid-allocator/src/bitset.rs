@@ -0,0 +1,250 @@
+//! A growable bitset used to track freed ids.
+
+use alloc::vec::Vec;
+
+/// The number of bits in each word of the bitset.
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A bitset of freed positions,
+/// optimized for finding the smallest set bit.
+#[derive(Clone, Default)]
+pub(crate) struct FreeBitset {
+    words: Vec<u64>,
+    /// The index of the first word which may have a set bit.
+    first_word: usize,
+    /// The number of set bits.
+    len: usize,
+}
+impl FreeBitset {
+    #[inline]
+    pub const fn new() -> Self {
+        FreeBitset {
+            words: Vec::new(),
+            first_word: 0,
+            len: 0,
+        }
+    }
+
+    #[inline]
+    pub fn contains(&self, pos: usize) -> bool {
+        self.words
+            .get(pos / WORD_BITS)
+            .is_some_and(|word| word & (1u64 << (pos % WORD_BITS)) != 0)
+    }
+
+    /// Set the specified bit, returning `false` if it was already set.
+    pub fn insert(&mut self, pos: usize) -> bool {
+        let (word, mask) = (pos / WORD_BITS, 1u64 << (pos % WORD_BITS));
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        if self.words[word] & mask != 0 {
+            return false;
+        }
+        self.words[word] |= mask;
+        self.first_word = self.first_word.min(word);
+        self.len += 1;
+        true
+    }
+
+    /// Clear and return the smallest set bit.
+    pub fn pop_first(&mut self) -> Option<usize> {
+        if self.len == 0 {
+            // avoid rescanning empty words
+            self.first_word = self.words.len();
+            return None;
+        }
+        let (offset, word) = self.words[self.first_word..]
+            .iter_mut()
+            .enumerate()
+            .find(|(_, word)| **word != 0)?;
+        let bit = word.trailing_zeros() as usize;
+        *word &= !(1u64 << bit);
+        self.first_word += offset;
+        self.len -= 1;
+        Some(self.first_word * WORD_BITS + bit)
+    }
+
+    /// The number of set bits.
+    #[inline]
+    #[cfg(feature = "serde")]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Iterate over the set bits in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words
+            .iter()
+            .enumerate()
+            .skip(self.first_word)
+            .flat_map(|(index, &word)| {
+                let mut rest = word;
+                core::iter::from_fn(move || {
+                    if rest == 0 {
+                        return None;
+                    }
+                    let bit = rest.trailing_zeros() as usize;
+                    // clear the lowest set bit
+                    rest &= rest - 1;
+                    Some(index * WORD_BITS + bit)
+                })
+            })
+    }
+
+    /// Find the smallest position starting a run of `count` set bits.
+    pub fn find_run(&self, count: usize) -> Option<usize> {
+        debug_assert!(count > 0);
+        let mut run_start = 0;
+        let mut run_len = 0;
+        for (index, &word) in self.words.iter().enumerate().skip(self.first_word) {
+            let mut bit = 0;
+            while bit < WORD_BITS {
+                let ones = (word >> bit).trailing_ones() as usize;
+                if ones > 0 {
+                    if run_len == 0 {
+                        run_start = index * WORD_BITS + bit;
+                    }
+                    run_len += ones;
+                    if run_len >= count {
+                        return Some(run_start);
+                    }
+                    bit += ones;
+                    if bit == WORD_BITS {
+                        // the run may continue into the next word
+                        break;
+                    }
+                }
+                run_len = 0;
+                // the shifted-in bits are zero, so this never overshoots the word
+                bit += (word >> bit).trailing_zeros() as usize;
+            }
+        }
+        None
+    }
+
+    /// The number of consecutive set bits immediately before `end`.
+    pub fn trailing_run(&self, end: usize) -> usize {
+        let mut run = 0;
+        let mut pos = end.min(self.words.len() * WORD_BITS);
+        if pos < end {
+            // bits past the last word are clear
+            return 0;
+        }
+        while pos > 0 {
+            let index = (pos - 1) / WORD_BITS;
+            let bits = pos - index * WORD_BITS;
+            // move the bits before `pos` to the top of the word
+            let ones = (self.words[index] << (WORD_BITS - bits)).leading_ones() as usize;
+            run += ones;
+            if ones < bits {
+                break;
+            }
+            pos -= bits;
+        }
+        run
+    }
+
+    /// Find the first set bit in the specified range.
+    pub fn first_in_range(&self, start: usize, count: usize) -> Option<usize> {
+        word_masks(start, start + count).find_map(|(index, mask)| {
+            let bits = self.words.get(index)? & mask;
+            (bits != 0).then(|| index * WORD_BITS + bits.trailing_zeros() as usize)
+        })
+    }
+
+    /// Set all bits in the specified range, which must all be clear.
+    pub fn insert_range(&mut self, start: usize, count: usize) {
+        if count == 0 {
+            return;
+        }
+        let end = start + count;
+        let words = end / WORD_BITS + usize::from(end % WORD_BITS != 0);
+        if words > self.words.len() {
+            self.words.resize(words, 0);
+        }
+        for (index, mask) in word_masks(start, end) {
+            let word = &mut self.words[index];
+            debug_assert_eq!(*word & mask, 0);
+            *word |= mask;
+        }
+        self.first_word = self.first_word.min(start / WORD_BITS);
+        self.len += count;
+    }
+
+    /// Clear all bits in the specified range, which must all be set.
+    pub fn remove_range(&mut self, start: usize, count: usize) {
+        for (index, mask) in word_masks(start, start + count) {
+            let word = &mut self.words[index];
+            debug_assert_eq!(*word & mask, mask);
+            *word &= !mask;
+        }
+        self.len -= count;
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.words.clear();
+        self.first_word = 0;
+        self.len = 0;
+    }
+}
+
+/// Split the positions from `start` up to (but excluding) `end` into words,
+/// yielding the index of each word and the mask of the positions within it.
+fn word_masks(start: usize, end: usize) -> impl Iterator<Item = (usize, u64)> {
+    let first = start / WORD_BITS;
+    let last = if start < end {
+        (end - 1) / WORD_BITS + 1
+    } else {
+        first
+    };
+    (first..last).map(move |index| {
+        let base = index * WORD_BITS;
+        let low = start.saturating_sub(base);
+        let high = core::cmp::min(end - base, WORD_BITS);
+        // the range is never empty, so `high > low`
+        let mask = (u64::MAX >> (WORD_BITS - (high - low))) << low;
+        (index, mask)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::FreeBitset;
+    use alloc::vec::Vec;
+
+    /// Check the word-at-a-time operations against a bit-by-bit reference,
+    /// for runs crossing word boundaries.
+    #[test]
+    fn ranges_across_words() {
+        let mut bits = FreeBitset::new();
+        bits.insert_range(60, 70);
+        bits.insert_range(200, 64);
+        bits.insert(3);
+        let set = (0..300)
+            .filter(|&pos| bits.contains(pos))
+            .collect::<Vec<_>>();
+        assert_eq!(bits.iter().collect::<Vec<_>>(), set);
+        assert_eq!(set.len(), 135);
+        assert_eq!(bits.find_run(1), Some(3));
+        assert_eq!(bits.find_run(2), Some(60));
+        assert_eq!(bits.find_run(70), Some(60));
+        assert_eq!(bits.find_run(71), None);
+        assert_eq!(bits.find_run(64), Some(60));
+        assert_eq!(bits.trailing_run(130), 70);
+        assert_eq!(bits.trailing_run(128), 68);
+        assert_eq!(bits.trailing_run(264), 64);
+        assert_eq!(bits.trailing_run(265), 0);
+        assert_eq!(bits.trailing_run(1000), 0);
+        assert_eq!(bits.first_in_range(4, 56), None);
+        assert_eq!(bits.first_in_range(4, 57), Some(60));
+        assert_eq!(bits.first_in_range(130, 1000), Some(200));
+        bits.remove_range(62, 66);
+        assert_eq!(bits.find_run(2), Some(60));
+        assert_eq!(bits.find_run(3), Some(200));
+        assert_eq!(bits.iter().take(4).collect::<Vec<_>>(), [3, 60, 61, 128]);
+        bits.remove_range(200, 64);
+        assert_eq!(bits.trailing_run(264), 0);
+    }
+}
@@ -0,0 +1,408 @@
+use alloc::vec::Vec;
+use core::fmt::{self, Debug, Formatter};
+use core::sync::atomic::Ordering;
+
+use crate::sync::{AtomicPrimInt, AtomicUsize, Mutex, MutexGuard, PoisonError};
+use crate::{AllocatorStats, IdExhaustedError, UniqueIdAllocatorAtomic};
+use intid::{primint, IntegerIdCounter};
+
+/// The default number of ids reserved by each [`LocalIdCache`] at a time.
+const DEFAULT_CHUNK_SIZE: usize = 64;
+
+/// Allocates ids from multiple threads
+/// while avoiding contention on a single shared counter.
+///
+/// Each thread allocates through its own [`LocalIdCache`], created by [`Self::cache`].
+/// A cache reserves a chunk of consecutive ids from the shared counter at a time,
+/// then allocates from that chunk without touching any shared state.
+/// Ids freed through a cache are kept by that cache for reuse.
+///
+/// Calling [`LocalIdCache::flush`] or dropping the cache
+/// gives its unused ids back to the shared allocator,
+/// where they are reused by other caches before reserving new chunks.
+///
+/// Ids are unique across all caches.
+/// However, ids reserved by one cache can not be allocated by another until flushed,
+/// so allocation may fail with [`IdExhaustedError`] while other caches still hold unused ids.
+///
+/// To avoid touching shared state on every allocation,
+/// each cache only reports its allocations and frees to [`Self::stats`]
+/// when it reserves a new chunk or is flushed.
+///
+/// # Example
+/// ```
+/// use intid_allocator::ThreadCachedIdAllocator;
+/// let alloc = ThreadCachedIdAllocator::<u32>::with_chunk_size(16);
+/// std::thread::scope(|scope| {
+///     for _ in 0..4 {
+///         scope.spawn(|| {
+///             let mut cache = alloc.cache();
+///             let id = cache.alloc();
+///             cache.free(id);
+///             assert_eq!(cache.alloc(), id);
+///         });
+///     }
+/// });
+/// ```
+pub struct ThreadCachedIdAllocator<T: IntegerIdCounter>
+where
+    T::Int: AtomicPrimInt,
+{
+    next_id: UniqueIdAllocatorAtomic<T>,
+    chunk_size: usize,
+    /// The number of ranges in `returned`.
+    ///
+    /// Checked before acquiring the lock, to avoid locking when nothing was returned.
+    returned_count: AtomicUsize,
+    /// Inclusive ranges of ids which were flushed by a cache without being allocated.
+    returned: Mutex<Vec<(T, T)>>,
+    /// The number of unused ids held by caches, as last reported by each cache.
+    parked: AtomicUsize,
+    /// The total number of ids allocated through caches, as last reported by each cache.
+    allocations: AtomicUsize,
+    /// The total number of ids freed through caches, as last reported by each cache.
+    frees: AtomicUsize,
+    /// The number of failed allocations, for statistics.
+    exhaustions: AtomicUsize,
+}
+impl<T: IntegerIdCounter> Default for ThreadCachedIdAllocator<T>
+where
+    T::Int: AtomicPrimInt,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T: IntegerIdCounter> ThreadCachedIdAllocator<T>
+where
+    T::Int: AtomicPrimInt,
+{
+    /// Create a new allocator, with ids starting at [`T::START`] (usually zero).
+    ///
+    /// Each cache reserves 64 ids at a time.
+    ///
+    /// [`T::START`]: IntegerIdCounter::START
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_chunk_size(DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Create a new allocator, where each cache reserves `chunk_size` ids at a time.
+    ///
+    /// Larger chunks reduce contention on the shared counter,
+    /// but more ids may be left unused by each cache.
+    ///
+    /// # Panics
+    /// If the chunk size is zero.
+    #[inline]
+    pub fn with_chunk_size(chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk size must be nonzero");
+        ThreadCachedIdAllocator {
+            next_id: UniqueIdAllocatorAtomic::new(),
+            chunk_size,
+            returned_count: AtomicUsize::new(0),
+            returned: Mutex::new(Vec::new()),
+            parked: AtomicUsize::new(0),
+            allocations: AtomicUsize::new(0),
+            frees: AtomicUsize::new(0),
+            exhaustions: AtomicUsize::new(0),
+        }
+    }
+
+    /// The number of ids each cache reserves at a time.
+    #[inline]
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Create a new cache for allocating ids.
+    ///
+    /// This should usually be called once per thread.
+    /// Creating a cache does not reserve any ids until the first allocation.
+    #[inline]
+    pub fn cache(&self) -> LocalIdCache<'_, T> {
+        LocalIdCache {
+            shared: self,
+            chunk: None,
+            freed: Vec::new(),
+            counts: CacheCounts::default(),
+            reported: CacheCounts::default(),
+            reserved: 0,
+            exhaustions: 0,
+        }
+    }
+
+    /// Statistics about the allocator, combining the reports of every cache.
+    ///
+    /// Ids held by a cache or given back by a flush count as free.
+    /// A cache reports its allocations and frees when it reserves a new chunk or is flushed,
+    /// so the result lags behind the caches and may be out of date
+    /// if other threads are concurrently allocating or freeing ids.
+    pub fn stats(&self) -> AllocatorStats {
+        let reserved = self.next_id.stats().high_water_mark;
+        let returned = self
+            .lock_returned()
+            .iter()
+            .map(|&(first, last)| range_len(first, last))
+            .fold(0, usize::saturating_add);
+        let free = returned.saturating_add(self.parked.load(Ordering::Relaxed));
+        AllocatorStats {
+            live: reserved.saturating_sub(free),
+            free,
+            high_water_mark: reserved,
+            allocations: self.allocations.load(Ordering::Relaxed),
+            frees: self.frees.load(Ordering::Relaxed),
+            exhaustions: self.exhaustions.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Reserve a range of ids for a cache,
+    /// preferring ids returned by other caches.
+    fn reserve(&self) -> Result<(T, T), IdExhaustedError<T>> {
+        if self.returned_count.load(Ordering::Relaxed) > 0 {
+            let mut returned = self.lock_returned();
+            if let Some(range) = returned.pop() {
+                self.returned_count.fetch_sub(1, Ordering::Relaxed);
+                return Ok(range);
+            }
+        }
+        match self.next_id.try_alloc_range(self.chunk_size) {
+            Ok(range) => Ok(range),
+            // fewer than a full chunk remains
+            Err(_) => self.next_id.try_alloc().map(|id| (id, id)),
+        }
+    }
+
+    fn give_back(&self, ranges: impl IntoIterator<Item = (T, T)>) {
+        let mut returned = self.lock_returned();
+        let old_len = returned.len();
+        returned.extend(ranges);
+        // updated while holding the lock, so the count never exceeds the length
+        self.returned_count
+            .fetch_add(returned.len() - old_len, Ordering::Relaxed);
+    }
+
+    #[inline]
+    fn lock_returned(&self) -> MutexGuard<'_, Vec<(T, T)>> {
+        // the list is always left in a consistent state,
+        // so there is no need to propagate poisoning
+        self.returned.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+impl<T: IntegerIdCounter> Debug for ThreadCachedIdAllocator<T>
+where
+    T::Int: AtomicPrimInt,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThreadCachedIdAllocator")
+            .field("next_id", &self.next_id)
+            .field("chunk_size", &self.chunk_size)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A cache of ids reserved from a [`ThreadCachedIdAllocator`],
+/// which allocates without touching any shared state.
+///
+/// Dropping the cache gives its unused ids back to the shared allocator,
+/// just like calling [`Self::flush`].
+pub struct LocalIdCache<'a, T: IntegerIdCounter>
+where
+    T::Int: AtomicPrimInt,
+{
+    shared: &'a ThreadCachedIdAllocator<T>,
+    /// The remaining ids of the current chunk, as an inclusive range.
+    chunk: Option<(T, T)>,
+    /// Ids freed through this cache, which are reused first.
+    freed: Vec<T>,
+    /// The totals of this cache, for statistics.
+    counts: CacheCounts,
+    /// The totals which were last reported to the shared allocator.
+    reported: CacheCounts,
+    /// The total number of ids reserved from the shared allocator, for statistics.
+    reserved: usize,
+    /// The number of failed allocations, for statistics.
+    exhaustions: usize,
+}
+/// The statistics which a [`LocalIdCache`] reports to the shared allocator.
+#[derive(Copy, Clone, Debug, Default)]
+struct CacheCounts {
+    allocations: usize,
+    frees: usize,
+    /// The number of unused ids held by the cache.
+    parked: usize,
+}
+impl<T: IntegerIdCounter> LocalIdCache<'_, T>
+where
+    T::Int: AtomicPrimInt,
+{
+    /// Allocate an id, reusing ids freed through this cache where possible.
+    ///
+    /// # Errors
+    /// Fails if the shared allocator has run out of ids,
+    /// and this cache has no unused ids remaining.
+    pub fn try_alloc(&mut self) -> Result<T, IdExhaustedError<T>> {
+        let id = if let Some(id) = self.freed.pop() {
+            id
+        } else if let Some((next, last)) = self.chunk {
+            self.take_from_chunk(next, last)
+        } else {
+            let (next, last) = match self.shared.reserve() {
+                Ok(range) => range,
+                Err(e) => {
+                    self.exhaustions = self.exhaustions.wrapping_add(1);
+                    self.shared.exhaustions.fetch_add(1, Ordering::Relaxed);
+                    return Err(e);
+                }
+            };
+            self.reserved = self.reserved.wrapping_add(range_len(next, last));
+            let id = self.take_from_chunk(next, last);
+            self.counts.allocations = self.counts.allocations.wrapping_add(1);
+            // already touched shared state to reserve the chunk
+            self.report();
+            return Ok(id);
+        };
+        self.counts.allocations = self.counts.allocations.wrapping_add(1);
+        Ok(id)
+    }
+
+    /// Allocate the first id of the chunk, keeping the rest.
+    #[inline]
+    fn take_from_chunk(&mut self, next: T, last: T) -> T {
+        self.chunk = if next.to_int() < last.to_int() {
+            IntegerIdCounter::checked_add(next, primint::one()).map(|after| (after, last))
+        } else {
+            None
+        };
+        next
+    }
+
+    /// Allocate an id, reusing ids freed through this cache where possible.
+    ///
+    /// # Panics
+    /// If the shared allocator has run out of ids.
+    /// See [`Self::try_alloc`] for a version that returns an error instead.
+    #[track_caller]
+    #[inline]
+    #[must_use]
+    pub fn alloc(&mut self) -> T {
+        match self.try_alloc() {
+            Ok(id) => id,
+            Err(e) => e.panic(),
+        }
+    }
+
+    /// Free the specified id, keeping it in this cache for reuse.
+    ///
+    /// The id may have been allocated by any cache of the same allocator.
+    /// Freeing an id that is still in use will cause it to be allocated twice.
+    #[inline]
+    pub fn free(&mut self, id: T) {
+        self.freed.push(id);
+        self.counts.frees = self.counts.frees.wrapping_add(1);
+    }
+
+    /// The number of ids this cache can allocate
+    /// without reserving more from the shared allocator.
+    pub fn cached_count(&self) -> usize {
+        let chunk_len = self.chunk.map_or(0, |(next, last)| range_len(next, last));
+        self.freed.len().saturating_add(chunk_len)
+    }
+
+    /// Statistics about the ids allocated and freed through this cache.
+    ///
+    /// The ids held by this cache count as free,
+    /// and the high-water mark is the total number of ids it has reserved.
+    /// Since ids can be freed through a different cache than they were allocated from,
+    /// the live count saturates at zero.
+    pub fn stats(&self) -> AllocatorStats {
+        AllocatorStats {
+            live: self.counts.allocations.saturating_sub(self.counts.frees),
+            free: self.cached_count(),
+            high_water_mark: self.reserved,
+            allocations: self.counts.allocations,
+            frees: self.counts.frees,
+            exhaustions: self.exhaustions,
+        }
+    }
+
+    /// Add the changes to the statistics since the last report to the shared allocator.
+    fn report(&mut self) {
+        self.counts.parked = self.cached_count();
+        let shared = self.shared;
+        shared.allocations.fetch_add(
+            self.counts
+                .allocations
+                .wrapping_sub(self.reported.allocations),
+            Ordering::Relaxed,
+        );
+        shared.frees.fetch_add(
+            self.counts.frees.wrapping_sub(self.reported.frees),
+            Ordering::Relaxed,
+        );
+        if self.counts.parked >= self.reported.parked {
+            shared
+                .parked
+                .fetch_add(self.counts.parked - self.reported.parked, Ordering::Relaxed);
+        } else {
+            shared
+                .parked
+                .fetch_sub(self.reported.parked - self.counts.parked, Ordering::Relaxed);
+        }
+        self.reported = self.counts;
+    }
+
+    /// Give all unused ids back to the shared allocator,
+    /// so they can be reused by other caches.
+    ///
+    /// Adjacent freed ids are merged into ranges.
+    /// This also reports the statistics of this cache to [`ThreadCachedIdAllocator::stats`].
+    pub fn flush(&mut self) {
+        if self.chunk.is_none() && self.freed.is_empty() {
+            self.report();
+            return;
+        }
+        self.freed.sort_unstable_by_key(|id| id.to_int());
+        let mut ranges = Vec::new();
+        for id in self.freed.drain(..) {
+            match ranges.last_mut() {
+                Some((_, last))
+                    if IntegerIdCounter::checked_add(*last, primint::one())
+                        .is_some_and(|after: T| after.to_int() == id.to_int()) =>
+                {
+                    *last = id;
+                }
+                _ => ranges.push((id, id)),
+            }
+        }
+        ranges.extend(self.chunk.take());
+        self.shared.give_back(ranges);
+        self.report();
+    }
+}
+impl<T: IntegerIdCounter> Drop for LocalIdCache<'_, T>
+where
+    T::Int: AtomicPrimInt,
+{
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+impl<T: IntegerIdCounter> Debug for LocalIdCache<'_, T>
+where
+    T::Int: AtomicPrimInt,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalIdCache")
+            .field("chunk", &self.chunk)
+            .field("freed", &self.freed)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The number of ids in an inclusive range, saturating at [`usize::MAX`].
+fn range_len<T: IntegerIdCounter>(first: T, last: T) -> usize {
+    primint::checked_sub(last.to_int(), first.to_int())
+        .and_then(primint::to_usize_checked)
+        .map_or(usize::MAX, |len| len.saturating_add(1))
+}
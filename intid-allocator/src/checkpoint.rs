@@ -0,0 +1,135 @@
+use core::cell::Cell;
+use core::fmt::{self, Debug, Formatter};
+
+use intid::IntegerIdCounter;
+
+/// A snapshot of a [`SpeculativeIdAllocator`]'s state,
+/// which can be restored to undo all allocations made since.
+///
+/// Created by [`SpeculativeIdAllocator::checkpoint`].
+/// Each checkpoint must be consumed by either `rollback_to` or `commit`,
+/// in the reverse order they were created.
+/// Dropping a checkpoint leaves it open,
+/// until it is discarded by [`SpeculativeIdAllocator::discard_checkpoints`].
+///
+/// A checkpoint does not record which allocator it came from.
+/// Passing it to a different allocator (including a clone of the original)
+/// is a logic error, which is not reliably detected.
+///
+/// [`SpeculativeIdAllocator`]: crate::SpeculativeIdAllocator
+/// [`SpeculativeIdAllocator::checkpoint`]: crate::SpeculativeIdAllocator::checkpoint
+/// [`SpeculativeIdAllocator::discard_checkpoints`]: crate::SpeculativeIdAllocator::discard_checkpoints
+#[must_use = "a checkpoint must be rolled back or committed"]
+pub struct Checkpoint<T: IntegerIdCounter> {
+    pub(crate) next_id: Option<T>,
+    pub(crate) depth: usize,
+    pub(crate) epoch: usize,
+}
+impl<T: IntegerIdCounter> Debug for Checkpoint<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Checkpoint")
+            .field("next_id", &self.next_id)
+            .field("depth", &self.depth)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A snapshot of an [`IdAllocator`]'s state,
+/// which can be restored to undo all allocations and frees made since.
+///
+/// Created by [`IdAllocator::checkpoint`].
+/// Each checkpoint must be consumed by either `rollback_to` or `commit`,
+/// in the reverse order they were created.
+/// Dropping a checkpoint leaves it open,
+/// so the allocator keeps logging every allocation and free
+/// until [`IdAllocator::discard_checkpoints`] is called.
+///
+/// Just like a [`Checkpoint`], passing it to a different allocator is a logic error,
+/// which is not reliably detected.
+///
+/// [`IdAllocator`]: crate::IdAllocator
+/// [`IdAllocator::checkpoint`]: crate::IdAllocator::checkpoint
+/// [`IdAllocator::discard_checkpoints`]: crate::IdAllocator::discard_checkpoints
+#[cfg(feature = "alloc")]
+#[must_use = "a checkpoint must be rolled back or committed"]
+pub struct IdAllocatorCheckpoint<T: IntegerIdCounter> {
+    /// The next id of the counter, along with the position in the checkpoint stack.
+    pub(crate) next_id: Checkpoint<T>,
+    /// The number of ids issued by the counter.
+    pub(crate) issued: usize,
+    /// The number of allocated ids.
+    pub(crate) allocated: usize,
+    /// The length of the undo log.
+    pub(crate) undo_len: usize,
+    /// The event counter, which orders frees for the reuse policy.
+    pub(crate) events: usize,
+}
+#[cfg(feature = "alloc")]
+impl<T: IntegerIdCounter> Debug for IdAllocatorCheckpoint<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IdAllocatorCheckpoint")
+            .field("next_id", &self.next_id.next_id)
+            .field("depth", &self.next_id.depth)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Tracks the checkpoints which are currently open,
+/// in order to detect stale checkpoints.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct CheckpointStack {
+    /// The number of open checkpoints.
+    depth: Cell<usize>,
+    /// Incremented whenever all open checkpoints are invalidated.
+    epoch: Cell<usize>,
+}
+impl CheckpointStack {
+    #[inline]
+    pub const fn new() -> Self {
+        CheckpointStack {
+            depth: Cell::new(0),
+            epoch: Cell::new(0),
+        }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.depth.get() == 0
+    }
+
+    /// Open a new checkpoint, which restores the counter to `next_id`.
+    #[inline]
+    pub fn push<T: IntegerIdCounter>(&self, next_id: Option<T>) -> Checkpoint<T> {
+        let depth = self.depth.get();
+        self.depth.set(depth + 1);
+        Checkpoint {
+            next_id,
+            depth,
+            epoch: self.epoch.get(),
+        }
+    }
+
+    /// Close the specified checkpoint,
+    /// panicking if it is not the most recently opened one.
+    #[track_caller]
+    pub fn pop<T: IntegerIdCounter>(&self, checkpoint: &Checkpoint<T>) {
+        assert!(
+            checkpoint.epoch == self.epoch.get(),
+            "Stale checkpoint: the allocator was reset after it was created"
+        );
+        assert!(
+            checkpoint.depth + 1 == self.depth.get(),
+            "Stale checkpoint: checkpoints must be consumed in the reverse order they were created"
+        );
+        self.depth.set(checkpoint.depth);
+    }
+
+    /// Invalidate all open checkpoints.
+    #[inline]
+    pub fn invalidate(&self) {
+        if !self.is_empty() {
+            self.depth.set(0);
+            self.epoch.set(self.epoch.get().wrapping_add(1));
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use crate::{AllocatorStats, IdExhaustedError, InvalidFreeError};
+use intid::{primint, IntegerIdCounter};
+
+/// The number of bits in each word of the bitset.
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Allocates and frees ids from a small fixed-size pool,
+/// without requiring a heap.
+///
+/// The pool holds up to `WORDS * 64` ids, which are tracked in an inline bitset.
+/// Like [`IdAllocator`](crate::IdAllocator), allocation always reuses the smallest free id,
+/// found using [`u64::trailing_zeros`] on each word of the bitset in turn.
+/// Freeing an id and checking if it [is allocated](Self::is_allocated) take constant time.
+///
+/// This is available without the `alloc` feature,
+/// and [`Self::new`] is a `const fn`, so the allocator can be placed in a `static`.
+///
+/// # Example
+/// ```
+/// use intid_allocator::FixedIdAllocator;
+/// // a pool of 100 handles, stored in two words
+/// let mut handles = FixedIdAllocator::<u8, 2>::with_capacity(100);
+/// let (a, b) = (handles.alloc(), handles.alloc());
+/// assert_eq!((a, b), (0, 1));
+/// handles.free(a);
+/// assert_eq!(handles.alloc(), 0);
+/// assert_eq!(handles.remaining(), 98);
+/// ```
+#[derive(Clone, Debug)]
+pub struct FixedIdAllocator<T: IntegerIdCounter, const WORDS: usize> {
+    /// The first id returned by the allocator.
+    start: T,
+    /// A bitset of allocated ids, relative to `start`.
+    used: [u64; WORDS],
+    /// The number of ids in the pool, which is at most `WORDS * 64`.
+    capacity: usize,
+    /// One past the largest position which was ever allocated,
+    /// used to distinguish double frees from ids which were never allocated.
+    issued: usize,
+    /// The number of currently allocated ids.
+    allocated: usize,
+    /// The total number of ids allocated, for statistics.
+    allocations: usize,
+    /// The total number of ids freed, for statistics.
+    frees: usize,
+    /// The number of failed allocations, for statistics.
+    exhaustions: usize,
+}
+impl<T: IntegerIdCounter, const WORDS: usize> Default for FixedIdAllocator<T, WORDS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T: IntegerIdCounter, const WORDS: usize> FixedIdAllocator<T, WORDS> {
+    /// The largest possible capacity of the pool.
+    pub const MAX_CAPACITY: usize = WORDS * WORD_BITS;
+
+    /// Create a new allocator with ids starting at [`T::START`] (usually zero),
+    /// which can allocate up to [`Self::MAX_CAPACITY`] ids at once.
+    ///
+    /// [`T::START`]: IntegerIdCounter::START
+    #[inline]
+    pub const fn new() -> Self {
+        Self::with_start(T::START)
+    }
+
+    /// Create a new allocator with ids starting at the specified value,
+    /// which can allocate up to [`Self::MAX_CAPACITY`] ids at once.
+    #[inline]
+    pub const fn with_start(start: T) -> Self {
+        FixedIdAllocator {
+            start,
+            used: [0; WORDS],
+            capacity: Self::MAX_CAPACITY,
+            issued: 0,
+            allocated: 0,
+            allocations: 0,
+            frees: 0,
+            exhaustions: 0,
+        }
+    }
+
+    /// Create a new allocator with ids starting at [`T::START`] (usually zero),
+    /// which can allocate up to `capacity` ids at once.
+    ///
+    /// # Panics
+    /// If the capacity exceeds [`Self::MAX_CAPACITY`].
+    ///
+    /// [`T::START`]: IntegerIdCounter::START
+    #[inline]
+    #[track_caller]
+    pub const fn with_capacity(capacity: usize) -> Self {
+        assert!(
+            capacity <= Self::MAX_CAPACITY,
+            "capacity exceeds the size of the bitset"
+        );
+        let mut allocator = Self::new();
+        allocator.capacity = capacity;
+        allocator
+    }
+
+    /// The maximum number of ids which can be allocated at once.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of ids which can still be allocated.
+    ///
+    /// This may overestimate if the pool extends past the maximum id of `T`.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.capacity - self.allocated
+    }
+
+    /// The number of currently allocated ids.
+    #[inline]
+    pub fn allocated_count(&self) -> usize {
+        self.allocated
+    }
+
+    /// Statistics about the allocator.
+    ///
+    /// The [high-water mark](AllocatorStats::high_water_mark) is one past
+    /// the largest position ever allocated since the last call to [`Self::free_all`].
+    pub fn stats(&self) -> AllocatorStats {
+        AllocatorStats {
+            live: self.allocated,
+            free: self.issued - self.allocated,
+            high_water_mark: self.issued,
+            allocations: self.allocations,
+            frees: self.frees,
+            exhaustions: self.exhaustions,
+        }
+    }
+
+    /// Allocate the smallest free id.
+    ///
+    /// # Errors
+    /// If all ids in the pool are allocated,
+    /// or if the next id would exceed the maximum id of `T`.
+    pub fn try_alloc(&mut self) -> Result<T, IdExhaustedError<T>> {
+        let Some((pos, id)) = self.first_free() else {
+            self.exhaustions = self.exhaustions.wrapping_add(1);
+            return Err(self.exhausted());
+        };
+        self.used[pos / WORD_BITS] |= 1 << (pos % WORD_BITS);
+        self.issued = core::cmp::max(self.issued, pos + 1);
+        self.allocated += 1;
+        self.allocations = self.allocations.wrapping_add(1);
+        Ok(id)
+    }
+
+    /// Allocate the smallest free id, panicking if the pool is exhausted.
+    ///
+    /// # Panics
+    /// If no ids are available.
+    /// See [`Self::try_alloc`] for a version that returns an error instead.
+    #[track_caller]
+    #[inline]
+    #[must_use]
+    pub fn alloc(&mut self) -> T {
+        match self.try_alloc() {
+            Ok(id) => id,
+            Err(e) => e.panic(),
+        }
+    }
+
+    /// Free the specified id, making it available for reuse.
+    ///
+    /// Freeing an id which is not currently allocated is a logic error.
+    /// This panics in debug builds, and otherwise leaves the allocator unchanged.
+    /// See [`Self::try_free`] for a version that returns an error instead.
+    #[track_caller]
+    #[inline]
+    pub fn free(&mut self, id: T) {
+        if let Err(e) = self.try_free(id) {
+            if cfg!(debug_assertions) {
+                e.panic()
+            }
+        }
+    }
+
+    /// Free the specified id, making it available for reuse,
+    /// or return an error if it is not currently allocated.
+    ///
+    /// # Errors
+    /// Fails if the id was already freed, or if it was never allocated.
+    /// In that case, the allocator is left unchanged.
+    pub fn try_free(&mut self, id: T) -> Result<(), InvalidFreeError<T>> {
+        let Some(pos) = self.pos_of(id).filter(|&pos| pos < self.issued) else {
+            return Err(InvalidFreeError::never_allocated(id));
+        };
+        let (word, mask) = (pos / WORD_BITS, 1 << (pos % WORD_BITS));
+        if self.used[word] & mask == 0 {
+            return Err(InvalidFreeError::double_free(id));
+        }
+        self.used[word] &= !mask;
+        self.allocated -= 1;
+        self.frees = self.frees.wrapping_add(1);
+        Ok(())
+    }
+
+    /// Free all existing ids, resetting the allocator.
+    #[inline]
+    pub fn free_all(&mut self) {
+        self.used = [0; WORDS];
+        self.frees = self.frees.wrapping_add(self.allocated);
+        self.issued = 0;
+        self.allocated = 0;
+    }
+
+    /// Check if the specified id is currently allocated.
+    #[inline]
+    pub fn is_allocated(&self, id: T) -> bool {
+        self.pos_of(id)
+            .is_some_and(|pos| self.used[pos / WORD_BITS] & (1 << (pos % WORD_BITS)) != 0)
+    }
+
+    /// Find the smallest free position and its corresponding id.
+    #[inline]
+    fn first_free(&self) -> Option<(usize, T)> {
+        let (index, word) = self
+            .used
+            .iter()
+            .enumerate()
+            .find(|(_, &word)| word != u64::MAX)?;
+        let pos = index * WORD_BITS + (!word).trailing_zeros() as usize;
+        if pos >= self.capacity {
+            return None;
+        }
+        let id = IntegerIdCounter::checked_add(self.start, primint::from_usize_checked(pos)?)?;
+        Some((pos, id))
+    }
+
+    /// The position of the specified id in the bitset,
+    /// or `None` if it is outside the pool.
+    #[inline]
+    fn pos_of(&self, id: T) -> Option<usize> {
+        primint::checked_sub(id.to_int(), self.start.to_int())
+            .and_then(primint::to_usize_checked)
+            .filter(|&pos| pos < self.capacity)
+    }
+
+    #[cold]
+    fn exhausted(&self) -> IdExhaustedError<T> {
+        primint::from_usize_checked(self.capacity)
+            .and_then(|capacity| IntegerIdCounter::checked_add(self.start, capacity))
+            .map_or_else(IdExhaustedError::new, |end| {
+                IdExhaustedError::bounded(self.start..end)
+            })
+    }
+}
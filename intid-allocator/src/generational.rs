@@ -0,0 +1,184 @@
+use alloc::vec::Vec;
+
+use crate::{AllocatorStats, IdAllocator, IdExhaustedError};
+use intid::primint::{self, UnsignedPrimInt};
+use intid::utils::Generational;
+use intid::IntegerIdCounter;
+
+/// Allocates [`Generational`] ids,
+/// bumping the generation of an index each time it is freed.
+///
+/// Indexes are reused just like an [`IdAllocator`],
+/// keeping lookup tables dense.
+/// However, a stale id from before the index was freed
+/// has an old generation and will no longer be [live](Self::is_live),
+/// instead of silently aliasing the new owner of the index.
+///
+/// Once the generation of an index reaches [`G::MAX`](UnsignedPrimInt),
+/// freeing it retires the index permanently instead of wrapping around.
+/// This guarantees stale ids are never revived,
+/// at the cost of leaking the index.
+///
+/// Generations are stored in a table indexed by the offset of the index from [`I::START`],
+/// so memory usage is proportional to the largest index allocated.
+///
+/// [`I::START`]: IntegerIdCounter::START
+pub struct GenerationalIdAllocator<I: IntegerIdCounter, G: UnsignedPrimInt = u32> {
+    indexes: IdAllocator<I>,
+    slots: Vec<Slot<G>>,
+    len: usize,
+}
+#[derive(Copy, Clone)]
+struct Slot<G: UnsignedPrimInt> {
+    generation: G,
+    live: bool,
+}
+impl<I: IntegerIdCounter, G: UnsignedPrimInt> Default for GenerationalIdAllocator<I, G> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<I: IntegerIdCounter, G: UnsignedPrimInt> GenerationalIdAllocator<I, G> {
+    /// Create a new allocator, with indexes starting at [`I::START`] (usually zero).
+    ///
+    /// [`I::START`]: IntegerIdCounter::START
+    #[inline]
+    pub fn new() -> Self {
+        GenerationalIdAllocator {
+            indexes: IdAllocator::new(),
+            slots: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Allocate a new id, reusing freed indexes wherever possible.
+    ///
+    /// # Errors
+    /// If no more indexes are available, this will return an error.
+    /// This can only happen if the entire range of the [`IntegerIdCounter`]
+    /// has been allocated or retired, and none have been freed.
+    pub fn try_alloc(&mut self) -> Result<Generational<I, G>, IdExhaustedError<I>> {
+        let index = self.indexes.try_alloc()?;
+        let Some(pos) = slot_pos(index) else {
+            // the index can not be stored, so give it back instead of leaking it
+            let freed = self.indexes.try_free(index);
+            debug_assert!(freed.is_ok(), "index was just allocated");
+            return Err(IdExhaustedError::new());
+        };
+        if pos >= self.slots.len() {
+            self.slots.resize(
+                pos + 1,
+                Slot {
+                    generation: primint::zero(),
+                    live: false,
+                },
+            );
+        }
+        let slot = &mut self.slots[pos];
+        debug_assert!(!slot.live, "index {index:?} allocated twice");
+        slot.live = true;
+        self.len += 1;
+        Ok(Generational::new(index, slot.generation))
+    }
+
+    /// Allocate a new id, reusing freed indexes wherever possible.
+    ///
+    /// # Panics
+    /// If there are no indexes available, this will panic.
+    /// See [`Self::try_alloc`] for a version that returns an error instead.
+    #[track_caller]
+    #[inline]
+    #[must_use]
+    pub fn alloc(&mut self) -> Generational<I, G> {
+        match self.try_alloc() {
+            Ok(id) => id,
+            Err(e) => e.panic(),
+        }
+    }
+
+    /// Free the specified id, bumping the generation of its index
+    /// so that the index can be reused.
+    ///
+    /// Returns `false` if the id is not [live](Self::is_live),
+    /// because it is stale or was never allocated.
+    /// In that case the allocator is left unchanged.
+    pub fn free(&mut self, id: Generational<I, G>) -> bool {
+        let slot = match slot_pos(id.index()).and_then(|pos| self.slots.get_mut(pos)) {
+            Some(slot) if slot.live && slot.generation == id.generation() => slot,
+            _ => return false,
+        };
+        slot.live = false;
+        self.len -= 1;
+        // retire the index once its generation is exhausted
+        if let Some(next) = primint::checked_add(slot.generation, primint::one()) {
+            slot.generation = next;
+            let freed = self.indexes.try_free(id.index());
+            debug_assert!(freed.is_ok(), "live index must be allocated");
+        }
+        true
+    }
+
+    /// Check if the specified id is currently allocated,
+    /// and its generation matches the current generation of its index.
+    #[inline]
+    pub fn is_live(&self, id: Generational<I, G>) -> bool {
+        slot_pos(id.index())
+            .and_then(|pos| self.slots.get(pos))
+            .is_some_and(|slot| slot.live && slot.generation == id.generation())
+    }
+
+    /// Get the live id for the specified index,
+    /// or `None` if the index is not currently allocated.
+    #[inline]
+    pub fn current(&self, index: I) -> Option<Generational<I, G>> {
+        slot_pos(index)
+            .and_then(|pos| self.slots.get(pos))
+            .filter(|slot| slot.live)
+            .map(|slot| Generational::new(index, slot.generation))
+    }
+
+    /// Statistics about the allocation of indexes,
+    /// which are reused just like an [`IdAllocator`].
+    ///
+    /// Retired indexes are counted as freed, but are neither live nor free,
+    /// so [`AllocatorStats::live`] always matches [`Self::len`].
+    /// Use [`Self::retired`] to count them.
+    #[inline]
+    pub fn stats(&self) -> AllocatorStats {
+        let retired = self.retired();
+        let stats = self.indexes.stats();
+        AllocatorStats {
+            live: stats.live - retired,
+            frees: stats.frees.wrapping_add(retired),
+            ..stats
+        }
+    }
+
+    /// The number of indexes which were retired because their generation was exhausted.
+    ///
+    /// These indexes will never be allocated again.
+    #[inline]
+    pub fn retired(&self) -> usize {
+        // retired indexes are never freed from the underlying allocator
+        self.indexes.allocated_count() - self.len
+    }
+
+    /// The number of currently live ids.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if there are no live ids.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// The position of the index in the table of slots,
+/// or `None` if it is before [`I::START`](IntegerIdCounter::START) or does not fit in a `usize`.
+#[inline]
+fn slot_pos<I: IntegerIdCounter>(index: I) -> Option<usize> {
+    primint::checked_sub(index.to_int(), I::START_INT).and_then(primint::to_usize_checked)
+}
@@ -2,26 +2,86 @@
 //!
 //! Use [`IdAllocator`] if you want to be able to [free](IdAllocator::free) existing ids for reuse.
 //! This will minimize the integer value of the keys, reducing memory needed for lookup tables.
+//! A [`ReusePolicy`] can delay reuse instead, to expose bugs where stale ids are still in use.
+//! With the `idmap` feature, [compacting](IdAllocator::compact) an allocator renumbers the live ids
+//! densely, returning an `IdRemap` to rewrite existing tables.
+//!
+//! Use [`FixedIdAllocator`] to reuse ids from a small fixed-size pool,
+//! which is stored inline and does not need the `alloc` feature.
+//!
+//! Use [`GenerationalIdAllocator`] to reuse ids while detecting stale ids,
+//! by pairing each index with a generation that is bumped when it is freed.
+//!
+//! Use [`IdAllocatorAtomic`] to free and reuse ids from multiple threads.
+//! If contention on the shared counter is a bottleneck,
+//! use [`ThreadCachedIdAllocator`] to reserve chunks of ids for each thread.
+//!
+//! Allocate an [`OwnedId`] guard to free an id automatically when it is dropped.
+//! Guards from a [`SharedIdAllocator`] are `'static`.
 //!
 //! Use [`UniqueIdAllocator`] or [`UniqueIdAllocatorAtomic`] if you don't care about reusing existing keys.
 //! These are more efficient and never require any allocation.
+//! Use [`SpeculativeIdAllocator`] to undo speculative allocations with checkpoints.
+//!
+//! Use [`ShardedIdAllocator`] to split the ids between workers which allocate without coordination.
+//!
+//! Each allocator reports [`AllocatorStats`],
+//! which are useful for sizing lookup tables and reporting memory usage.
 #![cfg_attr(not(feature = "std"), no_std)]
-#![deny(unsafe_code)] // not needed yet
+#![deny(unsafe_code)] // only allowed where a const fn has no safe alternative
 
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
 use core::fmt::{Debug, Display, Formatter};
-use core::marker::PhantomData;
+use core::ops::Range;
 use intid::IntegerId;
 
+#[cfg(feature = "alloc")]
+mod bitset;
+#[cfg(all(feature = "std", any(feature = "atomic", feature = "portable-atomic")))]
+mod cached;
+mod checkpoint;
+mod fixed;
+#[cfg(feature = "alloc")]
+mod generational;
+#[cfg(feature = "alloc")]
+mod owned;
+#[cfg(feature = "idmap")]
+mod remap;
 #[cfg(feature = "alloc")]
 mod reusing;
+mod sharded;
+mod speculative;
+mod stats;
+#[cfg(any(feature = "atomic", feature = "portable-atomic"))]
+mod sync;
 mod unique;
 
+#[cfg(all(feature = "std", any(feature = "atomic", feature = "portable-atomic")))]
+pub use self::cached::{LocalIdCache, ThreadCachedIdAllocator};
+pub use self::checkpoint::Checkpoint;
+#[cfg(feature = "alloc")]
+pub use self::checkpoint::IdAllocatorCheckpoint;
+pub use self::fixed::FixedIdAllocator;
+#[cfg(feature = "alloc")]
+pub use self::generational::GenerationalIdAllocator;
+#[cfg(feature = "alloc")]
+pub use self::owned::OwnedId;
+#[cfg(all(feature = "std", any(feature = "atomic", feature = "portable-atomic")))]
+pub use self::owned::SharedIdAllocator;
+#[cfg(feature = "idmap")]
+pub use self::remap::IdRemap;
+#[cfg(all(feature = "std", any(feature = "atomic", feature = "portable-atomic")))]
+pub use self::reusing::atomic::IdAllocatorAtomic;
 #[cfg(feature = "alloc")]
-pub use self::reusing::IdAllocator;
-#[cfg(feature = "atomic")]
+pub use self::reusing::{AllocatedIds, IdAllocator, ReusePolicy};
+pub use self::sharded::{IdShard, ShardedIdAllocator};
+pub use self::speculative::SpeculativeIdAllocator;
+pub use self::stats::AllocatorStats;
+#[cfg(any(feature = "atomic", feature = "portable-atomic"))]
+pub use self::sync::AtomicPrimInt;
+#[cfg(any(feature = "atomic", feature = "portable-atomic"))]
 pub use self::unique::atomic::UniqueIdAllocatorAtomic;
 pub use self::unique::UniqueIdAllocator;
 
@@ -29,7 +89,8 @@ pub use self::unique::UniqueIdAllocator;
 /// and can no longer be allocated.
 #[derive(Clone)]
 pub struct IdExhaustedError<T: IntegerId> {
-    marker: PhantomData<T>,
+    /// The window of ids available to the allocator, if it was bounded.
+    bounds: Option<(T, T)>,
 }
 impl<T: IntegerId> IdExhaustedError<T> {
     /// Indicate that ids have been exhausted for the type `T`,
@@ -39,11 +100,26 @@ impl<T: IntegerId> IdExhaustedError<T> {
     #[allow(clippy::new_without_default)] // doesn't make much sense for an error
     #[must_use]
     pub fn new() -> Self {
+        IdExhaustedError { bounds: None }
+    }
+
+    /// Indicate that ids have been exhausted within the specified window.
+    #[inline]
+    #[cold]
+    #[must_use]
+    pub fn bounded(bounds: Range<T>) -> Self {
         IdExhaustedError {
-            marker: PhantomData,
+            bounds: Some((bounds.start, bounds.end)),
         }
     }
 
+    /// The window of ids which was exhausted,
+    /// or `None` if the allocator could use every id after its start.
+    #[inline]
+    pub fn bounds(&self) -> Option<Range<T>> {
+        self.bounds.map(|(start, end)| start..end)
+    }
+
     /// Trigger a descriptive panic due to this error.
     ///
     /// This gives a better panic message than calling [`Result::unwrap`].
@@ -58,13 +134,18 @@ impl<T: IntegerId> IdExhaustedError<T> {
 }
 impl<T: IntegerId> Display for IdExhaustedError<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        write!(f, "Ran out of ids for {}", core::any::type_name::<T>())
+        write!(f, "Ran out of ids for {}", core::any::type_name::<T>())?;
+        if let Some((start, end)) = self.bounds {
+            write!(f, " in {start:?}..{end:?}")?;
+        }
+        Ok(())
     }
 }
 impl<T: IntegerId> Debug for IdExhaustedError<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("IdExhaustedError")
             .field("type_name", &core::any::type_name::<T>())
+            .field("bounds", &self.bounds())
             .finish_non_exhaustive()
     }
 }
@@ -75,3 +156,81 @@ impl<T: IntegerId> core::error::Error for IdExhaustedError<T> {}
 #[rustversion::before(1.81)]
 #[cfg(feature = "std")]
 impl<T: IntegerId> std::error::Error for IdExhaustedError<T> {}
+
+/// Indicates that an id could not be freed,
+/// because it is not currently allocated.
+#[derive(Clone)]
+pub struct InvalidFreeError<T: IntegerId> {
+    id: T,
+    double_free: bool,
+}
+impl<T: IntegerId> InvalidFreeError<T> {
+    /// Indicate that the id was already freed.
+    #[cold]
+    #[must_use]
+    pub fn double_free(id: T) -> Self {
+        InvalidFreeError {
+            id,
+            double_free: true,
+        }
+    }
+
+    /// Indicate that the id was never allocated in the first place.
+    #[cold]
+    #[must_use]
+    pub fn never_allocated(id: T) -> Self {
+        InvalidFreeError {
+            id,
+            double_free: false,
+        }
+    }
+
+    /// The id which could not be freed.
+    #[inline]
+    pub fn id(&self) -> T {
+        self.id
+    }
+
+    /// Return true if the id was already freed,
+    /// or false if it was never allocated.
+    #[inline]
+    pub fn is_double_free(&self) -> bool {
+        self.double_free
+    }
+
+    /// Trigger a descriptive panic due to this error.
+    ///
+    /// This gives a better panic message than calling [`Result::unwrap`].
+    ///
+    /// # Panics
+    /// Always.
+    #[track_caller]
+    #[cold]
+    pub fn panic(self) -> ! {
+        panic!("{self}")
+    }
+}
+impl<T: IntegerId> Display for InvalidFreeError<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        if self.double_free {
+            write!(f, "Id {:?} was already freed", self.id)
+        } else {
+            write!(f, "Id {:?} was never allocated", self.id)
+        }
+    }
+}
+impl<T: IntegerId> Debug for InvalidFreeError<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("InvalidFreeError")
+            .field("id", &self.id)
+            .field("double_free", &self.double_free)
+            .finish()
+    }
+}
+
+#[rustversion::since(1.81)]
+impl<T: IntegerId> core::error::Error for InvalidFreeError<T> {}
+
+#[rustversion::before(1.81)]
+#[cfg(feature = "std")]
+impl<T: IntegerId> std::error::Error for InvalidFreeError<T> {}
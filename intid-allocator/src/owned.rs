@@ -0,0 +1,294 @@
+use core::fmt::{self, Debug, Formatter};
+use core::ops::Deref;
+
+use crate::{IdAllocator, IdExhaustedError};
+use intid::IntegerIdCounter;
+
+#[cfg(all(feature = "std", any(feature = "atomic", feature = "portable-atomic")))]
+use crate::sync::AtomicPrimInt;
+#[cfg(all(feature = "std", any(feature = "atomic", feature = "portable-atomic")))]
+use crate::{IdAllocatorAtomic, InvalidFreeError};
+#[cfg(all(feature = "std", any(feature = "atomic", feature = "portable-atomic")))]
+use alloc::sync::Arc;
+
+/// An allocated id, which is freed when the guard is dropped.
+///
+/// This prevents leaking ids when an error path forgets to free them.
+/// Use [`Self::into_inner`] to keep the id allocated.
+///
+/// Created by [`IdAllocator::alloc_owned`], [`IdAllocatorAtomic::alloc_owned`],
+/// or [`SharedIdAllocator::alloc_owned`].
+/// Guards from a [`SharedIdAllocator`] have a `'static` lifetime.
+pub struct OwnedId<'a, T: IntegerIdCounter> {
+    id: T,
+    /// The allocator to free the id from,
+    /// or `None` if the id should be kept.
+    source: Option<Source<'a, T>>,
+}
+enum Source<'a, T: IntegerIdCounter> {
+    Reusing(&'a mut IdAllocator<T>),
+    #[cfg(all(feature = "std", any(feature = "atomic", feature = "portable-atomic")))]
+    Atomic(&'a dyn FreeAtomic<T>),
+    #[cfg(all(feature = "std", any(feature = "atomic", feature = "portable-atomic")))]
+    Shared(Arc<dyn FreeAtomic<T>>),
+}
+/// Frees ids from an [`IdAllocatorAtomic`],
+/// hiding the bounds on its counter from [`OwnedId`].
+#[cfg(all(feature = "std", any(feature = "atomic", feature = "portable-atomic")))]
+trait FreeAtomic<T: IntegerIdCounter>: Send + Sync {
+    fn try_free(&self, id: T) -> Result<(), InvalidFreeError<T>>;
+}
+#[cfg(all(feature = "std", any(feature = "atomic", feature = "portable-atomic")))]
+impl<T: IntegerIdCounter> FreeAtomic<T> for IdAllocatorAtomic<T>
+where
+    T::Int: AtomicPrimInt,
+{
+    #[inline]
+    fn try_free(&self, id: T) -> Result<(), InvalidFreeError<T>> {
+        IdAllocatorAtomic::try_free(self, id)
+    }
+}
+impl<'a, T: IntegerIdCounter> OwnedId<'a, T> {
+    #[inline]
+    fn new(id: T, source: Source<'a, T>) -> Self {
+        OwnedId {
+            id,
+            source: Some(source),
+        }
+    }
+
+    /// The id owned by this guard.
+    #[inline]
+    pub fn id(&self) -> T {
+        self.id
+    }
+
+    /// Release ownership of the id, without freeing it.
+    ///
+    /// The id remains allocated until it is explicitly freed.
+    #[inline]
+    #[must_use = "the id will remain allocated, use forget to ignore it"]
+    pub fn into_inner(mut self) -> T {
+        self.source = None;
+        self.id
+    }
+
+    /// Release ownership of the id without freeing it,
+    /// intentionally leaking it.
+    ///
+    /// Equivalent to discarding the result of [`Self::into_inner`].
+    #[inline]
+    pub fn forget(self) {
+        let _ = self.into_inner();
+    }
+}
+impl<T: IntegerIdCounter> Deref for OwnedId<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.id
+    }
+}
+impl<T: IntegerIdCounter> Drop for OwnedId<'_, T> {
+    fn drop(&mut self) {
+        let result = match self.source.take() {
+            None => return,
+            Some(Source::Reusing(alloc)) => alloc.try_free(self.id),
+            #[cfg(all(feature = "std", any(feature = "atomic", feature = "portable-atomic")))]
+            Some(Source::Atomic(alloc)) => alloc.try_free(self.id),
+            #[cfg(all(feature = "std", any(feature = "atomic", feature = "portable-atomic")))]
+            Some(Source::Shared(alloc)) => alloc.try_free(self.id),
+        };
+        // can only fail if the id was freed manually
+        debug_assert!(result.is_ok(), "owned id was already freed");
+    }
+}
+impl<T: IntegerIdCounter> Debug for OwnedId<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("OwnedId").field(&self.id).finish()
+    }
+}
+
+impl<T: IntegerIdCounter> IdAllocator<T> {
+    /// Allocate a new id, which is freed when the guard is dropped.
+    ///
+    /// The guard borrows the allocator mutably,
+    /// so only a single guard can be alive at a time,
+    /// and the allocator can not be used until it is dropped or released.
+    /// This is most useful to free an id on an error path before it is stored.
+    ///
+    /// To hold multiple guards at once, use [`IdAllocatorAtomic::try_alloc_owned`],
+    /// or [`SharedIdAllocator`] for guards which are `'static`.
+    ///
+    /// # Errors
+    /// If no more ids are available, this will return an error.
+    #[inline]
+    pub fn try_alloc_owned(&mut self) -> Result<OwnedId<'_, T>, IdExhaustedError<T>> {
+        let id = self.try_alloc()?;
+        Ok(OwnedId::new(id, Source::Reusing(self)))
+    }
+
+    /// Allocate a new id, which is freed when the guard is dropped.
+    ///
+    /// Just like [`Self::try_alloc_owned`], only a single guard can be alive at a time.
+    ///
+    /// # Panics
+    /// If there are no ids available.
+    /// See [`Self::try_alloc_owned`] for a version that returns an error instead.
+    #[track_caller]
+    #[inline]
+    pub fn alloc_owned(&mut self) -> OwnedId<'_, T> {
+        match self.try_alloc_owned() {
+            Ok(id) => id,
+            Err(e) => e.panic(),
+        }
+    }
+}
+
+#[cfg(all(feature = "std", any(feature = "atomic", feature = "portable-atomic")))]
+impl<T: IntegerIdCounter> IdAllocatorAtomic<T>
+where
+    T::Int: AtomicPrimInt,
+{
+    /// Allocate a new id, which is freed when the guard is dropped.
+    ///
+    /// # Errors
+    /// If no more ids are available, this will return an error.
+    #[inline]
+    pub fn try_alloc_owned(&self) -> Result<OwnedId<'_, T>, IdExhaustedError<T>> {
+        Ok(OwnedId::new(self.try_alloc()?, Source::Atomic(self)))
+    }
+
+    /// Allocate a new id, which is freed when the guard is dropped.
+    ///
+    /// # Panics
+    /// If there are no ids available.
+    /// See [`Self::try_alloc_owned`] for a version that returns an error instead.
+    #[track_caller]
+    #[inline]
+    pub fn alloc_owned(&self) -> OwnedId<'_, T> {
+        match self.try_alloc_owned() {
+            Ok(id) => id,
+            Err(e) => e.panic(),
+        }
+    }
+}
+
+/// A reference-counted [`IdAllocatorAtomic`],
+/// which allocates `'static` [`OwnedId`] guards.
+///
+/// Cloning this type shares the same underlying allocator.
+/// All other methods are available through [`Deref`].
+///
+/// # Example
+/// ```
+/// use intid_allocator::SharedIdAllocator;
+/// let alloc = SharedIdAllocator::<u32>::new();
+/// let id = alloc.alloc_owned();
+/// let handle = std::thread::spawn(move || *id);
+/// assert_eq!(handle.join().unwrap(), 0);
+/// // the id was freed when the guard was dropped
+/// assert_eq!(alloc.alloc(), 0);
+/// ```
+#[cfg(all(feature = "std", any(feature = "atomic", feature = "portable-atomic")))]
+pub struct SharedIdAllocator<T: IntegerIdCounter>
+where
+    T::Int: AtomicPrimInt,
+{
+    inner: Arc<IdAllocatorAtomic<T>>,
+}
+#[cfg(all(feature = "std", any(feature = "atomic", feature = "portable-atomic")))]
+impl<T: IntegerIdCounter> SharedIdAllocator<T>
+where
+    T::Int: AtomicPrimInt,
+{
+    /// Create a new allocator, with ids starting at [`T::START`] (usually zero).
+    ///
+    /// [`T::START`]: IntegerIdCounter::START
+    #[inline]
+    pub fn new() -> Self {
+        Self::from(IdAllocatorAtomic::new())
+    }
+
+    /// Allocate a new id, which is freed when the guard is dropped.
+    ///
+    /// # Errors
+    /// If no more ids are available, this will return an error.
+    #[inline]
+    pub fn try_alloc_owned(&self) -> Result<OwnedId<'static, T>, IdExhaustedError<T>> {
+        let id = self.inner.try_alloc()?;
+        Ok(OwnedId::new(
+            id,
+            Source::Shared(Arc::clone(&self.inner) as Arc<dyn FreeAtomic<T>>),
+        ))
+    }
+
+    /// Allocate a new id, which is freed when the guard is dropped.
+    ///
+    /// # Panics
+    /// If there are no ids available.
+    /// See [`Self::try_alloc_owned`] for a version that returns an error instead.
+    #[track_caller]
+    #[inline]
+    pub fn alloc_owned(&self) -> OwnedId<'static, T> {
+        match self.try_alloc_owned() {
+            Ok(id) => id,
+            Err(e) => e.panic(),
+        }
+    }
+}
+#[cfg(all(feature = "std", any(feature = "atomic", feature = "portable-atomic")))]
+impl<T: IntegerIdCounter> Default for SharedIdAllocator<T>
+where
+    T::Int: AtomicPrimInt,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+#[cfg(all(feature = "std", any(feature = "atomic", feature = "portable-atomic")))]
+impl<T: IntegerIdCounter> Clone for SharedIdAllocator<T>
+where
+    T::Int: AtomicPrimInt,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        SharedIdAllocator {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+#[cfg(all(feature = "std", any(feature = "atomic", feature = "portable-atomic")))]
+impl<T: IntegerIdCounter> From<IdAllocatorAtomic<T>> for SharedIdAllocator<T>
+where
+    T::Int: AtomicPrimInt,
+{
+    #[inline]
+    fn from(alloc: IdAllocatorAtomic<T>) -> Self {
+        SharedIdAllocator {
+            inner: Arc::new(alloc),
+        }
+    }
+}
+#[cfg(all(feature = "std", any(feature = "atomic", feature = "portable-atomic")))]
+impl<T: IntegerIdCounter> Deref for SharedIdAllocator<T>
+where
+    T::Int: AtomicPrimInt,
+{
+    type Target = IdAllocatorAtomic<T>;
+
+    #[inline]
+    fn deref(&self) -> &IdAllocatorAtomic<T> {
+        &self.inner
+    }
+}
+#[cfg(all(feature = "std", any(feature = "atomic", feature = "portable-atomic")))]
+impl<T: IntegerIdCounter> Debug for SharedIdAllocator<T>
+where
+    T::Int: AtomicPrimInt,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&*self.inner, f)
+    }
+}
@@ -0,0 +1,143 @@
+use alloc::vec::Vec;
+
+use idmap::{DirectIdMap, DirectIdSet};
+use intid::{primint, IntegerIdCounter};
+
+/// A mapping from old ids to new ids,
+/// produced by renumbering a set of live ids.
+///
+/// Created by [`IdAllocator::compact`] or [`IdRemap::compacting`].
+/// Ids which were not live have no mapping.
+///
+/// Use [`Self::apply_to_map`] and [`Self::apply_to_set`]
+/// to rewrite the keys of existing tables after compaction.
+///
+/// # Example
+/// ```
+/// use idmap::DirectIdMap;
+/// use intid_allocator::IdAllocator;
+/// let mut alloc = IdAllocator::<u32>::new();
+/// let mut names = DirectIdMap::new();
+/// for name in ["a", "b", "c"] {
+///     names.insert(alloc.alloc(), name);
+/// }
+/// alloc.free(1);
+/// names.remove(1);
+/// let remap = alloc.compact();
+/// remap.apply_to_map(&mut names);
+/// assert_eq!(names.get(0), Some(&"a"));
+/// assert_eq!(names.get(1), Some(&"c"));
+/// assert_eq!(alloc.alloc(), 2);
+/// ```
+///
+/// [`IdAllocator::compact`]: crate::IdAllocator::compact
+#[derive(Clone, Debug)]
+pub struct IdRemap<T: IntegerIdCounter> {
+    map: DirectIdMap<T, T>,
+}
+impl<T: IntegerIdCounter> IdRemap<T> {
+    /// Renumber the specified live ids densely,
+    /// starting from [`T::START`] (usually zero).
+    ///
+    /// The relative order of the ids is preserved,
+    /// so the smallest live id becomes `T::START`, the next smallest becomes its successor,
+    /// and so on. Duplicate ids are ignored.
+    ///
+    /// This is useful when the ids were not handed out by an [`IdAllocator`].
+    /// Afterwards, an allocator can continue from the end of the new ids
+    /// using [`IdAllocator::rebuild_from_used`].
+    ///
+    /// [`T::START`]: IntegerIdCounter::START
+    /// [`IdAllocator`]: crate::IdAllocator
+    /// [`IdAllocator::rebuild_from_used`]: crate::IdAllocator::rebuild_from_used
+    pub fn compacting(live_ids: impl IntoIterator<Item = T>) -> Self {
+        let mut live = live_ids.into_iter().collect::<Vec<T>>();
+        live.sort_unstable_by_key(|id| id.to_int());
+        live.dedup_by_key(|id| id.to_int());
+        Self::compacting_sorted(T::START, live)
+    }
+
+    /// Renumber the specified ids starting from `start`,
+    /// assuming they are sorted and unique.
+    pub(crate) fn compacting_sorted(start: T, live: impl IntoIterator<Item = T>) -> Self {
+        let mut map = DirectIdMap::new();
+        let mut next = Some(start);
+        for old in live {
+            // there can be no more live ids than there are ids after the start
+            let new = next.expect("too many ids to compact");
+            next = IntegerIdCounter::checked_add(new, primint::one());
+            map.insert(old, new);
+        }
+        IdRemap { map }
+    }
+
+    /// Get the new id corresponding to the specified old id,
+    /// or `None` if it was not live.
+    #[inline]
+    pub fn get(&self, old: T) -> Option<T> {
+        self.map.get(old).copied()
+    }
+
+    /// The number of live ids which were renumbered.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Check if there were no live ids.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.len() == 0
+    }
+
+    /// Check if every live id keeps its existing value,
+    /// in which case applying the remapping has no effect.
+    pub fn is_identity(&self) -> bool {
+        self.iter().all(|(old, new)| old == new)
+    }
+
+    /// Iterate over pairs of `(old, new)` ids,
+    /// in order of the old ids.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (T, T)> + '_ {
+        self.map.iter().map(|(old, &new)| (old, new))
+    }
+
+    /// The underlying map from old ids to new ids.
+    #[inline]
+    pub fn as_map(&self) -> &DirectIdMap<T, T> {
+        &self.map
+    }
+
+    /// Rewrite the keys of the specified map to use the new ids.
+    ///
+    /// Entries whose keys were not live are removed.
+    pub fn apply_to_map<V>(&self, map: &mut DirectIdMap<T, V>) {
+        let old = core::mem::take(map);
+        map.extend(
+            old.into_iter()
+                .filter_map(|(id, value)| Some((self.get(id)?, value))),
+        );
+    }
+
+    /// Rewrite the members of the specified set to use the new ids.
+    ///
+    /// Members which were not live are removed.
+    pub fn apply_to_set(&self, set: &mut DirectIdSet<T>) {
+        let old = core::mem::take(set);
+        for id in old {
+            if let Some(new) = self.get(id) {
+                set.insert(new);
+            }
+        }
+    }
+}
+impl<T: IntegerIdCounter> IntoIterator for IdRemap<T> {
+    type Item = (T, T);
+    type IntoIter = idmap::direct::map::IntoIter<T, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.into_iter()
+    }
+}
@@ -1,7 +1,20 @@
-use alloc::collections::BinaryHeap;
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
 
-use crate::{IdExhaustedError, UniqueIdAllocator};
-use intid::IntegerIdCounter;
+use crate::bitset::FreeBitset;
+use crate::checkpoint::CheckpointStack;
+#[cfg(feature = "idmap")]
+use crate::IdRemap;
+use crate::{
+    AllocatorStats, IdAllocatorCheckpoint, IdExhaustedError, InvalidFreeError, UniqueIdAllocator,
+};
+use intid::{primint, IntegerIdCounter};
+
+#[cfg(all(feature = "std", any(feature = "atomic", feature = "portable-atomic")))]
+pub mod atomic;
+#[cfg(feature = "serde")]
+mod serde;
 
 /// A type that allocates integer ids,
 /// with the ability to free unused ids back to storage.
@@ -14,18 +27,115 @@ use intid::IntegerIdCounter;
 /// consider [`crate::UniqueIdAllocator`] or [`crate::UniqueIdAllocatorAtomic`].
 /// These are more efficient and do not require an allocator.
 ///
-/// There is not any way to iterate over all currently allocated ids.
-/// With the current implementation (a [`BinaryHeap`]),
-/// it would be difficult to implement without any allocation.
+/// Freed ids are tracked using a bitset,
+/// taking one bit of memory for each id up to the largest freed id.
+/// By default, allocation reuses the smallest freed id first,
+/// found by scanning the bitset for the first set bit.
+/// Other orders can be chosen with a [`ReusePolicy`].
+/// The bitset also makes it possible to [iterate](Self::iter_allocated) over allocated ids
+/// and to detect [invalid frees](Self::free).
+///
+/// To persist an allocator, enable the `serde` feature to serialize its state,
+/// or use [`Self::rebuild_from_used`] to reconstruct it from the ids still in use.
 ///
 /// [idmap]: https://docs.rs/idmap/
 pub struct IdAllocator<T: IntegerIdCounter> {
-    next_id: crate::UniqueIdAllocator<T>,
-    /// Tracks freed ids for reuse, preferring smaller ids where possible
+    /// The first id returned by the allocator.
+    start: T,
+    next_id: UniqueIdAllocator<T>,
+    /// A bitset of freed ids, relative to `start`.
+    ///
+    /// Only contains ids which have been returned by `next_id`.
+    free: FreeBitset,
+    /// The number of ids returned by `next_id`,
+    /// which is the length of the free bitset in bits.
+    issued: usize,
+    /// The number of currently allocated ids.
+    allocated: usize,
+    /// Changes to the free bitset since the oldest open checkpoint.
     ///
-    /// The use of a [`BinaryHeap`] here is inspired by the `thread-local` crate.
-    /// No part of the implementation was copied.
-    heap: BinaryHeap<core::cmp::Reverse<intid::utils::OrderByInt<T>>>,
+    /// Empty if there are no open checkpoints.
+    undo: Vec<Undo>,
+    checkpoints: CheckpointStack,
+    policy: ReusePolicy,
+    /// The freed positions in the order they were freed.
+    ///
+    /// Empty if the policy is [`ReusePolicy::SmallestFirst`],
+    /// otherwise contains exactly the positions in the free bitset.
+    queue: VecDeque<QueuedId>,
+    /// The number of allocations and frees so far, used to time the quarantine.
+    events: usize,
+    /// The total number of ids allocated, for statistics.
+    allocations: usize,
+    /// The total number of ids freed, for statistics.
+    frees: usize,
+    /// The number of failed allocations, for statistics.
+    exhaustions: usize,
+}
+/// Determines which freed id an [`IdAllocator`] reuses first.
+///
+/// Reusing ids immediately keeps them small,
+/// but means a stale id quietly refers to whatever object was allocated next.
+/// Delaying reuse makes such bugs easier to expose,
+/// so debug builds may want a wider window than release builds:
+/// ```
+/// use intid_allocator::{IdAllocator, ReusePolicy};
+/// let policy = if cfg!(debug_assertions) {
+///     ReusePolicy::Quarantine { delay: 1024 }
+/// } else {
+///     ReusePolicy::SmallestFirst
+/// };
+/// let mut alloc = IdAllocator::<u32>::new().with_reuse_policy(policy);
+/// let id = alloc.alloc();
+/// alloc.free(id);
+/// assert_eq!(alloc.alloc() == id, !cfg!(debug_assertions));
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum ReusePolicy {
+    /// Reuse the smallest freed id,
+    /// which minimizes the integer value of the ids.
+    ///
+    /// This is the default.
+    #[default]
+    SmallestFirst,
+    /// Reuse the id which was freed the longest time ago.
+    Fifo,
+    /// Reuse the id which was freed most recently.
+    Lifo,
+    /// Keep freed ids unusable until `delay` other allocations or frees have happened,
+    /// then reuse them in the order they were freed.
+    ///
+    /// Allocating or freeing a range counts once for each id in the range.
+    /// Quarantined ids are still reused early if no new ids are left.
+    Quarantine {
+        /// The number of ids allocated or freed before a freed id can be reused.
+        delay: usize,
+    },
+}
+impl ReusePolicy {
+    /// Check if freed ids are tracked in the order they were freed.
+    #[inline]
+    fn is_queued(self) -> bool {
+        !matches!(self, ReusePolicy::SmallestFirst)
+    }
+}
+/// A freed position in the reuse queue of an [`IdAllocator`].
+#[derive(Copy, Clone, Debug)]
+struct QueuedId {
+    pos: usize,
+    /// The value of the event counter when the id was freed.
+    freed_at: usize,
+}
+/// A change to the free bitset of an [`IdAllocator`],
+/// which is undone by rolling back a checkpoint.
+#[derive(Copy, Clone, Debug)]
+enum Undo {
+    /// The range of positions was reused by an allocation.
+    Reused { start: usize, count: usize },
+    /// The range of positions was freed.
+    Freed { start: usize, count: usize },
+    /// A position was removed from the reuse queue by an allocation.
+    Dequeued(QueuedId),
 }
 impl<T: IntegerIdCounter> Default for IdAllocator<T> {
     fn default() -> Self {
@@ -52,26 +162,244 @@ impl<T: IntegerIdCounter> IdAllocator<T> {
     #[rustversion::attr(since(1.80), clippy::msrv = "1.80")]
     pub fn with_start(start: T) -> Self {
         IdAllocator {
+            start,
             next_id: UniqueIdAllocator::with_start(start),
-            heap: alloc::collections::BinaryHeap::new(),
+            free: FreeBitset::new(),
+            issued: 0,
+            allocated: 0,
+            undo: Vec::new(),
+            checkpoints: CheckpointStack::new(),
+            policy: ReusePolicy::SmallestFirst,
+            queue: VecDeque::new(),
+            events: 0,
+            allocations: 0,
+            frees: 0,
+            exhaustions: 0,
+        }
+    }
+
+    /// Create a new allocator,
+    /// which only allocates ids in the window from `start` up to (but excluding) `end`.
+    ///
+    /// Once every id in the window is allocated,
+    /// [`Self::try_alloc`] returns an [`IdExhaustedError`] which reports the bounds of the window.
+    /// If `end` is not greater than `start`, no ids can be allocated.
+    ///
+    /// The bounds are not preserved by serialization.
+    #[inline]
+    #[rustversion::attr(since(1.80), const)]
+    #[rustversion::attr(since(1.80), clippy::msrv = "1.80")]
+    pub fn with_bounds(start: T, end: T) -> Self {
+        IdAllocator {
+            start,
+            next_id: UniqueIdAllocator::with_bounds(start, end),
+            free: FreeBitset::new(),
+            issued: 0,
+            allocated: 0,
+            undo: Vec::new(),
+            checkpoints: CheckpointStack::new(),
+            policy: ReusePolicy::SmallestFirst,
+            queue: VecDeque::new(),
+            events: 0,
+            allocations: 0,
+            frees: 0,
+            exhaustions: 0,
         }
     }
 
-    /// Allocate a new id, reusing freed ids wherever possible.
+    /// The number of ids which can still be allocated,
+    /// including freed ids available for reuse.
+    ///
+    /// Saturates at [`usize::MAX`].
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        let freed = self.issued - self.allocated;
+        freed.saturating_add(self.next_id.remaining())
+    }
+
+    /// Statistics about the allocator.
+    ///
+    /// The [high-water mark](AllocatorStats::high_water_mark) is the number of ids
+    /// which have been handed out since the last call to [`Self::free_all`],
+    /// including freed ids available for reuse.
+    pub fn stats(&self) -> AllocatorStats {
+        AllocatorStats {
+            live: self.allocated,
+            free: self.issued - self.allocated,
+            high_water_mark: self.issued,
+            allocations: self.allocations,
+            frees: self.frees,
+            exhaustions: self.exhaustions,
+        }
+    }
+
+    /// Return the maximum currently allocated id,
+    /// or `None` if no ids are allocated.
+    pub fn max_used_id(&self) -> Option<T> {
+        let end = self.issued - self.free.trailing_run(self.issued);
+        end.checked_sub(1).map(|pos| self.id_at(pos))
+    }
+
+    /// The capacity needed for a table indexed by the integer value of the ids,
+    /// in order to hold every allocated id without resizing.
+    ///
+    /// This is one more than the integer value of [`Self::max_used_id`],
+    /// or zero if no ids are allocated.
+    /// It can be passed to [`DirectIdMap::with_capacity`] to presize a map.
+    /// Saturates at [`usize::MAX`].
+    ///
+    /// [`DirectIdMap::with_capacity`]: https://docs.rs/idmap/latest/idmap/direct/map/struct.DirectIdMap.html#method.with_capacity
+    pub fn capacity_hint(&self) -> usize {
+        self.max_used_id().map_or(0, |id| {
+            primint::to_usize_checked(id.to_int()).map_or(usize::MAX, |n| n.saturating_add(1))
+        })
+    }
+
+    /// Set the order in which freed ids are reused.
+    ///
+    /// Any ids which are already free are queued in order of their integer value,
+    /// and quarantined as if they were freed now.
+    ///
+    /// This discards the log of changes kept for [checkpoints](Self::checkpoint),
+    /// so all open checkpoints are invalidated.
+    /// Rolling back or committing one of them afterwards will panic.
+    ///
+    /// The policy is not preserved by serialization.
+    #[must_use]
+    pub fn with_reuse_policy(mut self, policy: ReusePolicy) -> Self {
+        self.discard_checkpoints();
+        self.policy = policy;
+        self.queue.clear();
+        if policy.is_queued() {
+            let freed_at = self.events;
+            self.queue
+                .extend(self.free.iter().map(|pos| QueuedId { pos, freed_at }));
+        }
+        self
+    }
+
+    /// The order in which freed ids are reused.
+    #[inline]
+    pub fn reuse_policy(&self) -> ReusePolicy {
+        self.policy
+    }
+
+    /// Reconstruct an allocator from the set of ids which are still in use,
+    /// with ids starting at [`T::START`] (usually zero).
+    ///
+    /// All ids between the start and the largest used id which are not in the set
+    /// are considered free, and will be reused by future allocations.
+    /// This is useful to restore an allocator after loading objects which store their ids.
+    /// Duplicate ids are ignored.
+    ///
+    /// # Panics
+    /// If any id is smaller than [`T::START`].
+    ///
+    /// [`T::START`]: IntegerIdCounter::START
+    pub fn rebuild_from_used(used: impl IntoIterator<Item = T>) -> Self {
+        let mut positions = used
+            .into_iter()
+            .map(|id| {
+                primint::checked_sub(id.to_int(), T::START_INT)
+                    .and_then(primint::to_usize_checked)
+                    .unwrap_or_else(|| panic!("Id {id:?} is below the start of the allocator"))
+            })
+            .collect::<Vec<usize>>();
+        positions.sort_unstable();
+        positions.dedup();
+        let issued = positions.last().map_or(0, |&last| {
+            last.checked_add(1).expect("too many ids to track")
+        });
+        let mut allocator = match Self::with_issued(T::START, issued) {
+            Ok(allocator) => allocator,
+            Err(e) => e.panic(),
+        };
+        let mut next_used = 0;
+        for pos in positions {
+            allocator.free.insert_range(next_used, pos - next_used);
+            allocator.allocated -= pos - next_used;
+            next_used = pos + 1;
+        }
+        // only the ids which are still in use count as allocated
+        allocator.allocations = allocator.allocated;
+        allocator
+    }
+
+    /// Create an allocator where the first `issued` ids are all allocated.
+    ///
+    /// # Errors
+    /// If there are fewer than `issued` ids after the start.
+    fn with_issued(start: T, issued: usize) -> Result<Self, IdExhaustedError<T>> {
+        let mut allocator = Self::with_start(start);
+        if issued > 0 {
+            allocator.next_id.try_alloc_range(issued)?;
+        }
+        allocator.issued = issued;
+        allocator.allocated = issued;
+        Ok(allocator)
+    }
+
+    /// Allocate a new id, reusing freed ids according to the [`ReusePolicy`].
     ///
     /// # Errors
     /// If no more ids are available, this will return an error.
     /// This can only happen if the entire range of the [`IntegerIdCounter`]
-    /// has been , and none have been fred
+    /// (or the window given to [`Self::with_bounds`]) has been allocated,
+    /// and none have been freed.
     #[inline]
     pub fn try_alloc(&mut self) -> Result<T, IdExhaustedError<T>> {
-        match self.heap.pop() {
-            Some(existing) => Ok(existing.0 .0),
-            None => self.next_id.try_alloc(),
-        }
+        let id = if let Some(pos) = self.take_freed(false) {
+            self.id_at(pos)
+        } else {
+            match self.next_id.try_alloc() {
+                Ok(id) => {
+                    self.issued += 1;
+                    id
+                }
+                // once new ids run out, quarantined ids are reused early
+                Err(e) => {
+                    let Some(pos) = self.take_freed(true) else {
+                        self.exhaustions = self.exhaustions.wrapping_add(1);
+                        return Err(e);
+                    };
+                    self.id_at(pos)
+                }
+            }
+        };
+        self.allocated += 1;
+        self.allocations = self.allocations.wrapping_add(1);
+        self.events = self.events.wrapping_add(1);
+        Ok(id)
     }
 
-    /// Allocate a new id, reusing freed ids wherever possible.
+    /// Remove the next freed position to reuse according to the policy,
+    /// ignoring the quarantine if `force` is true.
+    fn take_freed(&mut self, force: bool) -> Option<usize> {
+        let entry = match self.policy {
+            ReusePolicy::SmallestFirst => {
+                let pos = self.free.pop_first()?;
+                self.record(Undo::Reused {
+                    start: pos,
+                    count: 1,
+                });
+                return Some(pos);
+            }
+            ReusePolicy::Fifo => self.queue.pop_front()?,
+            ReusePolicy::Lifo => self.queue.pop_back()?,
+            ReusePolicy::Quarantine { delay } => {
+                let oldest = self.queue.front()?;
+                if !force && self.events.wrapping_sub(oldest.freed_at) < delay {
+                    return None;
+                }
+                self.queue.pop_front()?
+            }
+        };
+        self.free.remove_range(entry.pos, 1);
+        self.record(Undo::Dequeued(entry));
+        Some(entry.pos)
+    }
+
+    /// Allocate a new id, reusing freed ids according to the [`ReusePolicy`].
     ///
     /// # Panics
     /// If there are no ids available, this will panic.
@@ -86,19 +414,448 @@ impl<T: IntegerIdCounter> IdAllocator<T> {
         }
     }
 
+    /// Allocate `count` consecutive ids,
+    /// returning the first and last ids of the range (inclusive).
+    ///
+    /// This reuses the smallest run of `count` adjacent freed ids if one exists.
+    /// Otherwise, new ids are allocated,
+    /// merged with any freed ids immediately before them.
+    /// Freed ids are only reused by ranges under [`ReusePolicy::SmallestFirst`],
+    /// other policies always allocate new ids.
+    ///
+    /// # Errors
+    /// Returns an error if there is no space for `count` consecutive ids.
+    /// In that case, no ids are allocated.
+    ///
+    /// # Panics
+    /// If `count` is zero.
+    pub fn try_alloc_range(&mut self, count: usize) -> Result<(T, T), IdExhaustedError<T>> {
+        assert!(count > 0, "cannot allocate an empty range");
+        // only the default policy reuses freed ids for ranges,
+        // so don't bother scanning the bitset otherwise
+        let reuse = !self.policy.is_queued();
+        let run = if reuse {
+            self.free.find_run(count)
+        } else {
+            None
+        };
+        let start = if let Some(start) = run {
+            self.free.remove_range(start, count);
+            self.record(Undo::Reused { start, count });
+            start
+        } else {
+            let reused = if reuse {
+                self.free.trailing_run(self.issued)
+            } else {
+                0
+            };
+            if let Err(e) = self.next_id.try_alloc_range(count - reused) {
+                self.exhaustions = self.exhaustions.wrapping_add(1);
+                return Err(e);
+            }
+            let start = self.issued - reused;
+            self.free.remove_range(start, reused);
+            if reused > 0 {
+                self.record(Undo::Reused {
+                    start,
+                    count: reused,
+                });
+            }
+            self.issued += count - reused;
+            start
+        };
+        self.allocated += count;
+        self.allocations = self.allocations.wrapping_add(count);
+        // count each id, like freeing a range does
+        self.events = self.events.wrapping_add(count);
+        Ok((self.id_at(start), self.id_at(start + count - 1)))
+    }
+
+    /// Allocate `count` consecutive ids,
+    /// returning the first and last ids of the range (inclusive).
+    ///
+    /// # Panics
+    /// If there is no space for the ids, or if `count` is zero.
+    /// See [`Self::try_alloc_range`] for a version that returns an error instead.
+    #[track_caller]
+    #[inline]
+    #[must_use]
+    pub fn alloc_range(&mut self, count: usize) -> (T, T) {
+        match self.try_alloc_range(count) {
+            Ok(range) => range,
+            Err(e) => e.panic(),
+        }
+    }
+
     /// Free all existing ids, resetting the allocator.
+    ///
+    /// This invalidates all open [checkpoints](Self::checkpoint).
     #[inline]
     pub fn free_all(&mut self) {
-        self.heap.clear();
-        self.next_id.reset();
+        self.frees = self.frees.wrapping_add(self.allocated);
+        self.clear();
+    }
+
+    /// Discard all ids, without counting them as freed.
+    fn clear(&mut self) {
+        self.checkpoints.invalidate();
+        self.undo.clear();
+        self.free.clear();
+        self.queue.clear();
+        self.issued = 0;
+        self.allocated = 0;
+        self.next_id.set_next_id(self.start);
     }
 
-    /// Free the specified id, making it available
+    /// Free the specified id, making it available for reuse.
     ///
-    /// Used ids will be used in preference to creating new ones.
+    /// Freed ids will be used in preference to creating new ones,
+    /// unless they are quarantined by the [`ReusePolicy`].
+    ///
+    /// Freeing an id which is not currently allocated is a logic error.
+    /// This panics in debug builds, and otherwise leaves the allocator unchanged.
+    /// See [`Self::try_free`] for a version that returns an error instead.
+    #[track_caller]
     #[inline]
     pub fn free(&mut self, id: T) {
-        self.heap
-            .push(core::cmp::Reverse(intid::utils::OrderByInt(id)));
+        if let Err(e) = self.try_free(id) {
+            if cfg!(debug_assertions) {
+                e.panic()
+            }
+        }
+    }
+
+    /// Free the specified id, making it available for reuse,
+    /// or return an error if it is not currently allocated.
+    ///
+    /// # Errors
+    /// Fails if the id was already freed, or if it was never allocated.
+    /// In that case, the allocator is left unchanged.
+    pub fn try_free(&mut self, id: T) -> Result<(), InvalidFreeError<T>> {
+        let Some(pos) = self.allocated_pos(id) else {
+            return Err(InvalidFreeError::never_allocated(id));
+        };
+        if !self.free.insert(pos) {
+            return Err(InvalidFreeError::double_free(id));
+        }
+        self.record(Undo::Freed {
+            start: pos,
+            count: 1,
+        });
+        self.enqueue(pos, 1);
+        self.allocated -= 1;
+        Ok(())
+    }
+
+    /// Free all ids from `first` through `last` (inclusive),
+    /// making them available for reuse.
+    ///
+    /// Freeing an id which is not currently allocated is a logic error.
+    /// This panics in debug builds, and otherwise frees none of the ids.
+    /// See [`Self::try_free_range`] for a version that returns an error instead.
+    #[track_caller]
+    #[inline]
+    pub fn free_range(&mut self, first: T, last: T) {
+        if let Err(e) = self.try_free_range(first, last) {
+            if cfg!(debug_assertions) {
+                e.panic()
+            }
+        }
+    }
+
+    /// Free all ids from `first` through `last` (inclusive),
+    /// or return an error if any of them is not currently allocated.
+    ///
+    /// # Errors
+    /// Fails if any id in the range is not currently allocated,
+    /// returning an error for the first such id.
+    /// In that case, no ids are freed.
+    pub fn try_free_range(&mut self, first: T, last: T) -> Result<(), InvalidFreeError<T>> {
+        if last.to_int() < first.to_int() {
+            return Ok(()); // empty range
+        }
+        let Some(start) = self.allocated_pos(first) else {
+            return Err(InvalidFreeError::never_allocated(first));
+        };
+        let Some(end) = self.allocated_pos(last) else {
+            // the first id which was never allocated
+            let unallocated = IntegerIdCounter::checked_add(
+                self.start,
+                primint::from_usize_wrapping(self.issued),
+            );
+            return Err(InvalidFreeError::never_allocated(
+                unallocated.unwrap_or(last),
+            ));
+        };
+        let count = end - start + 1;
+        if let Some(pos) = self.free.first_in_range(start, count) {
+            return Err(InvalidFreeError::double_free(self.id_at(pos)));
+        }
+        self.free.insert_range(start, count);
+        self.record(Undo::Freed { start, count });
+        self.enqueue(start, count);
+        self.allocated -= count;
+        Ok(())
+    }
+
+    /// Count the frees of the specified range of positions,
+    /// adding them to the reuse queue if the policy needs one.
+    fn enqueue(&mut self, start: usize, count: usize) {
+        self.frees = self.frees.wrapping_add(count);
+        for pos in start..start + count {
+            self.events = self.events.wrapping_add(1);
+            if self.policy.is_queued() {
+                self.queue.push_back(QueuedId {
+                    pos,
+                    freed_at: self.events,
+                });
+            }
+        }
+    }
+
+    /// Create a checkpoint of the allocator's current state,
+    /// so that speculative allocations and frees can be undone by [`Self::rollback_to`].
+    ///
+    /// Checkpoints can be nested, but must be consumed by either [`Self::rollback_to`]
+    /// or [`Self::commit`] in the reverse order they were created.
+    /// While any checkpoint is open, the allocator keeps a log of reused and freed ids,
+    /// so rolling back only takes time proportional to the number of changes.
+    ///
+    /// A checkpoint which is dropped instead stays open,
+    /// for example when returning early with `?`.
+    /// The log then keeps growing with every allocation and free,
+    /// until [`Self::discard_checkpoints`] or [`Self::free_all`] is called.
+    ///
+    /// # Example
+    /// ```
+    /// use intid_allocator::IdAllocator;
+    /// let mut alloc = IdAllocator::<u32>::new();
+    /// let (a, b) = (alloc.alloc(), alloc.alloc());
+    /// alloc.free(a);
+    /// let checkpoint = alloc.checkpoint();
+    /// assert_eq!(alloc.alloc(), a);
+    /// alloc.free(b);
+    /// alloc.rollback_to(checkpoint);
+    /// assert!(!alloc.is_allocated(a));
+    /// assert!(alloc.is_allocated(b));
+    /// ```
+    #[inline]
+    pub fn checkpoint(&self) -> IdAllocatorCheckpoint<T> {
+        IdAllocatorCheckpoint {
+            next_id: self.checkpoints.push(self.next_id.next_id()),
+            issued: self.issued,
+            allocated: self.allocated,
+            undo_len: self.undo.len(),
+            events: self.events,
+        }
+    }
+
+    /// Undo all allocations and frees made since the checkpoint was created.
+    ///
+    /// The checkpoint must have been created by this allocator.
+    /// A checkpoint from a different allocator is not reliably detected,
+    /// and leaves this allocator in an unspecified (but memory safe) state.
+    ///
+    /// # Panics
+    /// If the checkpoint is stale, because a more recent checkpoint is still open,
+    /// or because [`Self::free_all`] was called after it was created.
+    #[track_caller]
+    #[allow(clippy::needless_pass_by_value)] // consumes the checkpoint
+    pub fn rollback_to(&mut self, checkpoint: IdAllocatorCheckpoint<T>) {
+        self.checkpoints.pop(&checkpoint.next_id);
+        self.next_id.restore_next_id(checkpoint.next_id.next_id);
+        // ids issued since the checkpoint are never reused before they are freed,
+        // so undoing the log in reverse clears their bits as well
+        for undo in self.undo.drain(checkpoint.undo_len..).rev() {
+            match undo {
+                Undo::Reused { start, count } => self.free.insert_range(start, count),
+                Undo::Freed { start, count } => {
+                    self.free.remove_range(start, count);
+                    if self.policy.is_queued() {
+                        self.queue.truncate(self.queue.len() - count);
+                    }
+                }
+                Undo::Dequeued(entry) => {
+                    self.free.insert(entry.pos);
+                    // put the entry back where it was taken from
+                    if self.policy == ReusePolicy::Lifo {
+                        self.queue.push_back(entry);
+                    } else {
+                        self.queue.push_front(entry);
+                    }
+                }
+            }
+        }
+        self.issued = checkpoint.issued;
+        self.allocated = checkpoint.allocated;
+        self.events = checkpoint.events;
+    }
+
+    /// Discard the checkpoint, keeping all allocations and frees made since it was created.
+    ///
+    /// If the checkpoint is nested inside another,
+    /// rolling back the outer checkpoint will still undo these changes.
+    ///
+    /// # Panics
+    /// If the checkpoint is stale, just like [`Self::rollback_to`].
+    #[track_caller]
+    #[inline]
+    #[allow(clippy::needless_pass_by_value)] // consumes the checkpoint
+    pub fn commit(&mut self, checkpoint: IdAllocatorCheckpoint<T>) {
+        self.checkpoints.pop(&checkpoint.next_id);
+        if self.checkpoints.is_empty() {
+            self.undo.clear();
+        }
+    }
+
+    /// Discard all open checkpoints, keeping all allocations and frees made since they were created.
+    ///
+    /// This frees the log of changes kept for checkpoints,
+    /// recovering from checkpoints which were dropped without being consumed.
+    /// Rolling back or committing any of the discarded checkpoints afterwards will panic.
+    #[inline]
+    pub fn discard_checkpoints(&mut self) {
+        self.checkpoints.invalidate();
+        self.undo.clear();
+    }
+
+    /// Record a change to the free bitset, if any checkpoints are open.
+    #[inline]
+    fn record(&mut self, undo: Undo) {
+        if !self.checkpoints.is_empty() {
+            self.undo.push(undo);
+        }
+    }
+
+    /// Renumber the allocated ids densely from the start of the allocator,
+    /// discarding all freed ids.
+    ///
+    /// Returns an [`IdRemap`] from each old id to its new value,
+    /// which preserves the relative order of the ids.
+    /// Existing tables can be updated using [`IdRemap::apply_to_map`] and [`IdRemap::apply_to_set`].
+    ///
+    /// This invalidates all open [checkpoints](Self::checkpoint).
+    #[cfg(feature = "idmap")]
+    pub fn compact(&mut self) -> IdRemap<T> {
+        let remap = IdRemap::compacting_sorted(self.start, self.iter_allocated());
+        let live = self.allocated;
+        self.clear();
+        if live > 0 {
+            // the ids were already issued before compaction
+            let _ = self
+                .next_id
+                .try_alloc_range(live)
+                .expect("live ids must fit in the issued range");
+        }
+        self.issued = live;
+        self.allocated = live;
+        remap
+    }
+
+    /// Check if the specified id is currently allocated.
+    #[inline]
+    pub fn is_allocated(&self, id: T) -> bool {
+        self.allocated_pos(id)
+            .is_some_and(|pos| !self.free.contains(pos))
+    }
+
+    /// The number of currently allocated ids.
+    #[inline]
+    pub fn allocated_count(&self) -> usize {
+        self.allocated
+    }
+
+    /// Iterate over all currently allocated ids,
+    /// in order of their integer value.
+    #[inline]
+    pub fn iter_allocated(&self) -> AllocatedIds<'_, T> {
+        AllocatedIds {
+            end: self.issued,
+            pos: 0,
+            remaining: self.allocated,
+            allocator: self,
+        }
+    }
+
+    /// The position of the specified id in the free bitset,
+    /// or `None` if it was never returned by the allocator.
+    #[inline]
+    fn allocated_pos(&self, id: T) -> Option<usize> {
+        let pos =
+            primint::to_usize_checked(primint::checked_sub(id.to_int(), self.start.to_int())?)?;
+        (pos < self.issued).then_some(pos)
+    }
+
+    /// Get the id at the specified position relative to the start.
+    ///
+    /// The position must have been returned by the allocator at some point.
+    #[inline]
+    fn id_at(&self, pos: usize) -> T {
+        IntegerIdCounter::checked_add(self.start, primint::from_usize_wrapping(pos))
+            .expect("previously allocated id must be valid")
+    }
+}
+
+/// An iterator over the currently allocated ids in an [`IdAllocator`].
+///
+/// Created by [`IdAllocator::iter_allocated`].
+pub struct AllocatedIds<'a, T: IntegerIdCounter> {
+    allocator: &'a IdAllocator<T>,
+    pos: usize,
+    end: usize,
+    remaining: usize,
+}
+impl<T: IntegerIdCounter> Iterator for AllocatedIds<'_, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        while self.pos < self.end {
+            let pos = self.pos;
+            self.pos += 1;
+            if !self.allocator.free.contains(pos) {
+                self.remaining -= 1;
+                return Some(self.allocator.id_at(pos));
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+impl<T: IntegerIdCounter> ExactSizeIterator for AllocatedIds<'_, T> {}
+impl<T: IntegerIdCounter> FusedIterator for AllocatedIds<'_, T> {}
+
+#[cfg(test)]
+mod test {
+    use super::IdAllocator;
+
+    #[test]
+    fn dropped_checkpoint_is_discarded() {
+        let mut alloc = IdAllocator::<u32>::new();
+        let outer = alloc.checkpoint();
+        drop(alloc.checkpoint());
+        for _ in 0..4 {
+            let id = alloc.alloc();
+            alloc.free(id);
+        }
+        // the dropped checkpoint keeps the log open
+        assert!(!alloc.undo.is_empty());
+        alloc.discard_checkpoints();
+        assert!(alloc.undo.is_empty());
+        for _ in 0..4 {
+            let id = alloc.alloc();
+            alloc.free(id);
+        }
+        assert!(alloc.undo.is_empty());
+        // new checkpoints work as usual
+        let checkpoint = alloc.checkpoint();
+        let _ = alloc.alloc();
+        alloc.rollback_to(checkpoint);
+        assert!(alloc.undo.is_empty());
+        drop(outer);
     }
 }
@@ -0,0 +1,222 @@
+use crate::bitset::FreeBitset;
+use crate::sync::{AtomicPrimInt, AtomicUsize, Mutex, MutexGuard};
+use crate::{AllocatorStats, IdExhaustedError, InvalidFreeError, UniqueIdAllocatorAtomic};
+use core::sync::atomic::Ordering;
+use intid::{primint, IntegerIdCounter};
+
+/// Allocates integer ids from multiple threads,
+/// with the ability to free unused ids for reuse.
+///
+/// This is the thread-safe counterpart of [`IdAllocator`](crate::IdAllocator),
+/// taking `&self` instead of `&mut self`.
+///
+/// New ids are allocated from a lock-free [`UniqueIdAllocatorAtomic`],
+/// but reusing ids takes a lock.
+/// Freed ids are tracked in a bitset behind a [`std::sync::Mutex`],
+/// which is acquired by every free,
+/// and by allocations whenever freed ids are available.
+/// A program which never frees ids will never lock.
+///
+/// Because of the mutex, this type requires the `std` feature,
+/// and is never lock-free even if [`UniqueIdAllocatorAtomic::is_lock_free`] returns true.
+///
+/// # Reuse policy
+/// Allocation reuses the smallest freed id,
+/// falling back to a new id once no freed ids remain.
+/// This is only guaranteed with respect to frees that happen-before the allocation.
+/// An allocation racing with a concurrent free may allocate a new id
+/// instead of waiting to reuse the freed one.
+///
+/// Like [`UniqueIdAllocatorAtomic`], this only guarantees that allocated ids are unique.
+/// It should not be used as a tool for synchronization.
+pub struct IdAllocatorAtomic<T: IntegerIdCounter>
+where
+    T::Int: AtomicPrimInt,
+{
+    /// The first id returned by the allocator.
+    start: T,
+    next_id: UniqueIdAllocatorAtomic<T>,
+    /// The number of freed ids available for reuse.
+    ///
+    /// Checked before acquiring the lock, to avoid locking when no ids are free.
+    free_count: AtomicUsize,
+    /// A bitset of freed ids, relative to `start`.
+    free: Mutex<FreeBitset>,
+    /// The total number of freed ids which were allocated again, for statistics.
+    reused: AtomicUsize,
+    /// The total number of ids freed, for statistics.
+    frees: AtomicUsize,
+}
+impl<T: IntegerIdCounter> Default for IdAllocatorAtomic<T>
+where
+    T::Int: AtomicPrimInt,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T: IntegerIdCounter> IdAllocatorAtomic<T>
+where
+    T::Int: AtomicPrimInt,
+{
+    /// Create a new allocator, with ids starting at [`T::START`] (usually zero).
+    ///
+    /// [`T::START`]: IntegerIdCounter::START
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_start(T::START)
+    }
+
+    /// Create a new allocator, with ids starting at the specified value.
+    #[inline]
+    pub fn with_start(start: T) -> Self {
+        IdAllocatorAtomic {
+            start,
+            next_id: UniqueIdAllocatorAtomic::with_start(start),
+            free_count: AtomicUsize::new(0),
+            free: Mutex::new(FreeBitset::new()),
+            reused: AtomicUsize::new(0),
+            frees: AtomicUsize::new(0),
+        }
+    }
+
+    /// Allocate a new id, reusing freed ids wherever possible.
+    ///
+    /// See the type-level docs for the exact reuse policy.
+    ///
+    /// # Errors
+    /// If no more ids are available, this will return an error.
+    /// This can only happen if the entire range of the [`IntegerIdCounter`]
+    /// has been allocated, and none have been freed.
+    pub fn try_alloc(&self) -> Result<T, IdExhaustedError<T>> {
+        if self.free_count.load(Ordering::Relaxed) > 0 {
+            if let Some(pos) = self.lock_free().pop_first() {
+                self.free_count.fetch_sub(1, Ordering::Relaxed);
+                self.reused.fetch_add(1, Ordering::Relaxed);
+                return Ok(self.id_at(pos));
+            }
+        }
+        self.next_id.try_alloc()
+    }
+
+    /// Allocate a new id, reusing freed ids wherever possible.
+    ///
+    /// # Panics
+    /// If there are no ids available, this will panic.
+    /// See [`Self::try_alloc`] for a version that returns an error instead.
+    #[track_caller]
+    #[inline]
+    #[must_use]
+    pub fn alloc(&self) -> T {
+        match self.try_alloc() {
+            Ok(id) => id,
+            Err(e) => e.panic(),
+        }
+    }
+
+    /// Free the specified id, making it available for reuse.
+    ///
+    /// Freeing an id which is not currently allocated is a logic error.
+    /// This panics in debug builds, and otherwise leaves the allocator unchanged.
+    /// See [`Self::try_free`] for a version that returns an error instead.
+    ///
+    /// Freeing an id which is still in use by another thread is not detected,
+    /// and will cause the id to be allocated twice.
+    #[track_caller]
+    #[inline]
+    pub fn free(&self, id: T) {
+        if let Err(e) = self.try_free(id) {
+            if cfg!(debug_assertions) {
+                e.panic()
+            }
+        }
+    }
+
+    /// Free the specified id, making it available for reuse,
+    /// or return an error if it is not currently allocated.
+    ///
+    /// # Errors
+    /// Fails if the id was already freed, or if it was never allocated.
+    /// In that case, the allocator is left unchanged.
+    ///
+    /// Like [`Self::free`], this does not detect ids which are still in use by another thread.
+    pub fn try_free(&self, id: T) -> Result<(), InvalidFreeError<T>> {
+        if id.to_int() >= self.next_id.next_int() {
+            return Err(InvalidFreeError::never_allocated(id));
+        }
+        let Some(pos) = primint::checked_sub(id.to_int(), self.start.to_int())
+            .and_then(primint::to_usize_checked)
+        else {
+            return Err(InvalidFreeError::never_allocated(id));
+        };
+        let mut free = self.lock_free();
+        if !free.insert(pos) {
+            return Err(InvalidFreeError::double_free(id));
+        }
+        // incremented while holding the lock,
+        // so the count never exceeds the size of the bitset
+        self.free_count.fetch_add(1, Ordering::Relaxed);
+        self.frees.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Statistics about the allocator.
+    ///
+    /// Allocating new ids is counted without slowing down the lock-free path,
+    /// but the result may be out of date if other threads are concurrently allocating or freeing ids.
+    pub fn stats(&self) -> AllocatorStats {
+        let unique = self.next_id.stats();
+        let free = self.free_count.load(Ordering::Relaxed);
+        let reused = self.reused.load(Ordering::Relaxed);
+        AllocatorStats {
+            live: unique.live.saturating_sub(free),
+            free,
+            high_water_mark: unique.high_water_mark,
+            allocations: unique.allocations.wrapping_add(reused),
+            frees: self.frees.load(Ordering::Relaxed),
+            exhaustions: unique.exhaustions,
+        }
+    }
+
+    /// Check if the specified id is currently allocated.
+    ///
+    /// The result may be immediately out of date
+    /// if other threads are concurrently allocating or freeing ids.
+    pub fn is_allocated(&self, id: T) -> bool {
+        if id.to_int() >= self.next_id.next_int() {
+            return false;
+        }
+        primint::checked_sub(id.to_int(), self.start.to_int())
+            .and_then(primint::to_usize_checked)
+            .is_some_and(|pos| !self.lock_free().contains(pos))
+    }
+
+    #[inline]
+    fn lock_free(&self) -> MutexGuard<'_, FreeBitset> {
+        // the bitset is always left in a consistent state,
+        // so there is no need to propagate poisoning
+        self.free
+            .lock()
+            .unwrap_or_else(crate::sync::PoisonError::into_inner)
+    }
+
+    /// Get the id at the specified position relative to the start.
+    ///
+    /// The position must have been returned by the allocator at some point.
+    #[inline]
+    fn id_at(&self, pos: usize) -> T {
+        IntegerIdCounter::checked_add(self.start, primint::from_usize_wrapping(pos))
+            .expect("previously allocated id must be valid")
+    }
+}
+impl<T: IntegerIdCounter> core::fmt::Debug for IdAllocatorAtomic<T>
+where
+    T::Int: AtomicPrimInt,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("IdAllocatorAtomic")
+            .field("next_id", &self.next_id)
+            .field("free_count", &self.free_count.load(Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,169 @@
+//! Enables serde serialization support for [`IdAllocator`].
+//!
+//! The allocator serializes as a struct containing the start,
+//! the next new id (or `None` if exhausted), and the list of freed ids.
+//!
+//! The [`ReusePolicy`](crate::ReusePolicy) is not serialized,
+//! so a deserialized allocator always reuses the smallest freed id first.
+//! For a queued policy, the order in which ids were freed is lost as well.
+//! Reapply the policy with [`IdAllocator::with_reuse_policy`] after deserializing.
+//!
+//! Since the free bitset takes one bit for each id up to the largest freed one,
+//! deserialization rejects any freed id more than [`u32::MAX`] ids past the start.
+//! This bounds the memory used by the bitset to 512 MiB,
+//! however large the ids in untrusted input are.
+use alloc::vec::Vec;
+use core::fmt::{self, Formatter};
+use core::marker::PhantomData;
+
+use super::IdAllocator;
+use intid::serde::AsInt;
+use intid::{primint, IntegerIdCounter};
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeSeq, SerializeStruct, Serializer};
+
+const FIELDS: &[&str] = &["start", "next_id", "free"];
+
+/// The largest offset from the start of a freed id which is accepted when deserializing.
+const MAX_FREED_OFFSET: u64 = u32::MAX as u64;
+
+impl<T: IntegerIdCounter> Serialize for IdAllocator<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let next_id = primint::from_usize_checked(self.issued)
+            .and_then(|offset| IntegerIdCounter::checked_add(self.start, offset));
+        let mut state = serializer.serialize_struct("IdAllocator", FIELDS.len())?;
+        state.serialize_field("start", &AsInt(self.start))?;
+        state.serialize_field("next_id", &next_id.map(AsInt))?;
+        state.serialize_field("free", &FreeIds(self))?;
+        state.end()
+    }
+}
+
+/// Serializes the freed ids of an allocator as a sequence.
+struct FreeIds<'a, T: IntegerIdCounter>(&'a IdAllocator<T>);
+impl<T: IntegerIdCounter> Serialize for FreeIds<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let allocator = self.0;
+        let mut seq = serializer.serialize_seq(Some(allocator.free.len()))?;
+        for pos in allocator.free.iter() {
+            seq.serialize_element(&AsInt(allocator.id_at(pos)))?;
+        }
+        seq.end()
+    }
+}
+
+/// Reconstruct an allocator from its serialized fields,
+/// rejecting freed ids which were never allocated, are duplicated,
+/// or are past [`MAX_FREED_OFFSET`].
+fn rebuild<T: IntegerIdCounter, E: de::Error>(
+    start: T,
+    next_id: Option<T>,
+    free: Vec<AsInt<T>>,
+) -> Result<IdAllocator<T>, E> {
+    let issued = match next_id {
+        Some(next_id) => primint::checked_sub(next_id.to_int(), start.to_int())
+            .ok_or_else(|| E::custom("next id is before the start"))
+            .map(primint::to_usize_checked)?,
+        // exhausted, so every id through the maximum was allocated
+        None => T::MAX_ID_INT
+            .and_then(|max| primint::checked_sub(max, start.to_int()))
+            .and_then(primint::to_usize_checked)
+            .and_then(|offset| offset.checked_add(1)),
+    };
+    let issued = issued.ok_or_else(|| E::custom("too many ids to track"))?;
+    let mut allocator = IdAllocator::with_issued(start, issued).map_err(E::custom)?;
+    for AsInt(id) in free {
+        let too_far = primint::checked_sub(id.to_int(), start.to_int()).is_some_and(|offset| {
+            primint::checked_cast::<_, u64>(offset).map_or(true, |offset| offset > MAX_FREED_OFFSET)
+        });
+        if too_far {
+            return Err(E::custom(format_args!(
+                "Id {id:?} is too far past the start to track"
+            )));
+        }
+        allocator.try_free(id).map_err(E::custom)?;
+    }
+    Ok(allocator)
+}
+
+enum Field {
+    Start,
+    NextId,
+    Free,
+}
+impl<'de> Deserialize<'de> for Field {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FieldVisitor;
+        impl Visitor<'_> for FieldVisitor {
+            type Value = Field;
+
+            fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+                f.write_str("`start`, `next_id`, or `free`")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Field, E> {
+                match value {
+                    "start" => Ok(Field::Start),
+                    "next_id" => Ok(Field::NextId),
+                    "free" => Ok(Field::Free),
+                    _ => Err(E::unknown_field(value, FIELDS)),
+                }
+            }
+        }
+        deserializer.deserialize_identifier(FieldVisitor)
+    }
+}
+
+struct IdAllocatorVisitor<T: IntegerIdCounter>(PhantomData<IdAllocator<T>>);
+impl<'de, T: IntegerIdCounter> Visitor<'de> for IdAllocatorVisitor<T> {
+    type Value = IdAllocator<T>;
+
+    fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("an IdAllocator")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let AsInt(start) = seq
+            .next_element::<AsInt<T>>()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let next_id = seq
+            .next_element::<Option<AsInt<T>>>()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        let free = seq
+            .next_element::<Vec<AsInt<T>>>()?
+            .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+        rebuild(start, next_id.map(AsInt::into_inner), free)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut start = None;
+        let mut next_id = None;
+        let mut free = None;
+        while let Some(field) = map.next_key::<Field>()? {
+            match field {
+                Field::Start if start.is_none() => {
+                    start = Some(map.next_value::<AsInt<T>>()?.into_inner());
+                }
+                Field::NextId if next_id.is_none() => {
+                    next_id = Some(map.next_value::<Option<AsInt<T>>>()?.map(AsInt::into_inner));
+                }
+                Field::Free if free.is_none() => {
+                    free = Some(map.next_value::<Vec<AsInt<T>>>()?);
+                }
+                Field::Start => return Err(de::Error::duplicate_field("start")),
+                Field::NextId => return Err(de::Error::duplicate_field("next_id")),
+                Field::Free => return Err(de::Error::duplicate_field("free")),
+            }
+        }
+        rebuild(
+            start.ok_or_else(|| de::Error::missing_field("start"))?,
+            next_id.ok_or_else(|| de::Error::missing_field("next_id"))?,
+            free.ok_or_else(|| de::Error::missing_field("free"))?,
+        )
+    }
+}
+impl<'de, T: IntegerIdCounter> Deserialize<'de> for IdAllocator<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct("IdAllocator", FIELDS, IdAllocatorVisitor(PhantomData))
+    }
+}
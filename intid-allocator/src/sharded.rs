@@ -0,0 +1,267 @@
+use core::cell::Cell;
+
+use crate::{AllocatorStats, IdExhaustedError};
+use intid::{primint, IntegerIdCounter};
+
+/// Splits the ids of an [`IntegerIdCounter`] between multiple shards,
+/// which allocate independently without any coordination.
+///
+/// The space of ids from [`T::START`] through the maximum id can be split in two ways:
+/// - [Strided](Self::strided): shard `k` of `n` gets every id where `(id - START) % n == k`.
+/// - [Blocked](Self::blocked): each shard gets a contiguous block of ids.
+///
+/// Each shard allocates from an [`IdShard`] created by [`Self::shard`],
+/// which may live in a different thread or even a different process.
+/// Any id can be mapped back to the shard that allocated it using [`Self::shard_of`].
+///
+/// # Example
+/// ```
+/// use intid_allocator::ShardedIdAllocator;
+/// let sharded = ShardedIdAllocator::<u32>::strided(4);
+/// let shard = sharded.shard(1);
+/// assert_eq!(shard.alloc(), 1);
+/// assert_eq!(shard.alloc(), 5);
+/// assert_eq!(sharded.shard_of(5), Some(1));
+/// ```
+///
+/// [`T::START`]: IntegerIdCounter::START
+#[derive(Clone, Debug)]
+pub struct ShardedIdAllocator<T: IntegerIdCounter> {
+    shard_count: usize,
+    /// The number of shards, as an integer.
+    shards: T::Int,
+    layout: Layout<T::Int>,
+}
+#[derive(Copy, Clone, Debug)]
+enum Layout<I> {
+    Strided,
+    /// Each shard gets `block_size` ids,
+    /// except for the last shard which also gets the remainder.
+    Blocked {
+        block_size: I,
+    },
+}
+impl<T: IntegerIdCounter> ShardedIdAllocator<T> {
+    /// Split the ids into `shards` interleaved shards,
+    /// where shard `k` allocates every id where `(id - START) % shards == k`.
+    ///
+    /// # Panics
+    /// If `shards` is zero, or if there are more shards than ids.
+    #[track_caller]
+    pub fn strided(shards: usize) -> Self {
+        ShardedIdAllocator {
+            shard_count: shards,
+            shards: Self::checked_shards(shards),
+            layout: Layout::Strided,
+        }
+    }
+
+    /// Split the ids into `shards` contiguous blocks of equal size.
+    ///
+    /// If the ids can not be evenly divided,
+    /// the last shard also gets the remaining ids at the end.
+    ///
+    /// # Panics
+    /// If `shards` is zero, or if there are more shards than ids.
+    #[track_caller]
+    pub fn blocked(shards: usize) -> Self {
+        let shards_int = Self::checked_shards(shards);
+        // the number of ids is `span + 1`, which may overflow
+        let span = Self::span();
+        let quotient = primint::checked_div(span, shards_int).unwrap();
+        let rem = primint::checked_rem(span, shards_int).unwrap();
+        let block_size = if primint::checked_add(rem, primint::one()) == Some(shards_int) {
+            // only overflows for a single shard, which has no end anyway
+            primint::checked_add(quotient, primint::one()).unwrap_or(quotient)
+        } else {
+            quotient
+        };
+        ShardedIdAllocator {
+            shard_count: shards,
+            shards: shards_int,
+            layout: Layout::Blocked { block_size },
+        }
+    }
+
+    /// The number of shards.
+    #[inline]
+    pub fn shard_count(&self) -> usize {
+        self.shard_count
+    }
+
+    /// Create an allocator for the shard with the specified index,
+    /// which allocates ids starting from the beginning of the shard.
+    ///
+    /// Creating multiple allocators for the same shard will give out duplicate ids.
+    ///
+    /// # Panics
+    /// If the index is not less than [`Self::shard_count`].
+    #[track_caller]
+    pub fn shard(&self, index: usize) -> IdShard<T> {
+        assert!(
+            index < self.shard_count,
+            "shard index {index} out of bounds for {} shards",
+            self.shard_count
+        );
+        let index_int = primint::from_usize_wrapping::<T::Int>(index);
+        let (offset, stride, len) = match self.layout {
+            Layout::Strided => (index_int, self.shards, None),
+            Layout::Blocked { block_size } => {
+                let offset = primint::checked_mul(index_int, block_size).unwrap();
+                let is_last = index + 1 == self.shard_count;
+                (offset, primint::one(), (!is_last).then_some(block_size))
+            }
+        };
+        let first = IntegerIdCounter::checked_add(T::START, offset)
+            .expect("shard must contain at least one id");
+        let end = len.map(|len| {
+            IntegerIdCounter::checked_add(first, len).expect("block must be followed by another")
+        });
+        IdShard {
+            index,
+            next_id: Cell::new(Some(first)),
+            first,
+            end,
+            stride,
+            allocations: Cell::new(0),
+            exhaustions: Cell::new(0),
+        }
+    }
+
+    /// Determine which shard the specified id belongs to,
+    /// or `None` if it is smaller than [`T::START`].
+    ///
+    /// [`T::START`]: IntegerIdCounter::START
+    pub fn shard_of(&self, id: T) -> Option<usize> {
+        let offset = primint::checked_sub(id.to_int(), T::START_INT)?;
+        let index = match self.layout {
+            Layout::Strided => primint::checked_rem(offset, self.shards)?,
+            Layout::Blocked { block_size } => {
+                let block = primint::checked_div(offset, block_size)?;
+                let last = primint::checked_sub(self.shards, primint::one())?;
+                core::cmp::min(block, last)
+            }
+        };
+        primint::to_usize_checked(index)
+    }
+
+    /// The largest offset of an id from the start.
+    #[inline]
+    fn span() -> T::Int {
+        let max_id = T::MAX_ID_INT.unwrap_or_else(primint::max_value);
+        primint::checked_sub(max_id, T::START_INT).unwrap_or_else(primint::zero)
+    }
+
+    #[track_caller]
+    fn checked_shards(shards: usize) -> T::Int {
+        assert!(shards > 0, "must have at least one shard");
+        primint::from_usize_checked::<T::Int>(shards - 1)
+            .filter(|&last| last <= Self::span())
+            .and_then(|last| primint::checked_add(last, primint::one()))
+            .unwrap_or_else(|| {
+                panic!(
+                    "Too many shards for {}: {shards}",
+                    core::any::type_name::<T>()
+                )
+            })
+    }
+}
+
+/// Allocates unique ids within a single shard of a [`ShardedIdAllocator`].
+///
+/// Ids allocated by different shards never overlap,
+/// so each shard can allocate without coordinating with the others.
+#[derive(Clone, Debug)]
+pub struct IdShard<T: IntegerIdCounter> {
+    index: usize,
+    next_id: Cell<Option<T>>,
+    first: T,
+    /// The exclusive end of the block, or `None` if the shard continues to the maximum id.
+    end: Option<T>,
+    /// The distance between consecutive ids in the shard.
+    stride: T::Int,
+    /// The number of ids allocated, for statistics.
+    allocations: Cell<usize>,
+    /// The number of failed allocations, for statistics.
+    exhaustions: Cell<usize>,
+}
+impl<T: IntegerIdCounter> IdShard<T> {
+    /// The index of this shard.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Attempt to allocate a new id,
+    /// returning an error if there are no more available in the shard.
+    ///
+    /// # Errors
+    /// If all ids in the shard have been allocated.
+    pub fn try_alloc(&self) -> Result<T, IdExhaustedError<T>> {
+        let id = self
+            .next_id
+            .get()
+            .filter(|id| self.end.map_or(true, |end| id.to_int() < end.to_int()))
+            .ok_or_else(|| self.exhausted())?;
+        self.next_id
+            .set(IntegerIdCounter::checked_add(id, self.stride));
+        self.allocations.set(self.allocations.get().wrapping_add(1));
+        Ok(id)
+    }
+
+    /// Allocate a new id, panicking if there are no more available in the shard.
+    ///
+    /// # Panics
+    /// If all ids in the shard have been allocated.
+    /// See [`Self::try_alloc`] for a version that returns an error instead.
+    #[track_caller]
+    #[inline]
+    #[must_use]
+    pub fn alloc(&self) -> T {
+        match self.try_alloc() {
+            Ok(id) => id,
+            Err(e) => e.panic(),
+        }
+    }
+
+    /// The number of ids which can still be allocated by this shard,
+    /// saturating at [`usize::MAX`].
+    pub fn remaining(&self) -> usize {
+        let Some(next) = self.next_id.get() else {
+            return 0;
+        };
+        let last = match self.end {
+            Some(end) => primint::checked_sub(end.to_int(), primint::one()),
+            None => T::MAX_ID_INT,
+        };
+        last.and_then(|last| primint::checked_sub(last, next.to_int()))
+            .and_then(|span| primint::checked_div(span, self.stride))
+            .map_or(0, |count| {
+                primint::to_usize_checked(count).map_or(usize::MAX, |n| n.saturating_add(1))
+            })
+    }
+
+    /// Statistics about the ids allocated by this shard.
+    ///
+    /// Since ids are never freed, every id handed out is counted as live.
+    pub fn stats(&self) -> AllocatorStats {
+        let allocations = self.allocations.get();
+        AllocatorStats {
+            live: allocations,
+            free: 0,
+            high_water_mark: allocations,
+            allocations,
+            frees: 0,
+            exhaustions: self.exhaustions.get(),
+        }
+    }
+
+    #[cold]
+    fn exhausted(&self) -> IdExhaustedError<T> {
+        self.exhaustions.set(self.exhaustions.get().wrapping_add(1));
+        match self.end {
+            Some(end) => IdExhaustedError::bounded(self.first..end),
+            None => IdExhaustedError::new(),
+        }
+    }
+}
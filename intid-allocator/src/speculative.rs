@@ -0,0 +1,218 @@
+use crate::checkpoint::{Checkpoint, CheckpointStack};
+use crate::{AllocatorStats, IdExhaustedError, UniqueIdAllocator};
+use intid::IntegerIdCounter;
+
+/// Allocates unique integer ids,
+/// with checkpoints to undo speculative allocations.
+///
+/// This wraps a [`UniqueIdAllocator`] with a stack of open checkpoints,
+/// so that stale checkpoints can be detected.
+/// Allocators which never roll back should use a plain [`UniqueIdAllocator`] instead.
+///
+/// # Example
+/// ```
+/// use intid_allocator::SpeculativeIdAllocator;
+/// let alloc = SpeculativeIdAllocator::<u32>::new();
+/// let checkpoint = alloc.checkpoint();
+/// assert_eq!(alloc.alloc(), 0);
+/// alloc.rollback_to(checkpoint);
+/// assert_eq!(alloc.alloc(), 0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct SpeculativeIdAllocator<T: IntegerIdCounter> {
+    ids: UniqueIdAllocator<T>,
+    checkpoints: CheckpointStack,
+}
+impl<T: IntegerIdCounter> Default for SpeculativeIdAllocator<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T: IntegerIdCounter> From<UniqueIdAllocator<T>> for SpeculativeIdAllocator<T> {
+    #[inline]
+    fn from(ids: UniqueIdAllocator<T>) -> Self {
+        SpeculativeIdAllocator {
+            ids,
+            checkpoints: CheckpointStack::new(),
+        }
+    }
+}
+impl<T: IntegerIdCounter> SpeculativeIdAllocator<T> {
+    /// Create a new allocator,
+    /// using [`T::START`] as the first id (usually zero).
+    ///
+    /// [`T::START`]: IntegerIdCounter::START
+    #[inline]
+    pub const fn new() -> Self {
+        Self::with_start(T::START)
+    }
+
+    /// Create a new allocator,
+    /// using the specified value as the first id.
+    ///
+    /// See [`UniqueIdAllocator::with_start`] for details.
+    #[inline]
+    pub const fn with_start(start: T) -> Self {
+        SpeculativeIdAllocator {
+            ids: UniqueIdAllocator::with_start(start),
+            checkpoints: CheckpointStack::new(),
+        }
+    }
+
+    /// Create a new allocator,
+    /// which only allocates ids in the window from `start` up to (but excluding) `end`.
+    ///
+    /// See [`UniqueIdAllocator::with_bounds`] for details.
+    #[inline]
+    pub const fn with_bounds(start: T, end: T) -> Self {
+        SpeculativeIdAllocator {
+            ids: UniqueIdAllocator::with_bounds(start, end),
+            checkpoints: CheckpointStack::new(),
+        }
+    }
+
+    /// Return the maximum currently used id,
+    /// or `None` if no ids have been allocated yet.
+    #[inline]
+    pub fn max_used_id(&self) -> Option<T> {
+        self.ids.max_used_id()
+    }
+
+    /// The number of ids which can still be allocated,
+    /// saturating at [`usize::MAX`].
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.ids.remaining()
+    }
+
+    /// Statistics about the allocator.
+    ///
+    /// See [`UniqueIdAllocator::stats`] for details.
+    #[inline]
+    pub fn stats(&self) -> AllocatorStats {
+        self.ids.stats()
+    }
+
+    /// Attempt to allocate a new id,
+    /// panicking if none are available.
+    ///
+    /// # Panics
+    /// This will panic when the range of the underlying [`IntegerIdCounter`] is exhausted.
+    /// See [`Self::try_alloc`] for a version that returns an error instead.
+    #[inline]
+    #[track_caller]
+    pub fn alloc(&self) -> T {
+        self.ids.alloc()
+    }
+
+    /// Attempt to allocate a new id,
+    /// returning an error if there are no more available.
+    ///
+    /// # Errors
+    /// If the range of the underlying [`IntegerIdCounter`]
+    /// or the window given to [`Self::with_bounds`] is exhausted,
+    /// this will return an error.
+    #[inline]
+    pub fn try_alloc(&self) -> Result<T, IdExhaustedError<T>> {
+        self.ids.try_alloc()
+    }
+
+    /// Attempt to allocate `count` consecutive ids,
+    /// returning the first and last ids of the range (inclusive).
+    ///
+    /// See [`UniqueIdAllocator::try_alloc_range`] for details.
+    ///
+    /// # Errors
+    /// Returns an error if fewer than `count` ids remain.
+    /// In that case, no ids are allocated.
+    ///
+    /// # Panics
+    /// If `count` is zero.
+    #[inline]
+    pub fn try_alloc_range(&self, count: usize) -> Result<(T, T), IdExhaustedError<T>> {
+        self.ids.try_alloc_range(count)
+    }
+
+    /// Set the id that will be returned from the [`Self::alloc`] function.
+    ///
+    /// See [`UniqueIdAllocator::set_next_id`] for details.
+    ///
+    /// This invalidates all open [checkpoints](Self::checkpoint).
+    #[inline]
+    pub fn set_next_id(&self, next_id: T) {
+        self.checkpoints.invalidate();
+        self.ids.set_next_id(next_id);
+    }
+
+    /// Reset the allocator to a pristine state,
+    /// beginning allocations all over again.
+    ///
+    /// See [`UniqueIdAllocator::reset`] for details.
+    ///
+    /// This invalidates all open [checkpoints](Self::checkpoint).
+    #[inline]
+    pub fn reset(&self) {
+        self.checkpoints.invalidate();
+        self.ids.reset();
+    }
+
+    /// Create a checkpoint of the allocator's current state,
+    /// so that speculative allocations can be undone by [`Self::rollback_to`].
+    ///
+    /// Checkpoints can be nested, but must be consumed by either [`Self::rollback_to`]
+    /// or [`Self::commit`] in the reverse order they were created.
+    /// A checkpoint which is dropped instead stays open,
+    /// so any checkpoints created afterwards can still be consumed,
+    /// but any enclosing ones become stale until [`Self::discard_checkpoints`] is called.
+    #[inline]
+    pub fn checkpoint(&self) -> Checkpoint<T> {
+        self.checkpoints.push(self.ids.next_id())
+    }
+
+    /// Undo all allocations made since the checkpoint was created.
+    ///
+    /// The checkpoint must have been created by this allocator.
+    /// A checkpoint from a different allocator is not reliably detected,
+    /// and restores its state into this one.
+    ///
+    /// # Panics
+    /// If the checkpoint is stale, because a more recent checkpoint is still open,
+    /// or because the allocator was [reset](Self::reset) after it was created.
+    #[track_caller]
+    #[inline]
+    #[allow(clippy::needless_pass_by_value)] // consumes the checkpoint
+    pub fn rollback_to(&self, checkpoint: Checkpoint<T>) {
+        self.checkpoints.pop(&checkpoint);
+        self.ids.restore_next_id(checkpoint.next_id);
+    }
+
+    /// Discard the checkpoint, keeping all allocations made since it was created.
+    ///
+    /// If the checkpoint is nested inside another,
+    /// rolling back the outer checkpoint will still undo these allocations.
+    ///
+    /// # Panics
+    /// If the checkpoint is stale, just like [`Self::rollback_to`].
+    #[track_caller]
+    #[inline]
+    #[allow(clippy::needless_pass_by_value)] // consumes the checkpoint
+    pub fn commit(&self, checkpoint: Checkpoint<T>) {
+        self.checkpoints.pop(&checkpoint);
+    }
+
+    /// Discard all open checkpoints, keeping all allocations made since they were created.
+    ///
+    /// This recovers from checkpoints which were dropped without being consumed,
+    /// for example by returning early with `?`.
+    /// Rolling back or committing any of the discarded checkpoints afterwards will panic.
+    #[inline]
+    pub fn discard_checkpoints(&self) {
+        self.checkpoints.invalidate();
+    }
+
+    /// Discard all checkpoints, returning the underlying allocator.
+    #[inline]
+    pub fn into_inner(self) -> UniqueIdAllocator<T> {
+        self.ids
+    }
+}
@@ -0,0 +1,32 @@
+/// A snapshot of the statistics of an allocator,
+/// useful for sizing lookup tables and reporting memory usage.
+///
+/// Returned by the `stats` method of each allocator.
+/// The totals are cumulative since the allocator was created,
+/// and are not reduced by resetting the allocator or rolling back a checkpoint.
+/// The exception is [`UniqueIdAllocator`](crate::UniqueIdAllocator),
+/// which keeps no counters and computes its statistics from the next id.
+/// The other fields describe the current state of the allocator.
+///
+/// The statistics of the atomic allocators are only approximate,
+/// since other threads may be concurrently allocating and freeing ids.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub struct AllocatorStats {
+    /// The number of ids which are currently allocated.
+    pub live: usize,
+    /// The number of freed ids which are waiting to be reused.
+    pub free: usize,
+    /// The number of distinct ids handed out since the allocator was created or reset,
+    /// including ids which have since been freed.
+    ///
+    /// This is one more than the distance from the start of the allocator to the largest id it has returned.
+    pub high_water_mark: usize,
+    /// The total number of ids which have been allocated,
+    /// including freed ids which were allocated again.
+    pub allocations: usize,
+    /// The total number of ids which have been freed.
+    pub frees: usize,
+    /// The number of times allocation failed because no ids were available.
+    pub exhaustions: usize,
+}
@@ -0,0 +1,436 @@
+//! Synchronization primitives, which are replaced by their [loom] equivalents
+//! when testing with `--cfg loom`.
+//!
+//! Atomic integers come from the [atomic] crate by default,
+//! or from [portable-atomic] if the `portable-atomic` feature is enabled.
+//!
+//! [loom]: https://docs.rs/loom/
+//! [atomic]: https://docs.rs/atomic/
+//! [portable-atomic]: https://docs.rs/portable-atomic/
+
+use intid::primint::UnsignedPrimInt;
+
+#[cfg(all(not(loom), not(feature = "portable-atomic")))]
+pub(crate) use core::sync::atomic::AtomicUsize;
+#[cfg(loom)]
+pub(crate) use loom::sync::atomic::AtomicUsize;
+#[cfg(loom)]
+pub(crate) use loom::sync::{Mutex, MutexGuard};
+#[cfg(all(not(loom), feature = "portable-atomic"))]
+pub(crate) use portable_atomic::AtomicUsize;
+#[cfg(feature = "std")]
+pub(crate) use std::sync::PoisonError;
+#[cfg(all(feature = "std", not(loom)))]
+pub(crate) use std::sync::{Mutex, MutexGuard};
+
+#[cfg(all(not(loom), not(feature = "portable-atomic")))]
+use self::atomic_int as backend;
+#[cfg(loom)]
+use self::loom_int as backend;
+#[cfg(all(not(loom), feature = "portable-atomic"))]
+use self::portable_int as backend;
+
+pub(crate) use self::backend::AtomicInt;
+
+/// An unsigned integer type which can be used as the counter of the atomic allocators.
+///
+/// This is implemented for every unsigned primitive integer,
+/// and is only needed as a bound by code which is generic over the id type.
+/// This trait is sealed, and can not be implemented outside this crate.
+pub trait AtomicPrimInt: UnsignedPrimInt + backend::AtomicOps {}
+impl<I: UnsignedPrimInt + backend::AtomicOps> AtomicPrimInt for I {}
+
+/// An integer constant, used to create atomics in a `const fn`.
+#[cfg(not(loom))]
+pub trait ConstInt<I> {
+    const VALUE: I;
+}
+
+/// Creates an atomic of type `A` in a constant.
+#[cfg(all(not(loom), feature = "portable-atomic"))]
+pub trait ConstAtomic<A> {
+    const ATOMIC: A;
+}
+
+/// Implements [`AtomicInt`] using [`atomic::Atomic`].
+#[cfg(all(not(loom), not(feature = "portable-atomic")))]
+mod atomic_int {
+    use atomic::Atomic;
+    use core::fmt::{self, Debug, Formatter};
+    use core::sync::atomic::Ordering;
+    use intid::primint::UnsignedPrimInt;
+
+    /// The operations which [`Atomic`] only implements for concrete integer types.
+    pub trait AtomicOps: UnsignedPrimInt {
+        /// Atomically add to the integer, returning the previous value.
+        fn fetch_add(atomic: &Atomic<Self>, val: Self, order: Ordering) -> Self;
+    }
+    macro_rules! impl_atomic_ops {
+        ($($int:ty),*) => {$(
+            impl AtomicOps for $int {
+                #[inline]
+                fn fetch_add(atomic: &Atomic<Self>, val: Self, order: Ordering) -> Self {
+                    atomic.fetch_add(val, order)
+                }
+            }
+        )*};
+    }
+    impl_atomic_ops!(u8, u16, u32, u64, u128, usize);
+
+    /// An atomic unsigned integer of any size.
+    pub struct AtomicInt<I: AtomicOps>(Atomic<I>);
+    impl<I: AtomicOps> AtomicInt<I> {
+        #[inline]
+        pub const fn new(val: I) -> Self {
+            AtomicInt(Atomic::new(val))
+        }
+
+        /// Create an atomic holding the value of `C`.
+        #[inline]
+        pub const fn new_const<C: super::ConstInt<I>>() -> Self {
+            Self::new(C::VALUE)
+        }
+
+        /// Create an atomic holding the specified value in a `const fn`.
+        #[inline]
+        #[cfg(feature = "atomic")] // only needed by `with_start_const`
+        pub const fn new_in_const(val: I) -> Self {
+            Self::new(val)
+        }
+
+        #[inline]
+        pub fn load(&self, order: Ordering) -> I {
+            self.0.load(order)
+        }
+
+        #[inline]
+        pub fn store(&self, val: I, order: Ordering) {
+            self.0.store(val, order);
+        }
+
+        #[inline]
+        pub fn swap(&self, val: I, order: Ordering) -> I {
+            self.0.swap(val, order)
+        }
+
+        #[inline]
+        pub fn fetch_update(
+            &self,
+            set_order: Ordering,
+            fetch_order: Ordering,
+            func: impl FnMut(I) -> Option<I>,
+        ) -> Result<I, I> {
+            self.0.fetch_update(set_order, fetch_order, func)
+        }
+
+        /// Atomically add to the integer, returning the previous value.
+        ///
+        /// Overflow wraps around.
+        #[inline]
+        pub fn fetch_add(&self, val: I, order: Ordering) -> I {
+            I::fetch_add(&self.0, val, order)
+        }
+
+        /// Check if operations are implemented without locks.
+        #[inline]
+        pub fn is_lock_free() -> bool {
+            Atomic::<I>::is_lock_free()
+        }
+    }
+    impl<I: AtomicOps> Debug for AtomicInt<I> {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            Debug::fmt(&self.0, f)
+        }
+    }
+}
+
+/// Implements [`AtomicInt`] using the loom atomic of the same width,
+/// so that wraparound behaves just like the real atomics.
+///
+/// Loom has no 128-bit atomics, so `u128` ids are not supported.
+#[cfg(loom)]
+mod loom_int {
+    use core::fmt::{self, Debug, Formatter};
+    use core::sync::atomic::Ordering;
+    use intid::primint::{self, UnsignedPrimInt};
+    use loom::sync::atomic::{AtomicU16, AtomicU32, AtomicU64, AtomicU8, AtomicUsize};
+
+    /// Maps an integer to the loom atomic of the same width.
+    pub trait AtomicOps: UnsignedPrimInt {
+        /// The loom atomic type.
+        type Atomic: Debug + Send + Sync;
+
+        fn new(val: Self) -> Self::Atomic;
+        fn load(atomic: &Self::Atomic, order: Ordering) -> Self;
+        fn store(atomic: &Self::Atomic, val: Self, order: Ordering);
+        fn swap(atomic: &Self::Atomic, val: Self, order: Ordering) -> Self;
+        fn fetch_update(
+            atomic: &Self::Atomic,
+            set_order: Ordering,
+            fetch_order: Ordering,
+            func: impl FnMut(Self) -> Option<Self>,
+        ) -> Result<Self, Self>;
+        fn fetch_add(atomic: &Self::Atomic, val: Self, order: Ordering) -> Self;
+    }
+    macro_rules! impl_atomic_ops {
+        ($($int:ty => $atomic:ty),*) => {$(
+            impl AtomicOps for $int {
+                type Atomic = $atomic;
+
+                fn new(val: Self) -> $atomic {
+                    <$atomic>::new(val)
+                }
+                fn load(atomic: &$atomic, order: Ordering) -> Self {
+                    atomic.load(order)
+                }
+                fn store(atomic: &$atomic, val: Self, order: Ordering) {
+                    atomic.store(val, order);
+                }
+                fn swap(atomic: &$atomic, val: Self, order: Ordering) -> Self {
+                    atomic.swap(val, order)
+                }
+                fn fetch_update(
+                    atomic: &$atomic,
+                    set_order: Ordering,
+                    fetch_order: Ordering,
+                    func: impl FnMut(Self) -> Option<Self>,
+                ) -> Result<Self, Self> {
+                    atomic.fetch_update(set_order, fetch_order, func)
+                }
+                fn fetch_add(atomic: &$atomic, val: Self, order: Ordering) -> Self {
+                    atomic.fetch_add(val, order)
+                }
+            }
+        )*};
+    }
+    impl_atomic_ops!(
+        u8 => AtomicU8,
+        u16 => AtomicU16,
+        u32 => AtomicU32,
+        u64 => AtomicU64,
+        usize => AtomicUsize
+    );
+
+    pub struct AtomicInt<I: AtomicOps>(I::Atomic);
+    impl<I: AtomicOps> AtomicInt<I> {
+        pub fn new(val: I) -> Self {
+            AtomicInt(I::new(val))
+        }
+
+        pub fn load(&self, order: Ordering) -> I {
+            I::load(&self.0, order)
+        }
+
+        pub fn store(&self, val: I, order: Ordering) {
+            I::store(&self.0, val, order);
+        }
+
+        pub fn swap(&self, val: I, order: Ordering) -> I {
+            I::swap(&self.0, val, order)
+        }
+
+        pub fn fetch_update(
+            &self,
+            set_order: Ordering,
+            fetch_order: Ordering,
+            func: impl FnMut(I) -> Option<I>,
+        ) -> Result<I, I> {
+            I::fetch_update(&self.0, set_order, fetch_order, func)
+        }
+
+        pub fn fetch_add(&self, val: I, order: Ordering) -> I {
+            let old = I::fetch_add(&self.0, val, order);
+            // the fast path relies on this never happening
+            assert!(
+                primint::checked_add(old, val).is_some(),
+                "fetch_add overflowed"
+            );
+            old
+        }
+
+        pub fn is_lock_free() -> bool {
+            true
+        }
+    }
+    impl<I: AtomicOps> Debug for AtomicInt<I> {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            Debug::fmt(&self.0, f)
+        }
+    }
+}
+
+/// Implements [`AtomicInt`] using the [`portable_atomic`] type of the same width,
+/// which is lock-free wherever the hardware allows
+/// and falls back to a lock or critical section elsewhere.
+#[cfg(all(not(loom), feature = "portable-atomic"))]
+mod portable_int {
+    use super::{ConstAtomic, ConstInt};
+    use core::fmt::{self, Debug, Formatter};
+    use core::marker::PhantomData;
+    #[cfg(feature = "atomic")]
+    use core::mem::ManuallyDrop;
+    use core::sync::atomic::Ordering;
+    use intid::primint::UnsignedPrimInt;
+    use portable_atomic::{AtomicU128, AtomicU16, AtomicU32, AtomicU64, AtomicU8, AtomicUsize};
+
+    /// Maps an integer to the portable atomic of the same width.
+    pub trait AtomicOps: UnsignedPrimInt {
+        /// The portable atomic type.
+        type Atomic: Debug + Send + Sync;
+        /// Creates the atomic from a constant, since trait methods can not be `const fn`.
+        type Const<C: ConstInt<Self>>: ConstAtomic<Self::Atomic>;
+
+        fn new(val: Self) -> Self::Atomic;
+        fn load(atomic: &Self::Atomic, order: Ordering) -> Self;
+        fn store(atomic: &Self::Atomic, val: Self, order: Ordering);
+        fn swap(atomic: &Self::Atomic, val: Self, order: Ordering) -> Self;
+        fn fetch_update(
+            atomic: &Self::Atomic,
+            set_order: Ordering,
+            fetch_order: Ordering,
+            func: impl FnMut(Self) -> Option<Self>,
+        ) -> Result<Self, Self>;
+        fn fetch_add(atomic: &Self::Atomic, val: Self, order: Ordering) -> Self;
+        fn is_lock_free() -> bool;
+    }
+
+    /// The atomic holding the value of `C`.
+    pub struct ConstOf<C>(PhantomData<C>);
+
+    macro_rules! impl_atomic_ops {
+        ($($int:ty => $atomic:ty),*) => {$(
+            impl AtomicOps for $int {
+                type Atomic = $atomic;
+                type Const<C: ConstInt<Self>> = ConstOf<C>;
+
+                #[inline]
+                fn new(val: Self) -> $atomic {
+                    <$atomic>::new(val)
+                }
+                #[inline]
+                fn load(atomic: &$atomic, order: Ordering) -> Self {
+                    atomic.load(order)
+                }
+                #[inline]
+                fn store(atomic: &$atomic, val: Self, order: Ordering) {
+                    atomic.store(val, order);
+                }
+                #[inline]
+                fn swap(atomic: &$atomic, val: Self, order: Ordering) -> Self {
+                    atomic.swap(val, order)
+                }
+                #[inline]
+                fn fetch_update(
+                    atomic: &$atomic,
+                    set_order: Ordering,
+                    fetch_order: Ordering,
+                    func: impl FnMut(Self) -> Option<Self>,
+                ) -> Result<Self, Self> {
+                    atomic.fetch_update(set_order, fetch_order, func)
+                }
+                #[inline]
+                fn fetch_add(atomic: &$atomic, val: Self, order: Ordering) -> Self {
+                    atomic.fetch_add(val, order)
+                }
+                #[inline]
+                fn is_lock_free() -> bool {
+                    <$atomic>::is_lock_free()
+                }
+            }
+            impl<C: ConstInt<$int>> ConstAtomic<$atomic> for ConstOf<C> {
+                #[allow(clippy::declare_interior_mutable_const)] // only used to initialize
+                const ATOMIC: $atomic = <$atomic>::new(C::VALUE);
+            }
+        )*};
+    }
+    impl_atomic_ops!(
+        u8 => AtomicU8,
+        u16 => AtomicU16,
+        u32 => AtomicU32,
+        u64 => AtomicU64,
+        u128 => AtomicU128,
+        usize => AtomicUsize
+    );
+
+    /// Reinterprets an integer as the portable atomic of the same width.
+    ///
+    /// Trait methods can not be `const fn`,
+    /// so this is the only way to create an atomic from a runtime value in a `const fn`.
+    #[cfg(feature = "atomic")]
+    union IntToAtomic<I: AtomicOps> {
+        int: I,
+        atomic: ManuallyDrop<I::Atomic>,
+    }
+
+    /// An atomic unsigned integer of any size.
+    pub struct AtomicInt<I: AtomicOps>(I::Atomic);
+    impl<I: AtomicOps> AtomicInt<I> {
+        #[inline]
+        pub fn new(val: I) -> Self {
+            AtomicInt(I::new(val))
+        }
+
+        /// Create an atomic holding the specified value in a `const fn`.
+        #[inline]
+        #[allow(unsafe_code)]
+        #[cfg(feature = "atomic")] // only needed by `with_start_const`
+        pub const fn new_in_const(val: I) -> Self {
+            assert!(core::mem::size_of::<I>() == core::mem::size_of::<I::Atomic>());
+            // SAFETY: Each portable atomic has the same in-memory representation as its integer,
+            // and `AtomicOps` is only implemented for the integer of the same width as `I::Atomic`.
+            // The sizes were checked above, so every byte of the atomic is initialized.
+            let atomic = unsafe { IntToAtomic::<I> { int: val }.atomic };
+            AtomicInt(ManuallyDrop::into_inner(atomic))
+        }
+
+        /// Create an atomic holding the value of `C`.
+        #[inline]
+        pub const fn new_const<C: ConstInt<I>>() -> Self {
+            AtomicInt(<I::Const<C> as ConstAtomic<I::Atomic>>::ATOMIC)
+        }
+
+        #[inline]
+        pub fn load(&self, order: Ordering) -> I {
+            I::load(&self.0, order)
+        }
+
+        #[inline]
+        pub fn store(&self, val: I, order: Ordering) {
+            I::store(&self.0, val, order);
+        }
+
+        #[inline]
+        pub fn swap(&self, val: I, order: Ordering) -> I {
+            I::swap(&self.0, val, order)
+        }
+
+        #[inline]
+        pub fn fetch_update(
+            &self,
+            set_order: Ordering,
+            fetch_order: Ordering,
+            func: impl FnMut(I) -> Option<I>,
+        ) -> Result<I, I> {
+            I::fetch_update(&self.0, set_order, fetch_order, func)
+        }
+
+        /// Atomically add to the integer, returning the previous value.
+        ///
+        /// Overflow wraps around.
+        #[inline]
+        pub fn fetch_add(&self, val: I, order: Ordering) -> I {
+            I::fetch_add(&self.0, val, order)
+        }
+
+        /// Check if operations are implemented without locks.
+        #[inline]
+        pub fn is_lock_free() -> bool {
+            I::is_lock_free()
+        }
+    }
+    impl<I: AtomicOps> Debug for AtomicInt<I> {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            Debug::fmt(&self.0, f)
+        }
+    }
+}
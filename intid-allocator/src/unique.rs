@@ -1,9 +1,11 @@
-use crate::IdExhaustedError;
+use crate::{AllocatorStats, IdExhaustedError};
 use core::cell::Cell;
 use intid::{primint, IntegerIdCounter};
 
-#[cfg(feature = "atomic")]
+#[cfg(any(feature = "atomic", feature = "portable-atomic"))]
 pub mod atomic;
+#[cfg(feature = "serde")]
+mod serde;
 
 /// Allocates unique integer ids.
 ///
@@ -11,9 +13,16 @@ pub mod atomic;
 /// unless [`Self::reset`] is called.
 ///
 /// Ids start at [`IntegerIdCounter::START`] by default, counting upwards from there.
+/// Use [`Self::with_bounds`] to restrict allocation to a window of ids.
+///
+/// Use a [`SpeculativeIdAllocator`](crate::SpeculativeIdAllocator)
+/// to undo speculative allocations with checkpoints.
 #[derive(Clone, Debug)]
 pub struct UniqueIdAllocator<T: IntegerIdCounter> {
     next_id: Cell<Option<T>>,
+    /// The start and (exclusive) end of the window of ids,
+    /// or `None` if every id after the start can be allocated.
+    bounds: Option<(T, T)>,
 }
 impl<T: IntegerIdCounter> Default for UniqueIdAllocator<T> {
     fn default() -> Self {
@@ -47,6 +56,68 @@ impl<T: IntegerIdCounter> UniqueIdAllocator<T> {
     pub const fn with_start(start: T) -> Self {
         UniqueIdAllocator {
             next_id: Cell::new(Some(start)),
+            bounds: None,
+        }
+    }
+
+    /// Create a new allocator,
+    /// which only allocates ids in the window from `start` up to (but excluding) `end`.
+    ///
+    /// Once the window is exhausted, [`Self::try_alloc`] returns an [`IdExhaustedError`]
+    /// which reports the bounds of the window.
+    /// If `end` is not greater than `start`, no ids can be allocated.
+    ///
+    /// The bounds are not preserved by serialization.
+    #[inline]
+    pub const fn with_bounds(start: T, end: T) -> Self {
+        UniqueIdAllocator {
+            next_id: Cell::new(Some(start)),
+            bounds: Some((start, end)),
+        }
+    }
+
+    /// The number of ids which can still be allocated,
+    /// saturating at [`usize::MAX`].
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.next_id.get().map_or(0, |next| {
+            remaining_ids(next.to_int(), self.bounds.map(|(_, end)| end))
+        })
+    }
+
+    /// Statistics about the allocator.
+    ///
+    /// The allocator keeps no counters, so the statistics are computed from the next id.
+    /// Since ids are never freed, every id before the next one is counted as live,
+    /// and as an allocation.
+    /// This counts from the start of the window given to [`Self::with_bounds`],
+    /// or from [`T::START`] if there is none,
+    /// so ids skipped by [`Self::with_start`] or [`Self::set_next_id`] are included.
+    /// Unlike the other allocators, the total allocations restart from zero
+    /// when the allocator is reset or rolled back to a checkpoint.
+    /// Failed allocations are not counted, so [`AllocatorStats::exhaustions`] is always zero.
+    ///
+    /// [`T::START`]: IntegerIdCounter::START
+    pub fn stats(&self) -> AllocatorStats {
+        let issued = self.issued();
+        AllocatorStats {
+            live: issued,
+            free: 0,
+            high_water_mark: issued,
+            allocations: issued,
+            frees: 0,
+            exhaustions: 0,
+        }
+    }
+
+    /// The number of ids from the start of the window up to the next id.
+    fn issued(&self) -> usize {
+        let start = self.start().to_int();
+        let end = self.bounds.map(|(_, end)| end);
+        match self.next_id.get() {
+            Some(next) => primint::checked_sub(next.to_int(), start)
+                .map_or(0, |n| primint::to_usize_checked(n).unwrap_or(usize::MAX)),
+            None => remaining_ids(start, end),
         }
     }
 
@@ -69,16 +140,46 @@ impl<T: IntegerIdCounter> UniqueIdAllocator<T> {
     /// returning an error if there are no more available.
     ///
     /// # Errors
-    /// If the range of the underlying [`IntegerIdCounter`] is exhausted,
+    /// If the range of the underlying [`IntegerIdCounter`]
+    /// or the window given to [`Self::with_bounds`] is exhausted,
     /// this will return an error.
     #[inline]
     pub fn try_alloc(&self) -> Result<T, IdExhaustedError<T>> {
-        let old_id = self.next_id.get().ok_or_else(IdExhaustedError::new)?;
+        let old_id = self
+            .next_id
+            .get()
+            .filter(|&id| self.is_below_end(id))
+            .ok_or_else(|| self.exhausted())?;
         self.next_id
             .set(IntegerIdCounter::checked_add(old_id, primint::one()));
         Ok(old_id)
     }
 
+    /// Attempt to allocate `count` consecutive ids,
+    /// returning the first and last ids of the range (inclusive).
+    ///
+    /// This is equivalent to calling [`Self::try_alloc`] `count` times,
+    /// but only updates the counter once.
+    ///
+    /// # Errors
+    /// Returns an error if fewer than `count` ids remain.
+    /// In that case, no ids are allocated.
+    ///
+    /// # Panics
+    /// If `count` is zero.
+    #[inline]
+    pub fn try_alloc_range(&self, count: usize) -> Result<(T, T), IdExhaustedError<T>> {
+        assert!(count > 0, "cannot allocate an empty range");
+        let first = self.next_id.get().ok_or_else(|| self.exhausted())?;
+        let last = primint::from_usize_checked(count - 1)
+            .and_then(|offset| IntegerIdCounter::checked_add(first, offset))
+            .filter(|&last| self.is_below_end(last))
+            .ok_or_else(|| self.exhausted())?;
+        self.next_id
+            .set(IntegerIdCounter::checked_add(last, primint::one()));
+        Ok((first, last))
+    }
+
     /// Set the id that will be returned from the [`Self::alloc`] function.
     ///
     /// Like a call to [`Self::reset`], this may cause the counter to unexpectedly jump backwards.
@@ -89,6 +190,19 @@ impl<T: IntegerIdCounter> UniqueIdAllocator<T> {
         self.next_id.set(Some(next_id));
     }
 
+    /// The next id to be returned, or `None` if the counter has overflowed.
+    #[inline]
+    pub(crate) fn next_id(&self) -> Option<T> {
+        self.next_id.get()
+    }
+
+    /// Restore the counter to a value previously returned by [`Self::next_id`],
+    /// undoing all allocations made since.
+    #[inline]
+    pub(crate) fn restore_next_id(&self, next_id: Option<T>) {
+        self.next_id.set(next_id);
+    }
+
     /// Reset the allocator to a pristine state,
     /// beginning allocations all over again.
     ///
@@ -101,8 +215,51 @@ impl<T: IntegerIdCounter> UniqueIdAllocator<T> {
     ///
     /// See also the [`Self::set_next_id`] function,
     /// which can cause the counter to jump forwards in addition to jumping backwards.
+    ///
+    /// If the allocator was created by [`Self::with_bounds`],
+    /// this restarts at the start of the window instead of [`T::START`].
+    ///
+    /// [`T::START`]: IntegerIdCounter::START
     #[inline]
     pub fn reset(&self) {
-        self.set_next_id(T::START);
+        self.set_next_id(self.start());
     }
+
+    /// The start of the window of ids, or [`T::START`] if there is none.
+    ///
+    /// [`T::START`]: IntegerIdCounter::START
+    #[inline]
+    fn start(&self) -> T {
+        self.bounds.map_or(T::START, |(start, _)| start)
+    }
+
+    /// Check that the id is before the end of the window, if any.
+    #[inline]
+    fn is_below_end(&self, id: T) -> bool {
+        self.bounds
+            .map_or(true, |(_, end)| id.to_int() < end.to_int())
+    }
+
+    #[cold]
+    fn exhausted(&self) -> IdExhaustedError<T> {
+        match self.bounds {
+            Some((start, end)) => IdExhaustedError::bounded(start..end),
+            None => IdExhaustedError::new(),
+        }
+    }
+}
+
+/// The number of ids from `next` up to (but excluding) `end`,
+/// or through the maximum id if there is no end.
+///
+/// Saturates at [`usize::MAX`].
+pub(crate) fn remaining_ids<T: IntegerIdCounter>(next: T::Int, end: Option<T>) -> usize {
+    let last = match end {
+        Some(end) => primint::checked_sub(end.to_int(), primint::one()),
+        None => T::MAX_ID_INT,
+    };
+    last.and_then(|last| primint::checked_sub(last, next))
+        .map_or(0, |offset| {
+            primint::to_usize_checked(offset).map_or(usize::MAX, |n| n.saturating_add(1))
+        })
 }
@@ -1,4 +1,8 @@
-use crate::IdExhaustedError;
+#[cfg(not(loom))]
+use crate::sync::ConstInt;
+use crate::sync::{AtomicInt, AtomicPrimInt, AtomicUsize};
+use crate::unique::remaining_ids;
+use crate::{AllocatorStats, IdExhaustedError};
 #[allow(unused_imports)] // used by docs
 use crate::{IntegerId, UniqueIdAllocator};
 use core::marker::PhantomData;
@@ -25,7 +29,10 @@ use intid::{primint, IntegerIdCounter};
 /// An incorrect implementation of [`IntegerId`] or [`IntegerIdCounter`] can break some or all of these guarantees,
 /// but will not be able to trigger undefined behavior.
 #[derive(Debug)]
-pub struct UniqueIdAllocatorAtomic<T: IntegerIdCounter> {
+pub struct UniqueIdAllocatorAtomic<T: IntegerIdCounter>
+where
+    T::Int: AtomicPrimInt,
+{
     // This could be improved by adding a T: bytemuck::NoUninit bound to IntegerIdCounter
     // It would allow us to avoid potentially costly conversions T <-> T::Int
     // and avoid the need for a separate with_start_const function
@@ -33,39 +40,128 @@ pub struct UniqueIdAllocatorAtomic<T: IntegerIdCounter> {
     // The downside is it would add bytemuck as a required dependency,
     // and require more work in the intid-derive (would we derive nouninit or would bytemuck?)
     // As another alternative, we could switch to crossbeam-utils
-    next_id: atomic::Atomic<T::Int>,
+    next_id: AtomicInt<T::Int>,
+    /// The start and (exclusive) end of the window of ids,
+    /// or `None` if every id after the start can be allocated.
+    bounds: Option<(T, T)>,
+    /// The integer value of the id the allocator started from, or was last reset to.
+    origin: AtomicInt<T::Int>,
+    /// The number of ids handed out before the last reset.
+    ///
+    /// Allocations since the reset are computed from the counter,
+    /// to avoid an extra atomic operation on the fast path.
+    issued_before_reset: AtomicUsize,
+    exhaustions: AtomicUsize,
     marker: PhantomData<T>,
 }
-impl<T: IntegerIdCounter> Default for UniqueIdAllocatorAtomic<T> {
+/// The integer value of [`T::START`](IntegerIdCounter::START),
+/// used to create the counter in a `const fn`.
+#[cfg(not(loom))]
+struct StartInt<T>(PhantomData<T>);
+#[cfg(not(loom))]
+impl<T: IntegerIdCounter> ConstInt<T::Int> for StartInt<T> {
+    const VALUE: T::Int = T::START_INT;
+}
+impl<T: IntegerIdCounter> Default for UniqueIdAllocatorAtomic<T>
+where
+    T::Int: AtomicPrimInt,
+{
     fn default() -> Self {
         Self::new()
     }
 }
-impl<T: IntegerIdCounter> UniqueIdAllocatorAtomic<T> {
+impl<T: IntegerIdCounter> UniqueIdAllocatorAtomic<T>
+where
+    T::Int: AtomicPrimInt,
+{
     /// Create a new allocator,
     /// using [`T::START`] as the first id (usually zero).
     ///
     /// [`T::START`]: IntegerIdCounter::START
     #[inline]
+    #[cfg(not(loom))]
     pub const fn new() -> Self {
         UniqueIdAllocatorAtomic {
-            next_id: atomic::Atomic::new(T::START_INT),
+            next_id: AtomicInt::new_const::<StartInt<T>>(),
+            bounds: None,
+            origin: AtomicInt::new_const::<StartInt<T>>(),
+            issued_before_reset: AtomicUsize::new(0),
+            exhaustions: AtomicUsize::new(0),
             marker: PhantomData,
         }
     }
 
+    /// Create a new allocator,
+    /// using [`T::START`] as the first id (usually zero).
+    ///
+    /// Not a `const fn` when testing with loom,
+    /// since loom atomics can not be created in a constant.
+    ///
+    /// [`T::START`]: IntegerIdCounter::START
+    #[inline]
+    #[cfg(loom)]
+    pub fn new() -> Self {
+        Self::with_start(T::START)
+    }
+
     /// Create a new allocator,
     /// using the specified value as the first id.
     ///
-    /// Use [`Self::with_start_const`] if you need a constant function.
+    /// Use `Self::with_start_const` if you need a constant function.
     #[inline]
     pub fn with_start(start: T) -> Self {
         UniqueIdAllocatorAtomic {
-            next_id: atomic::Atomic::new(start.to_int()),
+            next_id: AtomicInt::new(start.to_int()),
+            bounds: None,
+            origin: AtomicInt::new(start.to_int()),
+            issued_before_reset: AtomicUsize::new(0),
+            exhaustions: AtomicUsize::new(0),
+            marker: PhantomData,
+        }
+    }
+
+    /// Create a new allocator,
+    /// which only allocates ids in the window from `start` up to (but excluding) `end`.
+    ///
+    /// Once the window is exhausted, [`Self::try_alloc`] returns an [`IdExhaustedError`]
+    /// which reports the bounds of the window.
+    /// If `end` is not greater than `start`, no ids can be allocated.
+    ///
+    /// A bounded allocator always allocates using a compare-and-swap loop
+    /// instead of the `fetch_add` fast path,
+    /// so that the counter never moves past the end of the window.
+    ///
+    /// The bounds are not preserved by serialization.
+    #[inline]
+    pub fn with_bounds(start: T, end: T) -> Self {
+        UniqueIdAllocatorAtomic {
+            next_id: AtomicInt::new(start.to_int()),
+            bounds: Some((start, end)),
+            origin: AtomicInt::new(start.to_int()),
+            issued_before_reset: AtomicUsize::new(0),
+            exhaustions: AtomicUsize::new(0),
             marker: PhantomData,
         }
     }
 
+    /// The number of ids which can still be allocated,
+    /// saturating at [`usize::MAX`].
+    ///
+    /// The result may be immediately out of date
+    /// if other threads are concurrently allocating ids.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        let end = self.bounds.map(|(_, end)| end);
+        let remaining = remaining_ids(self.next_id.load(Ordering::Relaxed), end);
+        if end.is_none() && T::MAX_ID_INT == Some(primint::max_value()) {
+            // the maximum integer can never be allocated,
+            // since the counter can not be incremented past it
+            remaining.saturating_sub(1)
+        } else {
+            remaining
+        }
+    }
+
     /// Create a new allocator,
     /// using the specified value as the first id.
     ///
@@ -77,13 +173,18 @@ impl<T: IntegerIdCounter> UniqueIdAllocatorAtomic<T> {
     /// ## Safety
     /// This function cannot cause undefined behavior.
     #[track_caller]
+    #[cfg(all(not(loom), feature = "atomic"))]
     pub const fn with_start_const(start: T) -> Self
     where
         T: bytemuck::NoUninit,
     {
         let start = bytemuck::must_cast::<T, T::Int>(start);
         UniqueIdAllocatorAtomic {
-            next_id: atomic::Atomic::new(start),
+            next_id: AtomicInt::new_in_const(start),
+            bounds: None,
+            origin: AtomicInt::new_in_const(start),
+            issued_before_reset: AtomicUsize::new(0),
+            exhaustions: AtomicUsize::new(0),
             marker: PhantomData,
         }
     }
@@ -101,6 +202,83 @@ impl<T: IntegerIdCounter> UniqueIdAllocatorAtomic<T> {
         )
     }
 
+    /// Check if allocating ids of this type is lock-free on the current target.
+    ///
+    /// By default, the counter is implemented using the [atomic crate],
+    /// which falls back to a global lock for integers without native atomics
+    /// (such as `u128` on most targets).
+    /// Enabling the `portable-atomic` feature uses the [portable-atomic crate] instead,
+    /// which gives lock-free 64-bit and 128-bit counters wherever the hardware supports them,
+    /// detecting CPU features at runtime if necessary.
+    ///
+    /// [atomic crate]: https://docs.rs/atomic/
+    /// [portable-atomic crate]: https://docs.rs/portable-atomic/
+    #[inline]
+    pub fn is_lock_free() -> bool {
+        AtomicInt::<T::Int>::is_lock_free()
+    }
+
+    /// Statistics about the allocator.
+    ///
+    /// Since ids are never freed, every id handed out since the last reset is counted as live.
+    /// Counting allocations does not slow down [`Self::try_alloc`],
+    /// since they are computed from the counter itself.
+    pub fn stats(&self) -> AllocatorStats {
+        let issued = self.issued();
+        AllocatorStats {
+            live: issued,
+            free: 0,
+            high_water_mark: issued,
+            allocations: self
+                .issued_before_reset
+                .load(Ordering::Relaxed)
+                .wrapping_add(issued),
+            frees: 0,
+            exhaustions: self.exhaustions.load(Ordering::Relaxed),
+        }
+    }
+
+    /// The number of ids handed out since the allocator started from its origin.
+    fn issued(&self) -> usize {
+        self.issued_before(self.next_id.load(Ordering::Relaxed))
+    }
+
+    /// The number of ids handed out from the origin up to the specified counter value.
+    fn issued_before(&self, next: T::Int) -> usize {
+        let origin = self.origin.load(Ordering::Relaxed);
+        let issued = primint::checked_sub(next, origin)
+            .map_or(0, |n| primint::to_usize_checked(n).unwrap_or(usize::MAX));
+        // failed allocations may push the counter past the last valid id
+        core::cmp::min(
+            issued,
+            remaining_ids(origin, self.bounds.map(|(_, end)| end)),
+        )
+    }
+
+    /// Load the integer value of the next id,
+    /// which is greater than every id allocated so far.
+    #[inline]
+    #[cfg(any(feature = "std", feature = "serde"))] // used by IdAllocatorAtomic and serde
+    pub(crate) fn next_int(&self) -> T::Int {
+        self.next_id.load(Ordering::Relaxed)
+    }
+
+    /// Create an allocator from the integer value of the next id,
+    /// which may be past the maximum id if the allocator is exhausted.
+    #[inline]
+    #[cfg(feature = "serde")]
+    pub(crate) fn from_next_int(next: T::Int) -> Self {
+        UniqueIdAllocatorAtomic {
+            next_id: AtomicInt::new(next),
+            bounds: None,
+            // the ids before the next one are assumed to be in use
+            origin: AtomicInt::new(T::START_INT),
+            issued_before_reset: AtomicUsize::new(0),
+            exhaustions: AtomicUsize::new(0),
+            marker: PhantomData,
+        }
+    }
+
     /// Attempt to allocate a new id, returning an error if exhausted.
     ///
     /// This operation is guaranteed to be atomic,
@@ -110,24 +288,157 @@ impl<T: IntegerIdCounter> UniqueIdAllocatorAtomic<T> {
     ///
     /// # Errors
     /// Once the number of allocated ids exceeds the range of the underlying
-    /// [`IntegerIdCounter`], then this function will return an error.
+    /// [`IntegerIdCounter`] or the window given to [`Self::with_bounds`],
+    /// then this function will return an error.
     /// This function will never skip over valid ids,
     /// so the error can only occur if all ids have ben used.
     #[inline]
     pub fn try_alloc(&self) -> Result<T, IdExhaustedError<T>> {
+        // Safe to used relaxed ordering because we only guarantee atomicity, not synchronization
+        //
+        // Bounded allocators always use the CAS loop,
+        // since racing increments would push the counter past the end of the window.
+        if let (Some(limit), None) = (Self::fast_path_limit(), self.bounds) {
+            if self.next_id.load(Ordering::Relaxed) < limit {
+                // Between the check and the increment, other threads may also advance the counter,
+                // so the id may be past the limit or the maximum id, which is checked after the increment.
+                // The increment itself can not overflow, since past the limit
+                // the counter only advances one id at a time until it leaves `fast_path_band`.
+                let id = self.next_id.fetch_add(primint::one(), Ordering::Relaxed);
+                return T::from_int_checked(id).ok_or_else(|| self.exhausted());
+            }
+        }
+        self.try_alloc_slow()
+    }
+
+    /// Allocate an id using a CAS loop,
+    /// which is used once the counter nears the maximum id,
+    /// or if the allocator is bounded.
+    #[cold]
+    fn try_alloc_slow(&self) -> Result<T, IdExhaustedError<T>> {
         // Effectively this is "fused" because T: IntegerIdCounter => T: IntegerIdContiguous,
         // so once addition overflows all future calls will error
-        //
-        // See the comment in the Self::reset call for a way to potentially eliminate the CAS loop.
-        //
-        // Safe to used relaxed ordering because we only guarantee atomicity, not synchronization
+        // The counter also stops at the end of the window, once reached.
         self.next_id
             .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |x| {
-                primint::checked_add(x, primint::one())
+                if self.is_below_end(x) {
+                    primint::checked_add(x, primint::one())
+                } else {
+                    None
+                }
             })
             .ok()
             .and_then(T::from_int_checked)
-            .ok_or_else(IdExhaustedError::new)
+            .ok_or_else(|| self.exhausted())
+    }
+
+    /// Check that the integer is before the end of the window, if any.
+    #[inline]
+    fn is_below_end(&self, int: T::Int) -> bool {
+        self.bounds.map_or(true, |(_, end)| int < end.to_int())
+    }
+
+    #[cold]
+    fn exhausted(&self) -> IdExhaustedError<T> {
+        self.exhaustions.fetch_add(1, Ordering::Relaxed);
+        match self.bounds {
+            Some((start, end)) => IdExhaustedError::bounded(start..end),
+            None => IdExhaustedError::new(),
+        }
+    }
+
+    /// The counter value below which [`Self::try_alloc`] can use a `fetch_add`
+    /// instead of a CAS loop, or `None` to always use a CAS loop.
+    ///
+    /// This reserves a margin of a quarter of the integer range below the maximum id.
+    /// A thread on the fast path may load the counter just below the limit,
+    /// and then be suspended while other threads advance the counter.
+    /// Its increment would overflow if the counter reached the maximum integer in the meantime,
+    /// so [`Self::try_alloc_range`] refuses to move the counter through [`Self::fast_path_band`].
+    /// The counter can then only overflow if a thread stays suspended between its load and its increment
+    /// for as long as other threads take to allocate an eighth of the integer range one id at a time,
+    /// similar to how [`Arc::clone`] assumes there are fewer than [`isize::MAX`] threads.
+    /// Integers smaller than 32 bits have too small of a margin and always use the CAS loop.
+    ///
+    /// [`Arc::clone`]: https://doc.rust-lang.org/std/sync/struct.Arc.html#method.clone
+    #[inline]
+    fn fast_path_limit() -> Option<T::Int> {
+        if primint::bits::<T::Int>() < 32 {
+            return None;
+        }
+        let max_int = primint::max_value::<T::Int>();
+        let max_id = T::MAX_ID_INT.map_or(max_int, |max| core::cmp::min(max, max_int));
+        let margin = primint::checked_div(max_int, primint::from_usize_wrapping(4))?;
+        primint::checked_sub(max_id, margin)
+    }
+
+    /// The start and (exclusive) end of the counter values
+    /// which may only be passed through one id at a time,
+    /// or `None` if there is no fast path.
+    ///
+    /// This begins at the [fast path limit](Self::fast_path_limit)
+    /// and covers an eighth of the integer range.
+    /// A range may be allocated if it ends before the band or starts after it.
+    #[inline]
+    fn fast_path_band() -> Option<(T::Int, T::Int)> {
+        let limit = Self::fast_path_limit()?;
+        let width = primint::checked_div(
+            primint::max_value::<T::Int>(),
+            primint::from_usize_wrapping(8),
+        )?;
+        Some((limit, primint::checked_add(limit, width)?))
+    }
+
+    /// Attempt to allocate `count` consecutive ids,
+    /// returning the first and last ids of the range (inclusive).
+    ///
+    /// This is equivalent to calling [`Self::try_alloc`] `count` times,
+    /// but only requires a single successful compare-and-swap.
+    /// The range is allocated atomically,
+    /// so it is never interleaved with ids allocated by other threads.
+    ///
+    /// # Errors
+    /// Returns an error if fewer than `count` ids remain.
+    /// In that case, no ids are allocated.
+    ///
+    /// For unbounded allocators of integers with at least 32 bits,
+    /// this also returns an error if the range would reach the last quarter of the id range
+    /// while starting before its last eighth, even though enough ids remain.
+    /// Those ids can still be allocated one at a time with [`Self::try_alloc`],
+    /// after which ranges succeed again.
+    /// Otherwise, a pending increment on the `fetch_add` fast path of [`Self::try_alloc`]
+    /// could overflow the counter.
+    ///
+    /// # Panics
+    /// If `count` is zero.
+    #[inline]
+    pub fn try_alloc_range(&self, count: usize) -> Result<(T, T), IdExhaustedError<T>> {
+        assert!(count > 0, "cannot allocate an empty range");
+        let count = primint::from_usize_checked::<T::Int>(count).ok_or_else(|| self.exhausted())?;
+        let first = self
+            .next_id
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |x| {
+                let next = primint::checked_add(x, count)?;
+                let last = primint::checked_sub(next, primint::one())?;
+                // all ids are valid if the last one is, since T is contiguous
+                T::from_int_checked(last)?;
+                // jumping past the band could overflow a pending `fetch_add`
+                if let (Some((band_start, band_end)), None) = (Self::fast_path_band(), self.bounds)
+                {
+                    if next > band_start && x < band_end {
+                        return None;
+                    }
+                }
+                self.is_below_end(last).then_some(next)
+            })
+            .map_err(|_| self.exhausted())?;
+        let last = primint::checked_add(first, count)
+            .and_then(|next| primint::checked_sub(next, primint::one()))
+            .and_then(T::from_int_checked);
+        match (T::from_int_checked(first), last) {
+            (Some(first), Some(last)) => Ok((first, last)),
+            _ => Err(self.exhausted()),
+        }
     }
 
     /// Attempt to allocate a new id, panicking if exhausted.
@@ -153,25 +464,32 @@ impl<T: IntegerIdCounter> UniqueIdAllocatorAtomic<T> {
     ///
     /// This is equivalent to running `*allocator = UniqueIdAllocatorAtomic::new()`,
     /// but is done atomically and does not require a `&mut Self` reference.
+    /// Unlike replacing the allocator, this keeps the cumulative [statistics](Self::stats).
+    ///
+    /// The counter is reset by a single atomic operation,
+    /// so each concurrent allocation returns an id from either before or after the reset.
+    /// The statistics are updated separately afterwards,
+    /// so they may be inaccurate if they are read while the reset is in progress.
     ///
     /// This may cause unexpected behavior if ids are expected to be monotonically increasing,
     /// or if the new ids conflict with ones still in use.
     /// To avoid this, keep the id allocator private.
     ///
+    /// If the allocator was created by [`Self::with_bounds`],
+    /// this restarts at the start of the window instead of [`T::START`].
+    ///
     /// There is no counterpart [`UniqueIdAllocator::set_next_id`],
-    /// because the ability to force the counter to jump forwards
-    /// could prevent future optimizations.
+    /// because forcing the counter to jump forwards
+    /// could overflow a pending increment on the `fetch_add` fast path.
+    ///
+    /// [`T::START`]: IntegerIdCounter::START
     #[inline]
     pub fn reset(&self) {
-        /*
-         * I said this might prevent future optimizations.
-         * What I am referring to is the potential to convert the CAS loop
-         * into a fetch_add similar to how Arc::clone does.
-         * Based on the assumption there are fewer than isize::MAX threads,
-         * Arc::clone only has to worry about overflow if the counter exceeds that value.
-         *
-         * This seems like a micro-optimization but it could become important at some point.
-         */
-        self.next_id.store(T::START.to_int(), Ordering::Relaxed);
+        let start = self.bounds.map_or(T::START, |(start, _)| start);
+        let old = self.next_id.swap(start.to_int(), Ordering::Relaxed);
+        let issued = self.issued_before(old);
+        self.origin.store(start.to_int(), Ordering::Relaxed);
+        self.issued_before_reset
+            .fetch_add(issued, Ordering::Relaxed);
     }
 }
@@ -0,0 +1,67 @@
+//! Enables serde serialization support for the unique allocators.
+//!
+//! Both allocators serialize as the next id they will return,
+//! or `None` if they are exhausted.
+use core::cell::Cell;
+
+use super::UniqueIdAllocator;
+use intid::serde::AsInt;
+use intid::IntegerIdCounter;
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+
+impl<T: IntegerIdCounter> Serialize for UniqueIdAllocator<T> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.next_id.get().map(AsInt).serialize(serializer)
+    }
+}
+impl<'de, T: IntegerIdCounter> Deserialize<'de> for UniqueIdAllocator<T> {
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let next_id = Option::<AsInt<T>>::deserialize(deserializer)?;
+        Ok(UniqueIdAllocator {
+            next_id: Cell::new(next_id.map(AsInt::into_inner)),
+            // the ids before the next one are assumed to be in use
+            bounds: None,
+        })
+    }
+}
+
+#[cfg(any(feature = "atomic", feature = "portable-atomic"))]
+mod atomic {
+    use super::{AsInt, Deserialize, Deserializer, IntegerIdCounter, Serialize, Serializer};
+    use crate::{AtomicPrimInt, UniqueIdAllocatorAtomic};
+    use intid::primint;
+
+    impl<T: IntegerIdCounter> Serialize for UniqueIdAllocatorAtomic<T>
+    where
+        T::Int: AtomicPrimInt,
+    {
+        #[inline]
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let next = self.next_int();
+            // the counter is past the maximum id once exhausted,
+            // and can never be incremented past the maximum integer
+            let next_id = if next == primint::max_value() {
+                None
+            } else {
+                T::from_int_checked(next)
+            };
+            next_id.map(AsInt).serialize(serializer)
+        }
+    }
+    impl<'de, T: IntegerIdCounter> Deserialize<'de> for UniqueIdAllocatorAtomic<T>
+    where
+        T::Int: AtomicPrimInt,
+    {
+        #[inline]
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            Ok(match Option::<AsInt<T>>::deserialize(deserializer)? {
+                Some(AsInt(next_id)) => UniqueIdAllocatorAtomic::with_start(next_id),
+                // the maximum integer can never be incremented, so allocation always fails
+                None => UniqueIdAllocatorAtomic::from_next_int(primint::max_value()),
+            })
+        }
+    }
+}
@@ -0,0 +1,54 @@
+#![allow(missing_docs)]
+#![cfg(all(
+    feature = "std",
+    any(feature = "atomic", feature = "portable-atomic"),
+    not(loom)
+))]
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use intid_allocator::IdAllocatorAtomic;
+
+intid::define_newtype_counter! {
+    struct SlotId(u32);
+}
+
+#[test]
+fn reuses_smallest_free_id() {
+    let alloc = IdAllocatorAtomic::<SlotId>::new();
+    for _ in 0..100 {
+        let _ = alloc.alloc();
+    }
+    alloc.free(SlotId(70));
+    alloc.free(SlotId(3));
+    assert!(!alloc.is_allocated(SlotId(3)));
+    assert!(alloc.is_allocated(SlotId(4)));
+    assert_eq!(alloc.alloc(), SlotId(3));
+    assert_eq!(alloc.alloc(), SlotId(70));
+    assert_eq!(alloc.alloc(), SlotId(100));
+    assert!(alloc.try_free(SlotId(3)).is_ok());
+    assert!(alloc.try_free(SlotId(3)).unwrap_err().is_double_free());
+    assert!(!alloc.try_free(SlotId(101)).unwrap_err().is_double_free());
+}
+
+#[test]
+fn threads_never_share_ids() {
+    const THREADS: usize = 8;
+    const ROUNDS: usize = 1000;
+    let alloc = IdAllocatorAtomic::<SlotId>::new();
+    let live = Mutex::new(HashSet::new());
+    std::thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for _ in 0..ROUNDS {
+                    let id = alloc.alloc();
+                    assert!(live.lock().unwrap().insert(id), "{id:?} allocated twice");
+                    assert!(live.lock().unwrap().remove(&id));
+                    alloc.free(id);
+                }
+            });
+        }
+    });
+    // ids are reused, so the total never exceeds the number of threads
+    assert!(alloc.alloc().0 < u32::try_from(THREADS).unwrap());
+}
@@ -0,0 +1,110 @@
+#![allow(missing_docs)]
+#![cfg(not(loom))]
+
+use intid_allocator::{IdExhaustedError, UniqueIdAllocator};
+
+intid::define_newtype_counter! {
+    struct SlotId(u32);
+}
+
+fn assert_window(err: &IdExhaustedError<SlotId>, start: u32, end: u32) {
+    assert_eq!(err.bounds(), Some(SlotId(start)..SlotId(end)));
+}
+
+#[test]
+fn unique() {
+    let alloc = UniqueIdAllocator::<SlotId>::with_bounds(SlotId(10), SlotId(13));
+    assert_eq!(alloc.remaining(), 3);
+    assert_eq!(alloc.alloc(), SlotId(10));
+    assert!(alloc.try_alloc_range(3).is_err());
+    assert_eq!(alloc.try_alloc_range(2).unwrap(), (SlotId(11), SlotId(12)));
+    assert_eq!(alloc.remaining(), 0);
+    assert_window(&alloc.try_alloc().unwrap_err(), 10, 13);
+    assert_window(&alloc.try_alloc().unwrap_err(), 10, 13);
+    alloc.reset();
+    assert_eq!(alloc.alloc(), SlotId(10));
+
+    let empty = UniqueIdAllocator::<SlotId>::with_bounds(SlotId(5), SlotId(5));
+    assert_eq!(empty.remaining(), 0);
+    assert!(empty.try_alloc().is_err());
+
+    let unbounded = UniqueIdAllocator::<u8>::with_start(250);
+    assert_eq!(unbounded.remaining(), 6);
+    assert_eq!(unbounded.try_alloc_range(6).unwrap(), (250, 255));
+    assert_eq!(unbounded.remaining(), 0);
+    assert_eq!(unbounded.try_alloc().unwrap_err().bounds(), None);
+}
+
+#[test]
+fn partitioned() {
+    let builtins = UniqueIdAllocator::<SlotId>::with_bounds(SlotId(0), SlotId(1000));
+    let user = UniqueIdAllocator::<SlotId>::with_start(SlotId(1000));
+    assert_eq!(builtins.try_alloc_range(1000).unwrap().1, SlotId(999));
+    let err = builtins.try_alloc().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Ran out of ids for {} in SlotId(0)..SlotId(1000)",
+            core::any::type_name::<SlotId>()
+        )
+    );
+    assert_eq!(user.alloc(), SlotId(1000));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn reusing() {
+    use intid_allocator::IdAllocator;
+    let mut alloc = IdAllocator::<SlotId>::with_bounds(SlotId(10), SlotId(13));
+    assert_eq!(alloc.alloc_range(3), (SlotId(10), SlotId(12)));
+    assert_eq!(alloc.remaining(), 0);
+    assert_window(&alloc.try_alloc().unwrap_err(), 10, 13);
+    alloc.free(SlotId(11));
+    assert_eq!(alloc.remaining(), 1);
+    assert_eq!(alloc.alloc(), SlotId(11));
+    assert!(alloc.try_alloc().is_err());
+    alloc.free_all();
+    assert_eq!(alloc.remaining(), 3);
+    assert_eq!(alloc.alloc(), SlotId(10));
+}
+
+#[cfg(any(feature = "atomic", feature = "portable-atomic"))]
+#[test]
+fn atomic() {
+    use intid_allocator::UniqueIdAllocatorAtomic;
+    let alloc = UniqueIdAllocatorAtomic::<SlotId>::with_bounds(SlotId(10), SlotId(13));
+    assert_eq!(alloc.remaining(), 3);
+    assert_eq!(alloc.alloc(), SlotId(10));
+    assert!(alloc.try_alloc_range(3).is_err());
+    assert_eq!(alloc.try_alloc_range(2).unwrap(), (SlotId(11), SlotId(12)));
+    assert_eq!(alloc.remaining(), 0);
+    assert_window(&alloc.try_alloc().unwrap_err(), 10, 13);
+    assert_window(&alloc.try_alloc().unwrap_err(), 10, 13);
+    assert_eq!(alloc.remaining(), 0);
+    alloc.reset();
+    assert_eq!(alloc.alloc(), SlotId(10));
+
+    let unbounded = UniqueIdAllocatorAtomic::<u8>::with_start(250);
+    // the maximum integer is never allocated
+    assert_eq!(unbounded.remaining(), 5);
+}
+
+#[cfg(any(feature = "atomic", feature = "portable-atomic"))]
+#[test]
+fn atomic_threads() {
+    use intid_allocator::UniqueIdAllocatorAtomic;
+    let alloc = UniqueIdAllocatorAtomic::<SlotId>::with_bounds(SlotId(0), SlotId(1000));
+    let mut ids = std::thread::scope(|scope| {
+        let handles = (0..4)
+            .map(|_| {
+                scope.spawn(|| core::iter::from_fn(|| alloc.try_alloc().ok()).collect::<Vec<_>>())
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect::<Vec<SlotId>>()
+    });
+    ids.sort_unstable();
+    assert_eq!(ids, (0..1000).map(SlotId).collect::<Vec<_>>());
+}
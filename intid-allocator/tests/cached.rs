@@ -0,0 +1,107 @@
+#![allow(missing_docs)]
+#![cfg(all(
+    feature = "std",
+    any(feature = "atomic", feature = "portable-atomic"),
+    not(loom)
+))]
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use intid_allocator::ThreadCachedIdAllocator;
+
+intid::define_newtype_counter! {
+    struct SlotId(u32);
+}
+
+#[test]
+fn chunks_and_local_reuse() {
+    let alloc = ThreadCachedIdAllocator::<SlotId>::with_chunk_size(4);
+    let mut first = alloc.cache();
+    let mut second = alloc.cache();
+    assert_eq!(first.cached_count(), 0);
+    assert_eq!(first.alloc(), SlotId(0));
+    assert_eq!(first.cached_count(), 3);
+    assert_eq!(second.alloc(), SlotId(4));
+    second.free(SlotId(4));
+    assert_eq!(second.alloc(), SlotId(4));
+    for expected in 1..4 {
+        assert_eq!(first.alloc(), SlotId(expected));
+    }
+    assert_eq!(first.alloc(), SlotId(8));
+}
+
+#[test]
+fn flush_gives_back_unused_ids() {
+    let alloc = ThreadCachedIdAllocator::<SlotId>::with_chunk_size(8);
+    {
+        let mut cache = alloc.cache();
+        let ids = (0..3).map(|_| cache.alloc()).collect::<Vec<_>>();
+        cache.free(ids[0]);
+        cache.free(ids[1]);
+        // dropping flushes
+    }
+    let mut cache = alloc.cache();
+    let mut reused = (0..7).map(|_| cache.alloc().0).collect::<Vec<_>>();
+    reused.sort_unstable();
+    assert_eq!(reused, [0, 1, 3, 4, 5, 6, 7]);
+    cache.flush();
+    assert_eq!(cache.cached_count(), 0);
+}
+
+#[test]
+fn stats() {
+    let alloc = ThreadCachedIdAllocator::<SlotId>::with_chunk_size(4);
+    let mut cache = alloc.cache();
+    let ids = (0..5).map(|_| cache.alloc()).collect::<Vec<_>>();
+    cache.free(ids[0]);
+    let stats = cache.stats();
+    assert_eq!((stats.live, stats.free, stats.high_water_mark), (4, 4, 8));
+    assert_eq!((stats.allocations, stats.frees), (5, 1));
+
+    // ids held by the cache are free, but the free is not reported until the next chunk
+    let stats = alloc.stats();
+    assert_eq!((stats.live, stats.free, stats.high_water_mark), (5, 3, 8));
+    assert_eq!((stats.allocations, stats.frees), (5, 0));
+
+    // flushed ids stay free until another cache reserves them
+    cache.flush();
+    let stats = alloc.stats();
+    assert_eq!((stats.live, stats.free, stats.high_water_mark), (4, 4, 8));
+    assert_eq!((stats.allocations, stats.frees), (5, 1));
+    drop(cache);
+    let mut other = alloc.cache();
+    let _ = other.alloc();
+    drop(other);
+    let stats = alloc.stats();
+    assert_eq!((stats.live, stats.free, stats.allocations), (5, 3, 6));
+}
+
+#[test]
+fn unique_across_threads() {
+    const THREADS: usize = 8;
+    const ROUNDS: usize = 500;
+    let alloc = ThreadCachedIdAllocator::<SlotId>::with_chunk_size(16);
+    let live = Mutex::new(HashSet::new());
+    std::thread::scope(|scope| {
+        for thread in 0..THREADS {
+            let (alloc, live) = (&alloc, &live);
+            scope.spawn(move || {
+                let mut cache = alloc.cache();
+                let mut mine = Vec::new();
+                for round in 0..ROUNDS {
+                    let id = cache.alloc();
+                    assert!(live.lock().unwrap().insert(id), "{id:?} allocated twice");
+                    mine.push(id);
+                    if (round + thread) % 3 == 0 {
+                        let id = mine.swap_remove(round % mine.len());
+                        assert!(live.lock().unwrap().remove(&id));
+                        cache.free(id);
+                    }
+                    if round % 100 == 99 {
+                        cache.flush();
+                    }
+                }
+            });
+        }
+    });
+}
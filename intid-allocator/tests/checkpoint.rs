@@ -0,0 +1,136 @@
+#![allow(missing_docs)]
+#![cfg(not(loom))]
+
+use intid_allocator::SpeculativeIdAllocator;
+
+intid::define_newtype_counter! {
+    struct VarId(u32);
+}
+
+#[test]
+fn unique_nested() {
+    let alloc = SpeculativeIdAllocator::<VarId>::new();
+    let _ = alloc.alloc();
+    let outer = alloc.checkpoint();
+    assert_eq!(alloc.alloc(), VarId(1));
+    let inner = alloc.checkpoint();
+    assert_eq!(alloc.alloc(), VarId(2));
+    alloc.rollback_to(inner);
+    assert_eq!(alloc.alloc(), VarId(2));
+    let inner = alloc.checkpoint();
+    assert_eq!(alloc.alloc(), VarId(3));
+    alloc.commit(inner);
+    // rolling back the outer checkpoint also undoes committed allocations
+    alloc.rollback_to(outer);
+    assert_eq!(alloc.alloc(), VarId(1));
+}
+
+#[test]
+#[should_panic = "Stale checkpoint"]
+fn unique_out_of_order() {
+    let alloc = SpeculativeIdAllocator::<VarId>::new();
+    let outer = alloc.checkpoint();
+    let _inner = alloc.checkpoint();
+    alloc.rollback_to(outer);
+}
+
+#[test]
+#[should_panic = "Stale checkpoint: the allocator was reset"]
+fn unique_reset() {
+    let alloc = SpeculativeIdAllocator::<VarId>::new();
+    let checkpoint = alloc.checkpoint();
+    let _ = alloc.alloc();
+    alloc.set_next_id(VarId(7));
+    alloc.rollback_to(checkpoint);
+}
+
+#[test]
+#[should_panic = "Stale checkpoint"]
+fn unique_reset_then_checkpoint() {
+    let alloc = SpeculativeIdAllocator::<VarId>::new();
+    let stale = alloc.checkpoint();
+    alloc.reset();
+    // a new checkpoint at the same depth works, but the old one stays stale
+    let fresh = alloc.checkpoint();
+    let _ = alloc.alloc();
+    alloc.rollback_to(fresh);
+    assert_eq!(alloc.alloc(), VarId(0));
+    alloc.rollback_to(stale);
+}
+
+#[test]
+#[should_panic = "Stale checkpoint"]
+fn unique_discarded() {
+    let alloc = SpeculativeIdAllocator::<VarId>::new();
+    let outer = alloc.checkpoint();
+    drop(alloc.checkpoint());
+    alloc.discard_checkpoints();
+    let checkpoint = alloc.checkpoint();
+    assert_eq!(alloc.alloc(), VarId(0));
+    alloc.rollback_to(checkpoint);
+    assert_eq!(alloc.alloc(), VarId(0));
+    alloc.rollback_to(outer);
+}
+
+#[cfg(feature = "alloc")]
+mod reusing {
+    use super::VarId;
+    use intid_allocator::IdAllocator;
+
+    #[test]
+    fn restores_free_ids() {
+        let mut alloc = IdAllocator::<VarId>::new();
+        let _ = alloc.alloc_range(6);
+        alloc.free(VarId(1));
+        alloc.free(VarId(4));
+        let before = alloc.iter_allocated().collect::<Vec<_>>();
+
+        let checkpoint = alloc.checkpoint();
+        assert_eq!(alloc.alloc(), VarId(1));
+        alloc.free(VarId(0));
+        assert_eq!(alloc.alloc_range(2), (VarId(6), VarId(7)));
+        alloc.free_range(VarId(5), VarId(7));
+        assert_eq!(alloc.alloc_range(3), (VarId(4), VarId(6)));
+        alloc.free(VarId(6));
+        alloc.rollback_to(checkpoint);
+
+        assert_eq!(alloc.iter_allocated().collect::<Vec<_>>(), before);
+        assert_eq!(alloc.allocated_count(), 4);
+        assert!(!alloc.is_allocated(VarId(6)));
+        assert!(alloc.try_free(VarId(6)).is_err());
+        assert_eq!(alloc.alloc(), VarId(1));
+        assert_eq!(alloc.alloc(), VarId(4));
+        assert_eq!(alloc.alloc(), VarId(6));
+    }
+
+    #[test]
+    fn nested_commit() {
+        let mut alloc = IdAllocator::<VarId>::new();
+        let _ = alloc.alloc_range(3);
+        let outer = alloc.checkpoint();
+        alloc.free(VarId(0));
+        let inner = alloc.checkpoint();
+        alloc.free(VarId(2));
+        alloc.commit(inner);
+        assert_eq!(alloc.allocated_count(), 1);
+        alloc.rollback_to(outer);
+        assert_eq!(alloc.allocated_count(), 3);
+        assert_eq!(alloc.alloc(), VarId(3));
+
+        // once all checkpoints are consumed, later changes are kept
+        let checkpoint = alloc.checkpoint();
+        alloc.commit(checkpoint);
+        alloc.free(VarId(1));
+        assert_eq!(alloc.alloc(), VarId(1));
+    }
+
+    #[test]
+    #[should_panic = "Stale checkpoint"]
+    fn free_all_invalidates() {
+        let mut alloc = IdAllocator::<VarId>::new();
+        let checkpoint = alloc.checkpoint();
+        let _ = alloc.alloc();
+        alloc.free_all();
+        alloc.rollback_to(checkpoint);
+    }
+}
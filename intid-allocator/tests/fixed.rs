@@ -0,0 +1,80 @@
+#![allow(missing_docs)]
+#![cfg(not(loom))]
+
+use intid_allocator::FixedIdAllocator;
+
+intid::define_newtype_counter! {
+    struct HandleId(u8);
+}
+
+// the allocator can be created in a constant context
+const POOL: FixedIdAllocator<HandleId, 4> = FixedIdAllocator::new();
+
+#[test]
+fn smallest_first() {
+    let mut alloc = POOL;
+    assert_eq!(alloc.capacity(), 256);
+    for i in 0..70 {
+        assert_eq!(alloc.alloc(), HandleId(i));
+    }
+    alloc.free(HandleId(65));
+    alloc.free(HandleId(3));
+    alloc.free(HandleId(64));
+    assert!(!alloc.is_allocated(HandleId(3)));
+    assert!(alloc.is_allocated(HandleId(4)));
+    assert_eq!(alloc.allocated_count(), 67);
+    assert_eq!(alloc.alloc(), HandleId(3));
+    assert_eq!(alloc.alloc(), HandleId(64));
+    assert_eq!(alloc.alloc(), HandleId(65));
+    assert_eq!(alloc.alloc(), HandleId(70));
+}
+
+#[test]
+fn exhausted() {
+    let mut alloc = FixedIdAllocator::<HandleId, 4>::new();
+    for i in 0..=255 {
+        assert_eq!(alloc.alloc(), HandleId(i));
+    }
+    assert_eq!(alloc.remaining(), 0);
+    assert_eq!(alloc.try_alloc().unwrap_err().bounds(), None);
+    alloc.free(HandleId(200));
+    assert_eq!(alloc.alloc(), HandleId(200));
+
+    let mut limited = FixedIdAllocator::<HandleId, 2>::with_capacity(3);
+    let _ = (limited.alloc(), limited.alloc(), limited.alloc());
+    let err = limited.try_alloc().unwrap_err();
+    assert_eq!(err.bounds(), Some(HandleId(0)..HandleId(3)));
+    assert_eq!(limited.stats().exhaustions, 1);
+
+    // the pool is also limited by the maximum id
+    let mut offset = FixedIdAllocator::<u8, 1>::with_start(250);
+    assert_eq!(
+        (0..6).map(|_| offset.alloc()).collect::<Vec<_>>(),
+        [250, 251, 252, 253, 254, 255]
+    );
+    assert!(offset.try_alloc().is_err());
+}
+
+#[test]
+fn invalid_free() {
+    let mut alloc = FixedIdAllocator::<HandleId, 1>::with_capacity(10);
+    let id = alloc.alloc();
+    alloc.free(id);
+    assert!(alloc.try_free(id).unwrap_err().is_double_free());
+    assert!(!alloc.try_free(HandleId(5)).unwrap_err().is_double_free());
+    assert!(!alloc.try_free(HandleId(40)).unwrap_err().is_double_free());
+    assert!(!alloc.is_allocated(HandleId(40)));
+
+    let _ = (alloc.alloc(), alloc.alloc());
+    alloc.free_all();
+    assert_eq!(alloc.allocated_count(), 0);
+    assert!(!alloc.try_free(HandleId(1)).unwrap_err().is_double_free());
+    let stats = alloc.stats();
+    assert_eq!((stats.allocations, stats.frees), (3, 3));
+}
+
+#[test]
+#[should_panic = "capacity exceeds"]
+fn capacity_too_large() {
+    let _ = FixedIdAllocator::<HandleId, 1>::with_capacity(65);
+}
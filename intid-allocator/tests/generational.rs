@@ -0,0 +1,94 @@
+#![allow(missing_docs)]
+#![cfg(all(feature = "alloc", not(loom)))]
+use intid::IntegerIdCounter;
+use intid_allocator::GenerationalIdAllocator;
+
+intid::define_newtype_counter! {
+    struct SlotId(u32);
+}
+
+#[test]
+fn free_bumps_generation() {
+    let mut alloc = GenerationalIdAllocator::<SlotId>::new();
+    let a = alloc.alloc();
+    let b = alloc.alloc();
+    assert_eq!((a.index(), a.generation()), (SlotId(0), 0));
+    assert_eq!((b.index(), b.generation()), (SlotId(1), 0));
+    assert_eq!(alloc.len(), 2);
+    assert!(alloc.free(a));
+    assert!(!alloc.is_live(a));
+    // double free is rejected
+    assert!(!alloc.free(a));
+    assert_eq!(alloc.current(SlotId(0)), None);
+    let c = alloc.alloc();
+    assert_eq!((c.index(), c.generation()), (SlotId(0), 1));
+    assert!(alloc.is_live(c));
+    assert!(!alloc.is_live(a));
+    assert_eq!(alloc.current(SlotId(0)), Some(c));
+    assert_eq!(alloc.len(), 2);
+}
+
+#[test]
+fn exhausted_generation_retires_index() {
+    let mut alloc = GenerationalIdAllocator::<SlotId, u8>::new();
+    let mut id = alloc.alloc();
+    for generation in 1..=u8::MAX {
+        assert!(alloc.free(id));
+        id = alloc.alloc();
+        assert_eq!((id.index(), id.generation()), (SlotId(0), generation));
+    }
+    assert!(alloc.free(id));
+    assert_eq!(alloc.len(), 0);
+    assert_eq!(alloc.retired(), 1);
+    let stats = alloc.stats();
+    assert_eq!((stats.live, stats.free), (0, 0));
+    assert_eq!(stats.frees, 256);
+    let next = alloc.alloc();
+    assert_eq!((next.index(), next.generation()), (SlotId(1), 0));
+    assert!(!alloc.is_empty());
+    assert_eq!(alloc.stats().live, alloc.len());
+}
+
+/// An id whose first value is far from zero.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+struct HighId(u64);
+impl intid::IntegerId for HighId {
+    type Int = u64;
+    const MIN_ID: Option<Self> = Some(HighId(HighId::FIRST));
+    const MAX_ID: Option<Self> = Some(HighId(u64::MAX));
+    const MIN_ID_INT: Option<u64> = Some(HighId::FIRST);
+    const MAX_ID_INT: Option<u64> = Some(u64::MAX);
+
+    fn from_int_checked(id: u64) -> Option<Self> {
+        (id >= HighId::FIRST).then_some(HighId(id))
+    }
+
+    fn to_int(self) -> u64 {
+        self.0
+    }
+}
+impl intid::IntegerIdContiguous for HighId {}
+impl intid::IntegerIdCounter for HighId {
+    const START: Self = HighId(HighId::FIRST);
+    const START_INT: u64 = HighId::FIRST;
+}
+impl HighId {
+    const FIRST: u64 = 1 << 40;
+}
+
+#[test]
+fn large_start() {
+    // the table of generations starts from the first index, not from zero
+    let mut alloc = GenerationalIdAllocator::<HighId>::new();
+    let a = alloc.alloc();
+    let b = alloc.alloc();
+    assert_eq!(
+        (a.index(), b.index()),
+        (HighId::START, HighId(HighId::FIRST + 1))
+    );
+    assert!(alloc.free(a));
+    let c = alloc.alloc();
+    assert_eq!((c.index(), c.generation()), (HighId::START, 1));
+    assert!(alloc.is_live(b) && alloc.is_live(c));
+    assert_eq!(alloc.current(HighId(0)), None);
+}
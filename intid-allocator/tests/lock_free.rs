@@ -0,0 +1,82 @@
+#![allow(missing_docs)]
+#![cfg(all(any(feature = "atomic", feature = "portable-atomic"), not(loom)))]
+
+use intid_allocator::UniqueIdAllocatorAtomic;
+
+intid::define_newtype_counter! {
+    struct WideId(u128);
+}
+
+#[test]
+fn is_lock_free() {
+    assert_eq!(
+        UniqueIdAllocatorAtomic::<u32>::is_lock_free(),
+        cfg!(target_has_atomic = "32")
+    );
+    if cfg!(feature = "portable-atomic") {
+        assert_eq!(
+            UniqueIdAllocatorAtomic::<u64>::is_lock_free(),
+            cfg!(target_has_atomic = "64")
+        );
+    }
+    // either way, the answer does not change at runtime
+    assert_eq!(
+        UniqueIdAllocatorAtomic::<WideId>::is_lock_free(),
+        UniqueIdAllocatorAtomic::<u128>::is_lock_free()
+    );
+}
+
+#[test]
+fn const_new() {
+    static WIDE: UniqueIdAllocatorAtomic<WideId> = UniqueIdAllocatorAtomic::new();
+    static NARROW: UniqueIdAllocatorAtomic<u8> = UniqueIdAllocatorAtomic::new();
+    assert_eq!(WIDE.alloc(), WideId(0));
+    assert_eq!(WIDE.alloc(), WideId(1));
+    assert_eq!(NARROW.alloc(), 0);
+}
+
+/// Available with the `atomic` feature, even if `portable-atomic` is enabled as well.
+#[cfg(feature = "atomic")]
+#[test]
+fn const_with_start() {
+    static WIDE: UniqueIdAllocatorAtomic<u128> = UniqueIdAllocatorAtomic::with_start_const(7);
+    static NARROW: UniqueIdAllocatorAtomic<u16> = UniqueIdAllocatorAtomic::with_start_const(3);
+    assert_eq!(WIDE.alloc(), 7);
+    assert_eq!(WIDE.alloc(), 8);
+    assert_eq!(NARROW.alloc(), 3);
+}
+
+#[test]
+fn wide_ids() {
+    let alloc = UniqueIdAllocatorAtomic::<WideId>::with_start(WideId(u128::from(u64::MAX)));
+    assert_eq!(alloc.alloc(), WideId(u128::from(u64::MAX)));
+    assert_eq!(
+        alloc.try_alloc_range(2).unwrap(),
+        (WideId(1 << 64), WideId((1 << 64) + 1))
+    );
+    assert_eq!(alloc.approx_max_used_id(), Some(WideId((1 << 64) + 1)));
+    assert_eq!(alloc.stats().live, 3);
+
+    let near_max = UniqueIdAllocatorAtomic::<u128>::with_start(u128::MAX - 2);
+    assert_eq!(near_max.alloc(), u128::MAX - 2);
+    assert_eq!(near_max.alloc(), u128::MAX - 1);
+    // the maximum integer is never allocated
+    assert!(near_max.try_alloc().is_err());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn wide_ids_threads() {
+    let alloc = UniqueIdAllocatorAtomic::<WideId>::new();
+    let mut ids = std::thread::scope(|scope| {
+        let handles = (0..4)
+            .map(|_| scope.spawn(|| (0..1000).map(|_| alloc.alloc()).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect::<Vec<WideId>>()
+    });
+    ids.sort_unstable();
+    assert_eq!(ids, (0..4000).map(WideId).collect::<Vec<_>>());
+}
@@ -0,0 +1,212 @@
+//! Model tests for [`IdAllocatorAtomic`], run with `RUSTFLAGS="--cfg loom"`.
+#![allow(missing_docs)]
+#![cfg(loom)]
+use loom::sync::Arc;
+use loom::thread;
+
+use intid_allocator::IdAllocatorAtomic;
+
+intid::define_newtype_counter! {
+    struct SlotId(u32);
+}
+
+#[test]
+fn concurrent_alloc_is_unique() {
+    loom::model(|| {
+        let alloc = Arc::new(IdAllocatorAtomic::<SlotId>::new());
+        let other = {
+            let alloc = Arc::clone(&alloc);
+            thread::spawn(move || alloc.alloc())
+        };
+        let mine = alloc.alloc();
+        assert_ne!(mine, other.join().unwrap());
+    });
+}
+
+#[test]
+fn alloc_racing_free() {
+    loom::model(|| {
+        let alloc = Arc::new(IdAllocatorAtomic::<SlotId>::new());
+        let first = alloc.alloc();
+        let second = alloc.alloc();
+        let freer = {
+            let alloc = Arc::clone(&alloc);
+            thread::spawn(move || {
+                alloc.free(first);
+                alloc.alloc()
+            })
+        };
+        let mine = alloc.alloc();
+        let theirs = freer.join().unwrap();
+        assert_ne!(mine, theirs);
+        assert_ne!(mine, second);
+        assert_ne!(theirs, second);
+        // the freed id is always reused by one of the two allocations
+        assert!(mine == first || theirs == first);
+    });
+}
+
+#[test]
+fn concurrent_double_free() {
+    loom::model(|| {
+        let alloc = Arc::new(IdAllocatorAtomic::<SlotId>::new());
+        let id = alloc.alloc();
+        let other = {
+            let alloc = Arc::clone(&alloc);
+            thread::spawn(move || alloc.try_free(id).is_ok())
+        };
+        let mine = alloc.try_free(id).is_ok();
+        let theirs = other.join().unwrap();
+        assert!(mine ^ theirs, "exactly one free must succeed");
+        assert_eq!(alloc.alloc(), id);
+        assert_eq!(alloc.alloc(), SlotId(1));
+    });
+}
+
+#[test]
+fn concurrent_ranges_do_not_overlap() {
+    use intid_allocator::UniqueIdAllocatorAtomic;
+    loom::model(|| {
+        let alloc = Arc::new(UniqueIdAllocatorAtomic::<SlotId>::new());
+        let other = {
+            let alloc = Arc::clone(&alloc);
+            thread::spawn(move || alloc.try_alloc_range(3).unwrap())
+        };
+        let single = alloc.alloc();
+        let (first, last) = other.join().unwrap();
+        assert_eq!(last.0 - first.0, 2);
+        assert!(single < first || single > last);
+    });
+}
+
+/// The first counter value which uses the CAS loop instead of `fetch_add`.
+const SLOW_PATH_START: u32 = u32::MAX - u32::MAX / 4;
+
+#[test]
+fn fast_path_boundary_never_skips() {
+    use intid_allocator::UniqueIdAllocatorAtomic;
+    loom::model(|| {
+        let start = SLOW_PATH_START - 1;
+        let alloc = Arc::new(UniqueIdAllocatorAtomic::<u32>::with_start(start));
+        let threads = (0..2)
+            .map(|_| {
+                let alloc = Arc::clone(&alloc);
+                thread::spawn(move || alloc.alloc())
+            })
+            .collect::<Vec<_>>();
+        let mut ids = vec![alloc.alloc()];
+        ids.extend(threads.into_iter().map(|t| t.join().unwrap()));
+        ids.sort_unstable();
+        assert_eq!(ids, [start, start + 1, start + 2]);
+    });
+}
+
+#[test]
+fn range_never_overflows_fast_path() {
+    use intid_allocator::UniqueIdAllocatorAtomic;
+    loom::model(|| {
+        let start = SLOW_PATH_START - 1;
+        let alloc = Arc::new(UniqueIdAllocatorAtomic::<u32>::with_start(start));
+        let other = {
+            let alloc = Arc::clone(&alloc);
+            // would leave no room for a pending fast path increment
+            thread::spawn(move || alloc.try_alloc_range((u32::MAX - start) as usize).ok())
+        };
+        let mine = alloc.try_alloc().unwrap();
+        assert_eq!(other.join().unwrap(), None);
+        assert_eq!(mine, start);
+        assert_eq!(alloc.alloc(), start + 1);
+    });
+}
+
+#[test]
+fn exhaustion_is_fused() {
+    use intid_allocator::UniqueIdAllocatorAtomic;
+    loom::model(|| {
+        let last = u32::MAX - 1;
+        let alloc = Arc::new(UniqueIdAllocatorAtomic::<u32>::with_start(last));
+        let other = {
+            let alloc = Arc::clone(&alloc);
+            thread::spawn(move || alloc.try_alloc().ok())
+        };
+        let mine = alloc.try_alloc().ok();
+        let theirs = other.join().unwrap();
+        let mut allocated = [mine, theirs].into_iter().flatten().collect::<Vec<_>>();
+        allocated.sort_unstable();
+        assert_eq!(allocated, [last]);
+        assert!(alloc.try_alloc().is_err());
+        assert!(alloc.try_alloc_range(1).is_err());
+    });
+}
+
+#[test]
+fn bounded_never_overshoots() {
+    use intid_allocator::UniqueIdAllocatorAtomic;
+    loom::model(|| {
+        // only one id is left before the end of the window
+        let alloc = Arc::new(UniqueIdAllocatorAtomic::<SlotId>::with_bounds(
+            SlotId(4),
+            SlotId(6),
+        ));
+        assert_eq!(alloc.alloc(), SlotId(4));
+        let other = {
+            let alloc = Arc::clone(&alloc);
+            thread::spawn(move || alloc.try_alloc().ok())
+        };
+        let mine = alloc.try_alloc().ok();
+        let theirs = other.join().unwrap();
+        let mut allocated = [mine, theirs].into_iter().flatten().collect::<Vec<_>>();
+        allocated.sort_unstable();
+        assert_eq!(allocated, [SlotId(5)]);
+        // the counter stops at the end of the window
+        assert_eq!(alloc.approx_max_used_id(), Some(SlotId(5)));
+        assert_eq!(alloc.remaining(), 0);
+        assert_eq!(alloc.stats().live, 2);
+    });
+}
+
+#[test]
+fn reset_keeps_concurrent_allocations() {
+    use intid_allocator::UniqueIdAllocatorAtomic;
+    loom::model(|| {
+        let alloc = Arc::new(UniqueIdAllocatorAtomic::<SlotId>::new());
+        let _ = alloc.alloc();
+        let other = {
+            let alloc = Arc::clone(&alloc);
+            thread::spawn(move || alloc.alloc())
+        };
+        alloc.reset();
+        let theirs = other.join().unwrap();
+        // allocated either before the reset, or as the first id after it
+        let mine = alloc.alloc();
+        assert!(
+            theirs == SlotId(1) && mine == SlotId(0) || theirs == SlotId(0) && mine == SlotId(1)
+        );
+        assert_eq!(alloc.stats().allocations, 3);
+    });
+}
+
+#[test]
+fn thread_caches_are_unique() {
+    use intid_allocator::ThreadCachedIdAllocator;
+    loom::model(|| {
+        let alloc = Arc::new(ThreadCachedIdAllocator::<SlotId>::with_chunk_size(2));
+        let other = {
+            let alloc = Arc::clone(&alloc);
+            thread::spawn(move || {
+                let mut cache = alloc.cache();
+                let id = cache.alloc();
+                // give the rest of the chunk back
+                cache.flush();
+                id
+            })
+        };
+        let mut cache = alloc.cache();
+        let mine = [cache.alloc(), cache.alloc(), cache.alloc()];
+        let theirs = other.join().unwrap();
+        assert!(!mine.contains(&theirs));
+        assert_ne!(mine[0], mine[1]);
+        assert_ne!(mine[1], mine[2]);
+        assert_ne!(mine[0], mine[2]);
+    });
+}
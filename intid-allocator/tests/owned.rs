@@ -0,0 +1,90 @@
+#![allow(missing_docs)]
+#![cfg(all(feature = "alloc", not(loom)))]
+
+use intid_allocator::IdAllocator;
+
+intid::define_newtype_counter! {
+    struct SlotId(u32);
+}
+
+fn fallible(fail: bool) -> Result<(), ()> {
+    if fail {
+        Err(())
+    } else {
+        Ok(())
+    }
+}
+
+fn insert(alloc: &mut IdAllocator<SlotId>, fail: bool) -> Result<SlotId, ()> {
+    let id = alloc.alloc_owned();
+    fallible(fail)?;
+    Ok(id.into_inner())
+}
+
+#[test]
+fn freed_on_error_path() {
+    let mut alloc = IdAllocator::<SlotId>::new();
+    assert_eq!(insert(&mut alloc, false), Ok(SlotId(0)));
+    assert_eq!(insert(&mut alloc, true), Err(()));
+    assert!(!alloc.is_allocated(SlotId(1)));
+    assert_eq!(insert(&mut alloc, false), Ok(SlotId(1)));
+    assert_eq!(alloc.allocated_count(), 2);
+}
+
+#[test]
+fn forget_keeps_id() {
+    let mut alloc = IdAllocator::<SlotId>::new();
+    let id = alloc.alloc_owned();
+    assert_eq!(*id, SlotId(0));
+    assert_eq!(id.id(), SlotId(0));
+    id.forget();
+    assert!(alloc.is_allocated(SlotId(0)));
+    assert_eq!(alloc.alloc(), SlotId(1));
+}
+
+#[test]
+fn try_alloc_owned() {
+    let mut alloc = IdAllocator::<u8>::new();
+    let _ = alloc.alloc_range(255);
+    let id = alloc.try_alloc_owned().unwrap();
+    assert_eq!(*id, 255);
+    drop(id);
+    assert!(!alloc.is_allocated(255));
+    let _ = alloc.alloc();
+    assert!(alloc.try_alloc_owned().is_err());
+}
+
+#[cfg(all(feature = "std", any(feature = "atomic", feature = "portable-atomic")))]
+mod atomic {
+    use super::SlotId;
+    use intid_allocator::{IdAllocatorAtomic, OwnedId, SharedIdAllocator};
+
+    #[test]
+    fn multiple_guards() {
+        let alloc = IdAllocatorAtomic::<SlotId>::new();
+        let first = alloc.alloc_owned();
+        let second = alloc.alloc_owned();
+        assert_eq!((*first, *second), (SlotId(0), SlotId(1)));
+        drop(first);
+        assert!(!alloc.is_allocated(SlotId(0)));
+        assert_eq!(second.into_inner(), SlotId(1));
+        assert!(alloc.is_allocated(SlotId(1)));
+        assert_eq!(alloc.alloc(), SlotId(0));
+    }
+
+    #[test]
+    fn shared_guards_are_static() {
+        let alloc = SharedIdAllocator::<SlotId>::new();
+        let guards: Vec<OwnedId<'static, SlotId>> = (0..4).map(|_| alloc.alloc_owned()).collect();
+        let handle = std::thread::spawn(move || guards.iter().map(|id| **id).collect::<Vec<_>>());
+        assert_eq!(
+            handle.join().unwrap(),
+            [SlotId(0), SlotId(1), SlotId(2), SlotId(3)]
+        );
+        for i in 0..4 {
+            assert!(!alloc.is_allocated(SlotId(i)));
+        }
+        let kept = alloc.clone().alloc_owned().into_inner();
+        assert!(alloc.is_allocated(kept));
+    }
+}
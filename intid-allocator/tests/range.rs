@@ -0,0 +1,110 @@
+#![allow(missing_docs)]
+#![cfg(all(feature = "alloc", not(loom)))]
+use core::num::NonZeroU8;
+
+use intid_allocator::{IdAllocator, UniqueIdAllocator};
+
+intid::define_newtype_counter! {
+    struct SlotId(u32);
+}
+
+#[test]
+fn unique_range() {
+    let alloc = UniqueIdAllocator::<SlotId>::new();
+    assert_eq!(alloc.try_alloc_range(3).unwrap(), (SlotId(0), SlotId(2)));
+    assert_eq!(alloc.alloc(), SlotId(3));
+    assert_eq!(alloc.try_alloc_range(1).unwrap(), (SlotId(4), SlotId(4)));
+}
+
+#[test]
+fn unique_range_all_or_nothing() {
+    let alloc = UniqueIdAllocator::<NonZeroU8>::new();
+    let (first, last) = alloc.try_alloc_range(250).unwrap();
+    assert_eq!((first.get(), last.get()), (1, 250));
+    assert!(alloc.try_alloc_range(6).is_err());
+    // the failed allocation didn't consume anything
+    let (first, last) = alloc.try_alloc_range(5).unwrap();
+    assert_eq!((first.get(), last.get()), (251, 255));
+    assert!(alloc.try_alloc().is_err());
+}
+
+#[cfg(any(feature = "atomic", feature = "portable-atomic"))]
+#[test]
+fn atomic_range() {
+    let alloc = intid_allocator::UniqueIdAllocatorAtomic::<NonZeroU8>::new();
+    let (first, last) = alloc.try_alloc_range(200).unwrap();
+    assert_eq!((first.get(), last.get()), (1, 200));
+    assert_eq!(alloc.alloc().get(), 201);
+    assert!(alloc.try_alloc_range(100).is_err());
+    let (first, last) = alloc.try_alloc_range(50).unwrap();
+    assert_eq!((first.get(), last.get()), (202, 251));
+}
+
+#[cfg(any(feature = "atomic", feature = "portable-atomic"))]
+#[test]
+fn atomic_range_skips_fast_path_band() {
+    let limit = u32::MAX - u32::MAX / 4;
+    let band_end = limit + u32::MAX / 8;
+    let alloc = intid_allocator::UniqueIdAllocatorAtomic::<u32>::with_start(limit - 3);
+    assert_eq!(alloc.try_alloc_range(2).unwrap(), (limit - 3, limit - 2));
+    // would end past the limit, so it must be allocated one id at a time
+    assert!(alloc.try_alloc_range(2).is_err());
+    assert_eq!(alloc.alloc(), limit - 1);
+    assert_eq!(alloc.alloc(), limit);
+    let alloc = intid_allocator::UniqueIdAllocatorAtomic::<u32>::with_start(band_end);
+    let count = usize::try_from(u32::MAX - band_end).unwrap();
+    assert_eq!(
+        alloc.try_alloc_range(count).unwrap(),
+        (band_end, u32::MAX - 1)
+    );
+    assert!(alloc.try_alloc().is_err());
+}
+
+#[test]
+fn reusing_range_merges_freed_ids() {
+    let mut alloc = IdAllocator::<SlotId>::new();
+    assert_eq!(alloc.alloc_range(10), (SlotId(0), SlotId(9)));
+    alloc.free(SlotId(2));
+    alloc.free_range(SlotId(4), SlotId(6));
+    assert_eq!(alloc.allocated_count(), 6);
+    // smallest run which fits
+    assert_eq!(alloc.alloc_range(2), (SlotId(4), SlotId(5)));
+    assert_eq!(alloc.alloc_range(1), (SlotId(2), SlotId(2)));
+    // freed ids at the end are merged with new ones
+    alloc.free_range(SlotId(8), SlotId(9));
+    assert_eq!(alloc.alloc_range(4), (SlotId(8), SlotId(11)));
+    assert_eq!(alloc.alloc(), SlotId(6));
+    assert_eq!(alloc.alloc(), SlotId(12));
+    assert_eq!(alloc.allocated_count(), 13);
+}
+
+#[test]
+fn free_range_all_or_nothing() {
+    let mut alloc = IdAllocator::<SlotId>::new();
+    let _ = alloc.alloc_range(5);
+    alloc.free(SlotId(3));
+    let err = alloc.try_free_range(SlotId(1), SlotId(4)).unwrap_err();
+    assert!(err.is_double_free());
+    assert_eq!(err.id(), SlotId(3));
+    let err = alloc.try_free_range(SlotId(4), SlotId(7)).unwrap_err();
+    assert!(!err.is_double_free());
+    assert_eq!(err.id(), SlotId(5));
+    // nothing was freed by the failed calls
+    assert_eq!(
+        alloc.iter_allocated().collect::<Vec<_>>(),
+        [SlotId(0), SlotId(1), SlotId(2), SlotId(4)]
+    );
+    alloc.free_range(SlotId(0), SlotId(2));
+    assert_eq!(alloc.allocated_count(), 1);
+}
+
+#[test]
+fn reusing_range_exhausted() {
+    let mut alloc = IdAllocator::<NonZeroU8>::new();
+    let _ = alloc.alloc_range(250);
+    let three = NonZeroU8::new(3).unwrap();
+    alloc.free(three);
+    assert!(alloc.try_alloc_range(6).is_err());
+    assert_eq!(alloc.allocated_count(), 249);
+    assert_eq!(alloc.alloc(), three);
+}
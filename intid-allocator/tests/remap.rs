@@ -0,0 +1,106 @@
+#![allow(missing_docs)]
+#![cfg(all(feature = "idmap", not(loom)))]
+
+use idmap::{DirectIdMap, DirectIdSet};
+use intid_allocator::{IdAllocator, IdRemap};
+
+intid::define_newtype_counter! {
+    struct SlotId(u32);
+}
+
+#[test]
+fn compact() {
+    let mut alloc = IdAllocator::<SlotId>::new();
+    let mut values = DirectIdMap::new();
+    let mut marked = DirectIdSet::new();
+    for i in 0..8 {
+        let id = alloc.alloc();
+        values.insert(id, i);
+        if i % 3 == 0 {
+            marked.insert(id);
+        }
+    }
+    for i in [1, 2, 5] {
+        alloc.free(SlotId(i));
+        values.remove(SlotId(i));
+    }
+
+    let remap = alloc.compact();
+    assert_eq!(remap.len(), 5);
+    assert!(!remap.is_identity());
+    assert_eq!(remap.get(SlotId(0)), Some(SlotId(0)));
+    assert_eq!(remap.get(SlotId(3)), Some(SlotId(1)));
+    assert_eq!(remap.get(SlotId(7)), Some(SlotId(4)));
+    assert_eq!(remap.get(SlotId(2)), None);
+    assert_eq!(
+        alloc.iter_allocated().collect::<Vec<_>>(),
+        (0..5).map(SlotId).collect::<Vec<_>>()
+    );
+    assert_eq!(alloc.allocated_count(), 5);
+    assert!(alloc.try_free(SlotId(5)).is_err());
+    assert_eq!(alloc.alloc(), SlotId(5));
+
+    remap.apply_to_map(&mut values);
+    assert_eq!(
+        values.iter().map(|(id, &v)| (id, v)).collect::<Vec<_>>(),
+        vec![
+            (SlotId(0), 0),
+            (SlotId(1), 3),
+            (SlotId(2), 4),
+            (SlotId(3), 6),
+            (SlotId(4), 7)
+        ]
+    );
+    // members which were freed are dropped
+    remap.apply_to_set(&mut marked);
+    assert_eq!(
+        marked.iter().collect::<Vec<_>>(),
+        vec![SlotId(0), SlotId(1), SlotId(3)]
+    );
+}
+
+#[test]
+fn compact_with_start() {
+    let mut alloc = IdAllocator::<SlotId>::with_start(SlotId(10));
+    let _ = alloc.alloc_range(3);
+    alloc.free(SlotId(10));
+    let remap = alloc.compact();
+    assert_eq!(
+        remap.into_iter().collect::<Vec<_>>(),
+        vec![(SlotId(11), SlotId(10)), (SlotId(12), SlotId(11))]
+    );
+    assert_eq!(alloc.alloc(), SlotId(12));
+
+    let mut empty = IdAllocator::<SlotId>::new();
+    let _ = empty.alloc();
+    empty.free(SlotId(0));
+    assert!(empty.compact().is_empty());
+    assert_eq!(empty.alloc(), SlotId(0));
+}
+
+#[test]
+#[should_panic = "Stale checkpoint"]
+fn compact_invalidates_checkpoints() {
+    let mut alloc = IdAllocator::<SlotId>::new();
+    let checkpoint = alloc.checkpoint();
+    let _ = alloc.alloc();
+    let _ = alloc.compact();
+    alloc.rollback_to(checkpoint);
+}
+
+#[test]
+fn compacting() {
+    let remap = IdRemap::compacting([SlotId(40), SlotId(7), SlotId(40), SlotId(19)]);
+    assert_eq!(
+        remap.iter().collect::<Vec<_>>(),
+        vec![
+            (SlotId(7), SlotId(0)),
+            (SlotId(19), SlotId(1)),
+            (SlotId(40), SlotId(2))
+        ]
+    );
+    let mut alloc = IdAllocator::rebuild_from_used(remap.iter().map(|(_, new)| new));
+    assert_eq!(alloc.alloc(), SlotId(3));
+
+    assert!(IdRemap::compacting([SlotId(0), SlotId(1)]).is_identity());
+}
@@ -0,0 +1,123 @@
+#![allow(missing_docs)]
+#![cfg(all(feature = "alloc", not(loom)))]
+
+use intid_allocator::{IdAllocator, ReusePolicy};
+
+intid::define_newtype_counter! {
+    struct SlotId(u32);
+}
+
+fn allocator(policy: ReusePolicy) -> IdAllocator<SlotId> {
+    let mut alloc = IdAllocator::new().with_reuse_policy(policy);
+    let _ = alloc.alloc_range(6);
+    for id in [3, 1, 4] {
+        alloc.free(SlotId(id));
+    }
+    alloc
+}
+
+fn drain(alloc: &mut IdAllocator<SlotId>, count: usize) -> Vec<u32> {
+    (0..count).map(|_| alloc.alloc().0).collect()
+}
+
+#[test]
+fn orders() {
+    assert_eq!(ReusePolicy::default(), ReusePolicy::SmallestFirst);
+    assert_eq!(
+        drain(&mut allocator(ReusePolicy::SmallestFirst), 4),
+        [1, 3, 4, 6]
+    );
+    assert_eq!(drain(&mut allocator(ReusePolicy::Fifo), 4), [3, 1, 4, 6]);
+    assert_eq!(drain(&mut allocator(ReusePolicy::Lifo), 4), [4, 1, 3, 6]);
+}
+
+#[test]
+fn quarantine() {
+    let mut alloc = allocator(ReusePolicy::Quarantine { delay: 3 });
+    assert_eq!(alloc.reuse_policy(), ReusePolicy::Quarantine { delay: 3 });
+    // only two frees have happened since 3 was freed, so a new id is allocated first
+    assert_eq!(drain(&mut alloc, 4), [6, 3, 1, 4]);
+    assert_eq!(alloc.allocated_count(), 7);
+
+    // a double free is still detected while quarantined
+    alloc.free(SlotId(0));
+    assert!(alloc.try_free(SlotId(0)).unwrap_err().is_double_free());
+    assert!(!alloc.is_allocated(SlotId(0)));
+    assert_eq!(alloc.alloc(), SlotId(7));
+}
+
+#[test]
+fn quarantine_exhausted() {
+    let mut alloc =
+        IdAllocator::<u8>::new().with_reuse_policy(ReusePolicy::Quarantine { delay: 1000 });
+    let _ = alloc.alloc_range(256);
+    alloc.free(7);
+    alloc.free(3);
+    assert_eq!(alloc.remaining(), 2);
+    // quarantined ids are reused early once there are no new ids
+    assert_eq!(alloc.alloc(), 7);
+    assert_eq!(alloc.alloc(), 3);
+    assert!(alloc.try_alloc().is_err());
+}
+
+#[test]
+fn quarantine_ranges() {
+    let mut alloc =
+        IdAllocator::<SlotId>::new().with_reuse_policy(ReusePolicy::Quarantine { delay: 3 });
+    let _ = alloc.alloc_range(2);
+    alloc.free(SlotId(0));
+    assert_eq!(alloc.alloc(), SlotId(2));
+    // each id in a range counts towards the delay, just like single allocations
+    assert_eq!(alloc.alloc_range(2), (SlotId(3), SlotId(4)));
+    assert_eq!(alloc.alloc(), SlotId(0));
+
+    // and so does each id in a freed range
+    alloc.free_range(SlotId(3), SlotId(4));
+    assert_eq!(drain(&mut alloc, 3), [5, 6, 3]);
+}
+
+#[test]
+fn ranges_skip_freed_ids() {
+    let mut alloc = allocator(ReusePolicy::Fifo);
+    alloc.free(SlotId(5));
+    assert_eq!(alloc.alloc_range(2), (SlotId(6), SlotId(7)));
+    assert_eq!(drain(&mut alloc, 2), [3, 1]);
+}
+
+#[test]
+fn change_policy() {
+    let alloc = allocator(ReusePolicy::SmallestFirst);
+    let mut alloc = alloc.with_reuse_policy(ReusePolicy::Lifo);
+    // existing ids are queued in order of their value
+    assert_eq!(drain(&mut alloc, 3), [4, 3, 1]);
+}
+
+#[test]
+fn rollback() {
+    for policy in [
+        ReusePolicy::Fifo,
+        ReusePolicy::Lifo,
+        ReusePolicy::Quarantine { delay: 2 },
+    ] {
+        let mut expected = allocator(policy);
+        let expected = drain(&mut expected, 5);
+
+        let mut alloc = allocator(policy);
+        let checkpoint = alloc.checkpoint();
+        let _ = drain(&mut alloc, 2);
+        alloc.free(SlotId(0));
+        alloc.free_range(SlotId(5), SlotId(5));
+        let _ = alloc.alloc();
+        alloc.rollback_to(checkpoint);
+        assert_eq!(drain(&mut alloc, 5), expected, "{policy:?}");
+    }
+}
+
+#[test]
+#[should_panic = "Stale checkpoint"]
+fn change_policy_invalidates_checkpoints() {
+    let mut alloc = IdAllocator::<SlotId>::new();
+    let checkpoint = alloc.checkpoint();
+    alloc = alloc.with_reuse_policy(ReusePolicy::Fifo);
+    alloc.rollback_to(checkpoint);
+}
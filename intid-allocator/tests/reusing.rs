@@ -0,0 +1,87 @@
+#![allow(missing_docs)]
+#![cfg(all(feature = "alloc", not(loom)))]
+use core::num::NonZeroU8;
+
+use intid_allocator::IdAllocator;
+
+intid::define_newtype_counter! {
+    struct SlotId(u32);
+}
+
+#[test]
+fn reuses_smallest_free_id() {
+    let mut alloc = IdAllocator::<SlotId>::new();
+    let ids = (0..200).map(|_| alloc.alloc()).collect::<Vec<_>>();
+    assert_eq!(ids[199], SlotId(199));
+    alloc.free(SlotId(150));
+    alloc.free(SlotId(70));
+    alloc.free(SlotId(3));
+    assert_eq!(alloc.allocated_count(), 197);
+    assert_eq!(alloc.alloc(), SlotId(3));
+    assert_eq!(alloc.alloc(), SlotId(70));
+    assert_eq!(alloc.alloc(), SlotId(150));
+    assert_eq!(alloc.alloc(), SlotId(200));
+    assert_eq!(alloc.allocated_count(), 201);
+}
+
+#[test]
+fn iterate_allocated() {
+    let mut alloc = IdAllocator::<SlotId>::new();
+    assert_eq!(alloc.iter_allocated().next(), None);
+    for _ in 0..5 {
+        let _ = alloc.alloc();
+    }
+    alloc.free(SlotId(1));
+    alloc.free(SlotId(4));
+    let iter = alloc.iter_allocated();
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.collect::<Vec<_>>(), [SlotId(0), SlotId(2), SlotId(3)]);
+    assert!(alloc.is_allocated(SlotId(2)));
+    assert!(!alloc.is_allocated(SlotId(4)));
+    assert!(!alloc.is_allocated(SlotId(5)));
+    alloc.free_all();
+    assert_eq!(alloc.allocated_count(), 0);
+    assert!(!alloc.is_allocated(SlotId(0)));
+    assert_eq!(alloc.alloc(), SlotId(0));
+}
+
+#[test]
+fn invalid_free() {
+    let mut alloc = IdAllocator::<SlotId>::with_start(SlotId(10));
+    let id = alloc.alloc();
+    assert_eq!(id, SlotId(10));
+    let err = alloc.try_free(SlotId(11)).unwrap_err();
+    assert!(!err.is_double_free());
+    assert_eq!(err.to_string(), "Id SlotId(11) was never allocated");
+    assert!(!alloc.try_free(SlotId(9)).unwrap_err().is_double_free());
+    alloc.free(id);
+    let err = alloc.try_free(id).unwrap_err();
+    assert!(err.is_double_free());
+    assert_eq!(err.id(), id);
+    assert_eq!(err.to_string(), "Id SlotId(10) was already freed");
+    assert_eq!(alloc.allocated_count(), 0);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "Id SlotId(3) was already freed")]
+fn double_free_panics() {
+    let mut alloc = IdAllocator::<SlotId>::with_start(SlotId(3));
+    let id = alloc.alloc();
+    alloc.free(id);
+    alloc.free(id);
+}
+
+#[test]
+fn free_after_exhaustion() {
+    let mut alloc = IdAllocator::<NonZeroU8>::new();
+    for _ in 1..=u8::MAX {
+        let _ = alloc.alloc();
+    }
+    assert!(alloc.try_alloc().is_err());
+    let id = NonZeroU8::new(42).unwrap();
+    alloc.free(id);
+    assert_eq!(alloc.iter_allocated().len(), 254);
+    assert_eq!(alloc.alloc(), id);
+    assert!(alloc.try_alloc().is_err());
+}
@@ -0,0 +1,133 @@
+#![allow(missing_docs)]
+#![cfg(all(feature = "serde", feature = "alloc", not(loom)))]
+
+use intid_allocator::{IdAllocator, UniqueIdAllocator};
+use serde_test::{assert_de_tokens_error, assert_ser_tokens, Token};
+
+intid::define_newtype_counter! {
+    struct SlotId(u8);
+}
+
+fn allocator_tokens(start: u8, next_id: Option<u8>, free: &[u8]) -> Vec<Token> {
+    let mut tokens = vec![
+        Token::Struct {
+            name: "IdAllocator",
+            len: 3,
+        },
+        Token::Str("start"),
+        Token::U8(start),
+        Token::Str("next_id"),
+    ];
+    tokens.extend(match next_id {
+        Some(next_id) => vec![Token::Some, Token::U8(next_id)],
+        None => vec![Token::None],
+    });
+    tokens.push(Token::Str("free"));
+    tokens.push(Token::Seq {
+        len: Some(free.len()),
+    });
+    tokens.extend(free.iter().map(|&id| Token::U8(id)));
+    tokens.extend([Token::SeqEnd, Token::StructEnd]);
+    tokens
+}
+
+#[test]
+fn unique() {
+    let alloc = UniqueIdAllocator::<SlotId>::new();
+    let _ = alloc.try_alloc_range(3);
+    assert_ser_tokens(&alloc, &[Token::Some, Token::U8(3)]);
+    let _ = alloc.try_alloc_range(253);
+    assert_ser_tokens(&alloc, &[Token::None]);
+    let restored: UniqueIdAllocator<SlotId> = serde_json::from_str("7").unwrap();
+    assert_eq!(restored.alloc(), SlotId(7));
+}
+
+#[cfg(any(feature = "atomic", feature = "portable-atomic"))]
+#[test]
+fn unique_atomic() {
+    use intid_allocator::UniqueIdAllocatorAtomic;
+    let alloc = UniqueIdAllocatorAtomic::<SlotId>::new();
+    let _ = alloc.alloc();
+    assert_ser_tokens(&alloc, &[Token::Some, Token::U8(1)]);
+    let _ = alloc.try_alloc_range(254);
+    assert!(alloc.try_alloc().is_err());
+    assert_ser_tokens(&alloc, &[Token::None]);
+}
+
+#[test]
+fn reusing() {
+    let mut alloc = IdAllocator::<SlotId>::new();
+    let _ = alloc.alloc_range(10);
+    alloc.free(SlotId(7));
+    alloc.free(SlotId(2));
+    assert_ser_tokens(&alloc, &allocator_tokens(0, Some(10), &[2, 7]));
+
+    let mut restored: IdAllocator<SlotId> =
+        serde_json::from_str(r#"{"free": [7, 2], "start": 0, "next_id": 10}"#).unwrap();
+    assert_eq!(restored.allocated_count(), 8);
+    assert!(!restored.is_allocated(SlotId(2)));
+    assert_eq!(restored.alloc(), SlotId(2));
+    assert_eq!(restored.alloc(), SlotId(7));
+    assert_eq!(restored.alloc(), SlotId(10));
+
+    let exhausted = IdAllocator::<SlotId>::rebuild_from_used([SlotId(255)]);
+    assert_ser_tokens(
+        &exhausted,
+        &allocator_tokens(0, None, &(0..255).collect::<Vec<_>>()),
+    );
+    let restored: IdAllocator<SlotId> =
+        serde_json::from_str(r#"{"start": 5, "next_id": null, "free": []}"#).unwrap();
+    assert_eq!(restored.allocated_count(), 251);
+}
+
+#[test]
+fn reusing_invalid() {
+    assert_de_tokens_error::<IdAllocator<SlotId>>(
+        &allocator_tokens(0, Some(3), &[1, 1]),
+        "Id SlotId(1) was already freed",
+    );
+    assert_de_tokens_error::<IdAllocator<SlotId>>(
+        &allocator_tokens(0, Some(3), &[3]),
+        "Id SlotId(3) was never allocated",
+    );
+    assert_de_tokens_error::<IdAllocator<SlotId>>(
+        &allocator_tokens(4, Some(3), &[]),
+        "next id is before the start",
+    );
+}
+
+#[test]
+fn reusing_untrusted() {
+    // a few bytes must not be able to request a huge free bitset
+    let Err(err) = serde_json::from_str::<IdAllocator<u64>>(
+        r#"{"start": 0, "next_id": 1099511627776, "free": [1099511627775]}"#,
+    ) else {
+        panic!("deserialized an allocator with a huge free bitset");
+    };
+    assert!(err.to_string().contains("too far past the start"), "{err}");
+    let restored: IdAllocator<u64> = serde_json::from_str(
+        r#"{"start": 4294967296, "next_id": 4294967306, "free": [4294967300]}"#,
+    )
+    .unwrap();
+    assert_eq!(restored.allocated_count(), 9);
+}
+
+#[test]
+fn rebuild_from_used() {
+    let mut alloc =
+        IdAllocator::<SlotId>::rebuild_from_used([SlotId(5), SlotId(1), SlotId(3), SlotId(5)]);
+    assert_eq!(alloc.allocated_count(), 3);
+    assert_eq!(
+        alloc.iter_allocated().collect::<Vec<_>>(),
+        [SlotId(1), SlotId(3), SlotId(5)]
+    );
+    let stats = alloc.stats();
+    assert_eq!((stats.live, stats.free, stats.allocations), (3, 3, 3));
+    assert_eq!(alloc.alloc(), SlotId(0));
+    assert_eq!(alloc.alloc(), SlotId(2));
+    assert_eq!(alloc.alloc(), SlotId(4));
+    assert_eq!(alloc.alloc(), SlotId(6));
+
+    let empty = IdAllocator::<SlotId>::rebuild_from_used([]);
+    assert_eq!(empty.allocated_count(), 0);
+}
@@ -0,0 +1,85 @@
+#![allow(missing_docs)]
+#![cfg(not(loom))]
+
+use intid_allocator::ShardedIdAllocator;
+
+intid::define_newtype_counter! {
+    struct SlotId(u8);
+}
+
+#[test]
+fn strided() {
+    let sharded = ShardedIdAllocator::<SlotId>::strided(3);
+    assert_eq!(sharded.shard_count(), 3);
+    let shards = (0..3).map(|k| sharded.shard(k)).collect::<Vec<_>>();
+    let mut all = Vec::new();
+    for shard in &shards {
+        let before = shard.remaining();
+        let ids = core::iter::from_fn(|| shard.try_alloc().ok()).collect::<Vec<_>>();
+        assert_eq!(ids.len(), before);
+        assert_eq!(shard.remaining(), 0);
+        for &id in &ids {
+            assert_eq!(sharded.shard_of(id), Some(shard.index()));
+        }
+        all.extend(ids);
+    }
+    assert_eq!(shards[1].try_alloc().unwrap_err().bounds(), None);
+    all.sort_unstable();
+    assert_eq!(all, (0..=255).map(SlotId).collect::<Vec<_>>());
+    assert_eq!(sharded.shard(2).alloc(), SlotId(2));
+    assert_eq!(sharded.shard_of(SlotId(200)), Some(2));
+}
+
+#[test]
+fn blocked() {
+    let sharded = ShardedIdAllocator::<SlotId>::blocked(3);
+    let first = sharded.shard(0);
+    assert_eq!(first.remaining(), 85);
+    assert_eq!(first.alloc(), SlotId(0));
+    assert_eq!(sharded.shard(1).alloc(), SlotId(85));
+    let last = sharded.shard(2);
+    // the last shard gets the remainder
+    assert_eq!(last.remaining(), 86);
+    assert_eq!(last.alloc(), SlotId(170));
+    assert_eq!(sharded.shard_of(SlotId(84)), Some(0));
+    assert_eq!(sharded.shard_of(SlotId(85)), Some(1));
+    assert_eq!(sharded.shard_of(SlotId(255)), Some(2));
+
+    for _ in 1..85 {
+        let _ = first.alloc();
+    }
+    let err = first.try_alloc().unwrap_err();
+    assert_eq!(err.bounds(), Some(SlotId(0)..SlotId(85)));
+
+    let even = ShardedIdAllocator::<SlotId>::blocked(4);
+    assert_eq!(even.shard(3).alloc(), SlotId(192));
+    assert_eq!(even.shard(3).remaining(), 64);
+    let single = ShardedIdAllocator::<SlotId>::blocked(1);
+    assert_eq!(single.shard(0).remaining(), 256);
+    assert_eq!(single.shard_of(SlotId(255)), Some(0));
+}
+
+#[test]
+#[should_panic = "Too many shards"]
+fn too_many_shards() {
+    let _ = ShardedIdAllocator::<SlotId>::strided(257);
+}
+
+#[test]
+#[should_panic = "out of bounds"]
+fn shard_out_of_bounds() {
+    let _ = ShardedIdAllocator::<u32>::blocked(2).shard(2);
+}
+
+#[test]
+fn shard_stats() {
+    let sharded = ShardedIdAllocator::<SlotId>::blocked(4);
+    let shard = sharded.shard(0);
+    for _ in 0..64 {
+        let _ = shard.alloc();
+    }
+    assert!(shard.try_alloc().is_err());
+    let stats = shard.stats();
+    assert_eq!((stats.live, stats.free, stats.high_water_mark), (64, 0, 64));
+    assert_eq!((stats.allocations, stats.exhaustions), (64, 1));
+}
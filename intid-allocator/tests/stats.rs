@@ -0,0 +1,142 @@
+#![allow(missing_docs)]
+#![cfg(not(loom))]
+
+use intid_allocator::{AllocatorStats, SpeculativeIdAllocator, UniqueIdAllocator};
+
+intid::define_newtype_counter! {
+    struct SlotId(u32);
+}
+
+#[test]
+fn unique() {
+    let alloc = UniqueIdAllocator::<SlotId>::with_bounds(SlotId(10), SlotId(14));
+    assert_eq!(alloc.stats(), AllocatorStats::default());
+    let _ = alloc.alloc();
+    let _ = alloc.try_alloc_range(2).unwrap();
+    assert!(alloc.try_alloc_range(2).is_err());
+    let stats = alloc.stats();
+    assert_eq!((stats.live, stats.free, stats.high_water_mark), (3, 0, 3));
+    // computed from the counter, so failed allocations are not counted
+    assert_eq!(
+        (stats.allocations, stats.frees, stats.exhaustions),
+        (3, 0, 0)
+    );
+
+    // rolling back or resetting restarts the totals
+    let alloc = SpeculativeIdAllocator::from(alloc);
+    let checkpoint = alloc.checkpoint();
+    let _ = alloc.alloc();
+    assert_eq!(alloc.stats().allocations, 4);
+    alloc.rollback_to(checkpoint);
+    assert_eq!((alloc.stats().live, alloc.stats().allocations), (3, 3));
+
+    alloc.reset();
+    let _ = alloc.alloc();
+    let stats = alloc.stats();
+    assert_eq!((stats.live, stats.allocations), (1, 1));
+
+    let exhausted = UniqueIdAllocator::<u8>::with_start(254);
+    assert_eq!(exhausted.try_alloc_range(2).unwrap(), (254, 255));
+    assert!(exhausted.try_alloc().is_err());
+    // skipped ids are counted as well
+    assert_eq!(exhausted.stats().live, 256);
+    let skipped = UniqueIdAllocator::<u8>::new();
+    skipped.set_next_id(10);
+    assert_eq!(skipped.stats().high_water_mark, 10);
+}
+
+#[cfg(feature = "alloc")]
+mod reusing {
+    use super::SlotId;
+    use intid_allocator::{GenerationalIdAllocator, IdAllocator};
+
+    #[test]
+    fn stats() {
+        let mut alloc = IdAllocator::<SlotId>::with_bounds(SlotId(0), SlotId(6));
+        let _ = alloc.alloc_range(5);
+        alloc.free(SlotId(1));
+        alloc.free_range(SlotId(3), SlotId(4));
+        let stats = alloc.stats();
+        assert_eq!((stats.live, stats.free, stats.high_water_mark), (2, 3, 5));
+        assert_eq!(
+            (stats.allocations, stats.frees, stats.exhaustions),
+            (5, 3, 0)
+        );
+
+        assert_eq!(alloc.alloc(), SlotId(1));
+        assert!(alloc.try_alloc_range(4).is_err());
+        let stats = alloc.stats();
+        assert_eq!((stats.live, stats.free, stats.allocations), (3, 2, 6));
+        assert_eq!(stats.exhaustions, 1);
+
+        alloc.free_all();
+        let stats = alloc.stats();
+        assert_eq!((stats.live, stats.high_water_mark, stats.frees), (0, 0, 6));
+    }
+
+    #[test]
+    fn max_used_id() {
+        let mut alloc = IdAllocator::<SlotId>::with_start(SlotId(4));
+        assert_eq!(alloc.max_used_id(), None);
+        assert_eq!(alloc.capacity_hint(), 0);
+        let _ = alloc.alloc_range(4);
+        alloc.free(SlotId(7));
+        alloc.free(SlotId(6));
+        assert_eq!(alloc.max_used_id(), Some(SlotId(5)));
+        assert_eq!(alloc.capacity_hint(), 6);
+        alloc.free(SlotId(4));
+        assert_eq!(alloc.max_used_id(), Some(SlotId(5)));
+        alloc.free(SlotId(5));
+        assert_eq!(alloc.max_used_id(), None);
+    }
+
+    #[test]
+    fn generational() {
+        let mut alloc = GenerationalIdAllocator::<SlotId>::new();
+        let first = alloc.alloc();
+        let _ = alloc.alloc();
+        assert!(alloc.free(first));
+        let _ = alloc.alloc();
+        let stats = alloc.stats();
+        assert_eq!((stats.live, stats.high_water_mark), (2, 2));
+        assert_eq!((stats.allocations, stats.frees), (3, 1));
+    }
+}
+
+#[cfg(any(feature = "atomic", feature = "portable-atomic"))]
+#[test]
+fn unique_atomic() {
+    use intid_allocator::UniqueIdAllocatorAtomic;
+    let alloc = UniqueIdAllocatorAtomic::<SlotId>::with_bounds(SlotId(10), SlotId(14));
+    let _ = alloc.alloc();
+    let _ = alloc.try_alloc_range(2).unwrap();
+    let _ = alloc.alloc();
+    // failed allocations may advance the counter, but are not counted as live
+    for _ in 0..3 {
+        assert!(alloc.try_alloc().is_err());
+    }
+    let stats = alloc.stats();
+    assert_eq!((stats.live, stats.high_water_mark), (4, 4));
+    assert_eq!((stats.allocations, stats.exhaustions), (4, 3));
+    alloc.reset();
+    let _ = alloc.alloc();
+    let stats = alloc.stats();
+    assert_eq!((stats.live, stats.allocations), (1, 5));
+}
+
+#[cfg(all(feature = "std", any(feature = "atomic", feature = "portable-atomic")))]
+#[test]
+fn reusing_atomic() {
+    use intid_allocator::IdAllocatorAtomic;
+    let alloc = IdAllocatorAtomic::<SlotId>::new();
+    let ids = (0..4).map(|_| alloc.alloc()).collect::<Vec<_>>();
+    alloc.free(ids[1]);
+    alloc.free(ids[2]);
+    let _ = alloc.alloc();
+    let stats = alloc.stats();
+    assert_eq!((stats.live, stats.free, stats.high_water_mark), (3, 1, 4));
+    assert_eq!(
+        (stats.allocations, stats.frees, stats.exhaustions),
+        (5, 2, 0)
+    );
+}
@@ -0,0 +1,110 @@
+//! Conversions between primitive integers which work in a `const` context,
+//! generic over [`UnsignedPrimInt`].
+//!
+//! These are needed to compute associated constants like [`IntegerId::MIN_ID_INT`](crate::IntegerId::MIN_ID_INT),
+//! since the conversion methods of [`UnsignedPrimInt`] are not `const`.
+
+use core::mem::size_of;
+
+use primint::UnsignedPrimInt;
+
+/// The maximum size of a primitive integer in bytes.
+const MAX_SIZE: usize = size_of::<u128>();
+
+/// An integer followed by zeroed padding,
+/// so that the first [`MAX_SIZE`] bytes are always initialized.
+#[repr(C, packed)]
+struct Padded<I: UnsignedPrimInt> {
+    val: I,
+    padding: [u8; MAX_SIZE],
+}
+impl<I: UnsignedPrimInt> Copy for Padded<I> {}
+impl<I: UnsignedPrimInt> Clone for Padded<I> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+#[repr(C)]
+union ToBytes<I: UnsignedPrimInt> {
+    padded: Padded<I>,
+    bytes: [u8; MAX_SIZE],
+}
+
+#[repr(C)]
+union FromBytes<I: UnsignedPrimInt> {
+    bytes: [u8; MAX_SIZE],
+    val: I,
+}
+
+/// Losslessly convert an integer into a `u128`.
+pub const fn to_u128<I: UnsignedPrimInt>(val: I) -> u128 {
+    let size = size_of::<I>();
+    let native = {
+        let helper = ToBytes {
+            padded: Padded {
+                val,
+                padding: [0; MAX_SIZE],
+            },
+        };
+        // SAFETY: The first `MAX_SIZE` bytes of `Padded` are always initialized
+        unsafe { helper.bytes }
+    };
+    let mut le = [0u8; MAX_SIZE];
+    let mut i = 0;
+    while i < size {
+        le[i] = if cfg!(target_endian = "little") {
+            native[i]
+        } else {
+            native[size - 1 - i]
+        };
+        i += 1;
+    }
+    u128::from_le_bytes(le)
+}
+
+/// Convert a `u128` into an integer, returning `None` on overflow.
+pub const fn from_u128_checked<I: UnsignedPrimInt>(val: u128) -> Option<I> {
+    let size = size_of::<I>();
+    let le = val.to_le_bytes();
+    let mut native = [0u8; MAX_SIZE];
+    let mut i = 0;
+    while i < MAX_SIZE {
+        if i >= size {
+            if le[i] != 0 {
+                return None;
+            }
+        } else if cfg!(target_endian = "little") {
+            native[i] = le[i];
+        } else {
+            native[size - 1 - i] = le[i];
+        }
+        i += 1;
+    }
+    let helper = FromBytes { bytes: native };
+    // SAFETY: All bytes are initialized, and any bit pattern is a valid integer
+    Some(unsafe { helper.val })
+}
+
+/// Convert between integer types, returning `None` on overflow.
+pub const fn checked_cast<I: UnsignedPrimInt, O: UnsignedPrimInt>(val: I) -> Option<O> {
+    from_u128_checked(to_u128(val))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        assert_eq!(to_u128(0x1234u16), 0x1234);
+        assert_eq!(to_u128(u64::MAX), u128::from(u64::MAX));
+        assert_eq!(to_u128(u128::MAX), u128::MAX);
+        assert_eq!(from_u128_checked::<u16>(0x1234), Some(0x1234));
+        assert_eq!(from_u128_checked::<u16>(0x1_0000), None);
+        assert_eq!(from_u128_checked::<u128>(u128::MAX), Some(u128::MAX));
+        assert_eq!(checked_cast::<u64, u8>(255), Some(255u8));
+        assert_eq!(checked_cast::<u64, u8>(256), None);
+        assert_eq!(checked_cast::<usize, u32>(7), Some(7u32));
+    }
+}
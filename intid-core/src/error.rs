@@ -0,0 +1,73 @@
+//! Defines [`InvalidIdError`].
+
+use core::fmt::{self, Debug, Display, Formatter};
+use core::marker::PhantomData;
+
+use crate::IntegerId;
+
+/// Indicates that an integer is not a valid id,
+/// as determined by [`IntegerId::from_int_checked`].
+///
+/// This is returned by [`IntegerId::try_from_int`],
+/// and by the `TryFrom` implementations generated by `#[intid(from)]`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct InvalidIdError<T: IntegerId> {
+    int: T::Int,
+    marker: PhantomData<T>,
+}
+impl<T: IntegerId> InvalidIdError<T> {
+    /// Indicate that the specified integer is not a valid id for the type `T`.
+    ///
+    /// This does not check whether the integer is actually invalid.
+    #[inline]
+    #[cold]
+    pub fn new(int: T::Int) -> Self {
+        InvalidIdError {
+            int,
+            marker: PhantomData,
+        }
+    }
+
+    /// The integer which was rejected.
+    #[inline]
+    pub fn int(&self) -> T::Int {
+        self.int
+    }
+
+    /// Trigger a descriptive panic due to this error.
+    ///
+    /// This gives a better panic message than calling [`Result::unwrap`].
+    ///
+    /// # Panics
+    /// Always.
+    #[track_caller]
+    #[cold]
+    pub fn panic(self) -> ! {
+        panic!("{self}")
+    }
+}
+impl<T: IntegerId> Display for InvalidIdError<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Invalid id for {}: {}",
+            core::any::type_name::<T>(),
+            primint::fmt::debug_desc(self.int)
+        )
+    }
+}
+impl<T: IntegerId> Debug for InvalidIdError<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InvalidIdError")
+            .field("int", &self.int)
+            .field("type_name", &core::any::type_name::<T>())
+            .finish()
+    }
+}
+
+#[rustversion::since(1.81)]
+impl<T: IntegerId> core::error::Error for InvalidIdError<T> {}
+
+#[rustversion::before(1.81)]
+#[cfg(feature = "std")]
+impl<T: IntegerId> std::error::Error for InvalidIdError<T> {}
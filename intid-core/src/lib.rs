@@ -20,6 +20,8 @@
 #![no_std]
 #![cfg_attr(feature = "nightly", feature(never_type,))]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 /// The `primint` crate used to abstract over primitive integer types.
 ///
@@ -34,13 +36,30 @@ use core::fmt::Debug;
 mod macros;
 #[doc(hidden)]
 pub mod array;
+mod const_int;
+mod error;
 mod impls;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+#[cfg(feature = "quickcheck")]
+pub mod quickcheck;
+#[cfg(any(feature = "proptest", feature = "quickcheck"))]
+mod sample;
+#[cfg(feature = "serde")]
+pub mod serde;
 pub mod trusted;
 pub mod uint;
 pub mod utils;
 
+pub use self::error::InvalidIdError;
 pub use primint::UnsignedPrimInt;
 
+/// Implementation details used by the macros.
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::utils::display::{fmt_with_affixes, parse_with_affixes, split_format};
+}
+
 /// An identifier which can be sensibly converted to/from an unsigned integer value.
 ///
 ///
@@ -151,6 +170,19 @@ pub trait IntegerId: Copy + Eq + Debug + Send + Sync + 'static {
     /// returning `None` if the value is invalid.
     fn from_int_checked(id: Self::Int) -> Option<Self>;
 
+    /// Create an id from the underlying integer value,
+    /// returning an [`InvalidIdError`] if the value is invalid.
+    ///
+    /// This is equivalent to [`Self::from_int_checked`],
+    /// but gives an error which can be propagated with `?`.
+    ///
+    /// # Errors
+    /// Fails if the integer is not a valid id.
+    #[inline]
+    fn try_from_int(id: Self::Int) -> Result<Self, InvalidIdError<Self>> {
+        Self::from_int_checked(id).ok_or_else(|| InvalidIdError::new(id))
+    }
+
     /// Create an id from the underlying integer value,
     /// triggering undefined behavior if the value is invalid.
     ///
@@ -8,6 +8,9 @@
 /// because it also derives the secondary traits.
 /// In addition, it reduces build time dependencies by avoiding procedural macros.
 ///
+/// The `#[intid(display = "...")]` attribute is also supported,
+/// with the same meaning as for [`define_newtype_id!`].
+///
 /// [`IntegerIdCounter`]: crate::IntegerIdCounter
 /// [`IntegerId`]: crate::IntegerId
 /// [`define_newtype_id!`]: crate::define_newtype_id
@@ -16,11 +19,11 @@
 #[macro_export]
 macro_rules! define_newtype_counter {
     (
-        $(#[$ty_attr:meta])*
+        $(#[$($ty_attr:tt)*])*
         $vis:vis struct $name:ident($(#[$field_attr:meta])* $inner_vis:vis $inner:ty);
     ) => {
         $crate::define_newtype_id! {
-            $(#[$ty_attr])*
+            $(#[$($ty_attr)*])*
             $vis struct $name($(#[$field_attr])* $inner_vis $inner);
         }
         impl $crate::IntegerIdContiguous for $name {}
@@ -43,15 +46,38 @@ macro_rules! define_newtype_counter {
 ///
 /// See the similar [`define_newtype_counter!`] if you also wish to derive [`IntegerIdCounter`]
 ///
+/// # Display
+/// The attribute `#[intid(display = "n{}")]` implements [`Display`] and [`FromStr`],
+/// replacing the `{}` with the underlying integer value.
+/// See [`impl_newtype_id_display!`] for details.
+/// ```
+/// intid_core::define_newtype_id! {
+///     /// The id of a node in the graph.
+///     #[intid(display = "n{}")]
+///     pub struct NodeId(u32);
+/// }
+/// assert_eq!(NodeId(42).to_string(), "n42");
+/// assert_eq!("n42".parse::<NodeId>().unwrap(), NodeId(42));
+/// ```
+///
 /// [`IntegerIdCounter`]: crate::IntegerIdCounter
 /// [`IntegerId`]: crate::IntegerId
 /// [`define_newtype_id!`]: crate::define_newtype_id
+/// [`impl_newtype_id_display!`]: crate::impl_newtype_id_display
 /// [`Hash`]: core::hash::Hash
 /// [`Debug`]: core::fmt::Debug
+/// [`Display`]: core::fmt::Display
+/// [`FromStr`]: core::str::FromStr
 #[macro_export]
 macro_rules! define_newtype_id {
+    (@parse [$($attrs:tt)*] [$($fmt:literal)?] #[intid(display = $new_fmt:literal)] $($rest:tt)*) => {
+        $crate::define_newtype_id!(@parse [$($attrs)*] [$new_fmt] $($rest)*);
+    };
+    (@parse [$($attrs:tt)*] [$($fmt:literal)?] #[$($attr:tt)*] $($rest:tt)*) => {
+        $crate::define_newtype_id!(@parse [$($attrs)* #[$($attr)*]] [$($fmt)?] $($rest)*);
+    };
     (
-        $(#[$ty_attr:meta])*
+        @parse [$(#[$ty_attr:meta])*] [$($fmt:literal)?]
         $vis:vis struct $name:ident($(#[$field_attr:meta])* $inner_vis:vis $inner:ty);
     ) => {
         $(#[$ty_attr])*
@@ -61,6 +87,67 @@ macro_rules! define_newtype_id {
         impl $crate::IntegerId for $name {
             $crate::impl_newtype_id_body!(for $name($inner));
         }
+        $($crate::impl_newtype_id_display!(for $name, $fmt);)?
+    };
+    (@parse $($tokens:tt)*) => {
+        compile_error!("expected a newtype struct like `pub struct Id(u32);`");
+    };
+    ($($tokens:tt)*) => {
+        $crate::define_newtype_id!(@parse [] [] $($tokens)*);
+    };
+}
+
+/// Implements [`Display`] and [`FromStr`] for an [`IntegerId`],
+/// using a format string like `"n{}"`.
+///
+/// The format string must contain exactly one `{}`,
+/// which is replaced by the underlying integer value.
+/// Parsing fails with a [`ParseIdError`] if the string does not match the format,
+/// or if the integer is not a valid id.
+///
+/// This is used by the `#[intid(display = "...")]` option of [`define_newtype_id!`],
+/// and can be used directly for types defined by hand.
+///
+/// # Examples
+/// ```
+/// # use intid_core::{IntegerId, impl_newtype_id_body, impl_newtype_id_display};
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+/// struct NodeId(u32);
+/// impl IntegerId for NodeId {
+///     impl_newtype_id_body!(for NodeId(u32));
+/// }
+/// impl_newtype_id_display!(for NodeId, "n{}");
+///
+/// assert_eq!(NodeId(42).to_string(), "n42");
+/// assert_eq!("n42".parse::<NodeId>().unwrap(), NodeId(42));
+/// assert!("42".parse::<NodeId>().is_err());
+/// ```
+///
+/// [`Display`]: core::fmt::Display
+/// [`FromStr`]: core::str::FromStr
+/// [`ParseIdError`]: crate::utils::ParseIdError
+/// [`define_newtype_id!`]: crate::define_newtype_id
+#[macro_export]
+macro_rules! impl_newtype_id_display {
+    (for $name:ident, $fmt:literal) => {
+        impl core::fmt::Display for $name {
+            #[inline]
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                const SPLIT: usize = $crate::__private::split_format($fmt);
+                let (prefix, suffix) = $fmt.split_at(SPLIT);
+                $crate::__private::fmt_with_affixes(*self, prefix, &suffix[2..], f)
+            }
+        }
+        impl core::str::FromStr for $name {
+            type Err = $crate::utils::ParseIdError<Self>;
+
+            #[inline]
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                const SPLIT: usize = $crate::__private::split_format($fmt);
+                let (prefix, suffix) = $fmt.split_at(SPLIT);
+                $crate::__private::parse_with_affixes(s, prefix, &suffix[2..])
+            }
+        }
     };
 }
 
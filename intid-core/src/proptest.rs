@@ -0,0 +1,90 @@
+//! Integration with [`proptest`](::proptest), generating arbitrary [`IntegerId`]s.
+//!
+//! Unlike generating a random `T::Int` and converting it,
+//! these strategies respect the range `MIN_ID_INT..=MAX_ID_INT`
+//! and never produce an invalid id.
+//! Generation is biased towards boundary values like [`IntegerId::MIN_ID`] and [`IntegerId::MAX_ID`],
+//! where off-by-one bugs tend to hide.
+//!
+//! # Example
+//! ```
+//! use core::num::NonZeroU16;
+//! use intid_core::proptest::any_id;
+//! use intid_core::IntegerId;
+//! use proptest::prelude::*;
+//!
+//! proptest! {
+//!     fn roundtrip(id in any_id::<NonZeroU16>()) {
+//!         prop_assert_eq!(NonZeroU16::from_int(id.to_int()), id);
+//!     }
+//! }
+//! # roundtrip();
+//! ```
+
+use ::proptest::prelude::{Just, Strategy};
+
+use crate::sample::{int_bounds, nearest_valid, nth_enum_id, to_u128};
+use crate::{EnumId, IntegerId, IntegerIdCounter};
+
+/// The number of values near each boundary that are specially favored.
+const NEAR_BOUNDARY: u128 = 16;
+
+fn biased_ints(min: u128, max: u128, extra: u128) -> impl Strategy<Value = u128> {
+    ::proptest::prop_oneof![
+        1 => Just(min),
+        1 => Just(max),
+        1 => Just(extra),
+        1 => min..=min.saturating_add(NEAR_BOUNDARY).min(max),
+        1 => max.saturating_sub(NEAR_BOUNDARY).max(min)..=max,
+        5 => min..=max,
+    ]
+}
+
+/// A strategy that generates arbitrary valid values of an [`IntegerId`].
+///
+/// Generation is biased towards [`IntegerId::MIN_ID`] and [`IntegerId::MAX_ID`]
+/// and the values near them.
+/// Values shrink towards [`IntegerId::MIN_ID`].
+///
+/// If not all integers are valid ids (like an enum with gaps in its discriminants),
+/// invalid integers are replaced with the next valid id.
+/// Use [`any_enum_id`] to pick uniformly from the variants of an [`EnumId`].
+///
+/// # Panics
+/// Panics if the type is uninhabited, as there are no values to generate.
+#[track_caller]
+pub fn any_id<T: IntegerId>() -> impl Strategy<Value = T> {
+    let (min, max) = int_bounds::<T>();
+    biased_ints(min, max, min).prop_map(nearest_valid::<T>)
+}
+
+/// A strategy that generates arbitrary valid values of an [`IntegerIdCounter`].
+///
+/// This is the same as [`any_id`],
+/// but additionally favors [`IntegerIdCounter::START`].
+///
+/// # Panics
+/// Panics if the type is uninhabited, as there are no values to generate.
+#[track_caller]
+pub fn any_counter_id<T: IntegerIdCounter>() -> impl Strategy<Value = T> {
+    let (min, max) = int_bounds::<T>();
+    biased_ints(min, max, to_u128(T::START_INT)).prop_map(nearest_valid::<T>)
+}
+
+/// A strategy that generates a variant of an [`EnumId`],
+/// choosing uniformly between the valid ids.
+///
+/// Values shrink towards the variant with the smallest integer value.
+///
+/// # Panics
+/// Panics if the type is uninhabited, as there are no values to generate.
+#[track_caller]
+pub fn any_enum_id<T: EnumId>() -> impl Strategy<Value = T> {
+    assert_ne!(
+        T::COUNT,
+        0,
+        "Unable to generate ids for uninhabited type {}",
+        core::any::type_name::<T>()
+    );
+    (0..T::COUNT).prop_map(nth_enum_id::<T>)
+}
@@ -0,0 +1,88 @@
+//! Integration with [`quickcheck`](::quickcheck), generating arbitrary [`IntegerId`]s.
+//!
+//! Since the [`Arbitrary`] trait can't be implemented for every [`IntegerId`],
+//! this uses the [`ArbitraryId`] wrapper.
+
+use alloc::boxed::Box;
+use core::ops::Deref;
+
+use ::quickcheck::{Arbitrary, Gen};
+
+use crate::sample::{int_bounds, nearest_valid, to_u128};
+use crate::IntegerId;
+
+/// The number of values near each boundary that are specially favored.
+const NEAR_BOUNDARY: u8 = 16;
+
+/// A wrapper around an [`IntegerId`] which implements [`Arbitrary`].
+///
+/// Generated values always fall in the range `MIN_ID_INT..=MAX_ID_INT`,
+/// and are always valid ids.
+/// Generation is biased towards [`IntegerId::MIN_ID`] and [`IntegerId::MAX_ID`]
+/// and the values near them.
+/// Values shrink towards [`IntegerId::MIN_ID`].
+///
+/// If not all integers are valid ids (like an enum with gaps in its discriminants),
+/// invalid integers are replaced with the next valid id.
+///
+/// # Example
+/// ```
+/// use intid_core::quickcheck::ArbitraryId;
+/// use quickcheck::QuickCheck;
+///
+/// fn in_range(id: ArbitraryId<core::num::NonZeroU8>) -> bool {
+///     id.get() != 0
+/// }
+/// QuickCheck::new().quickcheck(in_range as fn(_) -> bool);
+/// ```
+///
+/// # Panics
+/// Generating a value panics if the type is uninhabited.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ArbitraryId<T: IntegerId>(pub T);
+impl<T: IntegerId> ArbitraryId<T> {
+    /// Return the wrapped id.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+impl<T: IntegerId> Deref for ArbitraryId<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+impl<T: IntegerId> Arbitrary for ArbitraryId<T> {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let (min, max) = int_bounds::<T>();
+        let near = u128::from(u8::arbitrary(g) % NEAR_BOUNDARY);
+        let value = match g.choose(&[0, 1, 2, 3, 4, 5, 6, 7]).copied() {
+            Some(0) => min,
+            Some(1) => max,
+            Some(2) => min.saturating_add(near).min(max),
+            Some(3) => max.saturating_sub(near).max(min),
+            _ => match (max - min).checked_add(1) {
+                Some(span) => min + (u128::arbitrary(g) % span),
+                // spans the entire range of u128
+                None => u128::arbitrary(g),
+            },
+        };
+        ArbitraryId(nearest_valid(value))
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let (min, _) = int_bounds::<T>();
+        let original = self.0;
+        let offset = to_u128(original.to_int()) - min;
+        Box::new(
+            offset
+                .shrink()
+                .map(move |offset| nearest_valid::<T>(min + offset))
+                .filter(move |&id| id != original)
+                .map(ArbitraryId),
+        )
+    }
+}
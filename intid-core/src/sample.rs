@@ -0,0 +1,73 @@
+//! Shared logic for generating arbitrary ids,
+//! used by both the [`proptest`](crate::proptest) and [`quickcheck`](crate::quickcheck) integrations.
+
+#[cfg(feature = "proptest")]
+use crate::EnumId;
+use crate::IntegerId;
+
+/// The maximum number of integers to scan when searching for a valid id.
+///
+/// Only relevant for non-contiguous ids like C-like enums with gaps in their discriminants.
+const MAX_SCAN: u32 = 4096;
+
+/// The `MIN_ID_INT..=MAX_ID_INT` range of the id as a pair of `u128`s.
+///
+/// # Panics
+/// If the type is uninhabited, there is nothing that can be generated.
+#[track_caller]
+pub(crate) fn int_bounds<T: IntegerId>() -> (u128, u128) {
+    match (T::MIN_ID_INT, T::MAX_ID_INT) {
+        (Some(min), Some(max)) => (to_u128(min), to_u128(max)),
+        _ => panic!(
+            "Unable to generate ids for uninhabited type {}",
+            core::any::type_name::<T>()
+        ),
+    }
+}
+
+#[inline]
+pub(crate) fn to_u128<I: primint::UnsignedPrimInt>(value: I) -> u128 {
+    primint::checked_cast(value).expect("unsigned integer should fit in u128")
+}
+
+/// Map an integer in the range `MIN_ID_INT..=MAX_ID_INT` to a valid id.
+///
+/// If the integer is not valid, this searches upwards (then downwards) for the nearest valid id.
+/// This respects the gaps in a C-like enum or [`EnumId`],
+/// although the resulting distribution will favor the values just after a gap.
+/// Falls back to [`IntegerId::MIN_ID`] if the search fails.
+pub(crate) fn nearest_valid<T: IntegerId>(value: u128) -> T {
+    let (min, max) = int_bounds::<T>();
+    let value = value.clamp(min, max);
+    let try_int = |x: u128| T::from_int_checked(primint::checked_cast(x)?);
+    let upwards = (value..=max).take(MAX_SCAN as usize);
+    let downwards = (min..value).rev().take(MAX_SCAN as usize);
+    upwards
+        .chain(downwards)
+        .find_map(try_int)
+        .or(T::MIN_ID)
+        .expect("type is inhabited")
+}
+
+/// Select the `index`th valid value of an [`EnumId`], wrapping around if out of bounds.
+///
+/// This gives a uniform distribution over all variants, regardless of gaps.
+#[cfg(feature = "proptest")]
+pub(crate) fn nth_enum_id<T: EnumId>(index: u32) -> T {
+    assert_ne!(
+        T::COUNT,
+        0,
+        "Unable to generate ids for uninhabited type {}",
+        core::any::type_name::<T>()
+    );
+    let (min, max) = int_bounds::<T>();
+    (min..=max)
+        .filter_map(|x| T::from_int_checked(primint::checked_cast(x)?))
+        .nth((index % T::COUNT) as usize)
+        .unwrap_or_else(|| {
+            panic!(
+                "EnumId::COUNT for {} exceeds the number of valid ids",
+                core::any::type_name::<T>()
+            )
+        })
+}
@@ -0,0 +1,151 @@
+//! Serialize and deserialize [`IntegerId`]s as their underlying integer value.
+//!
+//! Deriving [`Serialize`] on a newtype id yields whatever the inner field does,
+//! and for C-like enums it yields the variant names.
+//! The helpers in this module always use the integer value from [`IntegerId::to_int`],
+//! and reject invalid integers with a descriptive error when deserializing.
+//!
+//! There are three ways to use this:
+//! 1. The [`as_int`] module, for use with `#[serde(with = "intid::serde::as_int")]`.
+//! 2. The [`AsInt`] wrapper type, which is useful for generic code or nested types like `Vec<AsInt<T>>`.
+//! 3. The `#[intid(serde)]` option of the `IntegerId` derive macro,
+//!    which implements [`Serialize`] and [`Deserialize`] directly using this module.
+//!
+//! # Example
+//! ```
+//! # use intid_core as intid;
+//! # use serde_derive::{Deserialize, Serialize};
+//! #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+//! enum Color {
+//!     Red,
+//!     Green,
+//! }
+//! # impl intid::IntegerId for Color {
+//! #     type Int = u8;
+//! #     const MIN_ID: Option<Self> = Some(Color::Red);
+//! #     const MAX_ID: Option<Self> = Some(Color::Green);
+//! #     const MIN_ID_INT: Option<u8> = Some(0);
+//! #     const MAX_ID_INT: Option<u8> = Some(1);
+//! #     fn from_int_checked(id: u8) -> Option<Self> {
+//! #         match id {
+//! #             0 => Some(Color::Red),
+//! #             1 => Some(Color::Green),
+//! #             _ => None,
+//! #         }
+//! #     }
+//! #     fn to_int(self) -> u8 {
+//! #         self as u8
+//! #     }
+//! # }
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Pixel {
+//!     #[serde(with = "intid::serde::as_int")]
+//!     color: Color,
+//! }
+//! ```
+
+use core::fmt::{self, Formatter};
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+use ::serde::de::{Expected, Unexpected};
+use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::IntegerId;
+
+/// Re-exports used by the `#[intid(serde)]` option of the derive macro.
+#[doc(hidden)]
+pub mod __private {
+    pub use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+}
+
+/// Serialize and deserialize an [`IntegerId`] as its underlying integer,
+/// for use with `#[serde(with = "intid::serde::as_int")]`.
+pub mod as_int {
+    use super::{invalid_id, Deserialize, Deserializer, IntegerId, Serialize, Serializer};
+
+    /// Serialize an id as its underlying integer value.
+    ///
+    /// # Errors
+    /// Propagates any error from the serializer.
+    #[inline]
+    pub fn serialize<T: IntegerId, S: Serializer>(
+        id: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        id.to_int().serialize(serializer)
+    }
+
+    /// Deserialize an id from its underlying integer value.
+    ///
+    /// # Errors
+    /// Fails if the integer is not a valid id according to [`IntegerId::from_int_checked`],
+    /// or if the deserializer fails.
+    #[inline]
+    pub fn deserialize<'de, T: IntegerId, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        let int = T::Int::deserialize(deserializer)?;
+        T::from_int_checked(int).ok_or_else(|| invalid_id::<T, D::Error>(int))
+    }
+}
+
+/// Create an error indicating that the integer is not a valid id.
+#[cold]
+fn invalid_id<T: IntegerId, E: ::serde::de::Error>(int: T::Int) -> E {
+    struct ExpectedId<T>(PhantomData<T>);
+    impl<T> Expected for ExpectedId<T> {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            write!(f, "a valid {}", core::any::type_name::<T>())
+        }
+    }
+    let expected = ExpectedId::<T>(PhantomData);
+    match primint::checked_cast::<T::Int, u64>(int) {
+        Some(int) => E::invalid_value(Unexpected::Unsigned(int), &expected),
+        None => E::custom(format_args!(
+            "invalid value: integer `{int}`, expected {}",
+            &expected as &dyn Expected
+        )),
+    }
+}
+
+/// A wrapper around an [`IntegerId`] which serializes as its underlying integer value.
+///
+/// This is equivalent to [`as_int`], but can be used in generic code and nested types.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[repr(transparent)]
+pub struct AsInt<T: IntegerId>(pub T);
+impl<T: IntegerId> AsInt<T> {
+    /// Return the wrapped id.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+impl<T: IntegerId> From<T> for AsInt<T> {
+    #[inline]
+    fn from(value: T) -> Self {
+        AsInt(value)
+    }
+}
+impl<T: IntegerId> Deref for AsInt<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+impl<T: IntegerId> Serialize for AsInt<T> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        as_int::serialize(&self.0, serializer)
+    }
+}
+impl<'de, T: IntegerId> Deserialize<'de> for AsInt<T> {
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        as_int::deserialize(deserializer).map(AsInt)
+    }
+}
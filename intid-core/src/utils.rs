@@ -1,5 +1,15 @@
 //! Miscellaneous utilities relating to the [`IntegerId`](crate::IntegerId) trait.
 
+pub(crate) mod display;
+mod generational;
+mod narrow;
+mod option;
 mod order;
+mod packed;
 
+pub use self::display::{DisplayInt, ParseIdError, ParseIdErrorKind};
+pub use self::generational::Generational;
+pub use self::narrow::{Narrow, NarrowError};
+pub use self::option::OptionId;
 pub use self::order::OrderByInt;
+pub use self::packed::Packed;
@@ -0,0 +1,206 @@
+//! Implements [`DisplayInt`] and the helpers for formatting and parsing ids with a prefix.
+
+use core::fmt::{self, Debug, Display, Formatter};
+use core::marker::PhantomData;
+use core::num::ParseIntError;
+use core::ops::Deref;
+use core::str::FromStr;
+
+use crate::IntegerId;
+
+/// A wrapper around an [`IntegerId`] which implements [`Display`] and [`FromStr`]
+/// using the underlying integer value.
+///
+/// This is useful in generic code, where the id may not implement [`Display`] itself.
+/// Parsing rejects integers which are not valid ids.
+///
+/// # Example
+/// ```
+/// use core::num::NonZeroU32;
+/// use intid_core::utils::{DisplayInt, ParseIdErrorKind};
+///
+/// let id = NonZeroU32::new(42).unwrap();
+/// assert_eq!(DisplayInt(id).to_string(), "42");
+/// assert_eq!("42".parse::<DisplayInt<NonZeroU32>>().unwrap().0, id);
+/// let err = "0".parse::<DisplayInt<NonZeroU32>>().unwrap_err();
+/// assert_eq!(*err.kind(), ParseIdErrorKind::InvalidId);
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[repr(transparent)]
+pub struct DisplayInt<T: IntegerId>(pub T);
+impl<T: IntegerId> DisplayInt<T> {
+    /// Return the wrapped id.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+impl<T: IntegerId> From<T> for DisplayInt<T> {
+    #[inline]
+    fn from(value: T) -> Self {
+        DisplayInt(value)
+    }
+}
+impl<T: IntegerId> Deref for DisplayInt<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+impl<T: IntegerId> Display for DisplayInt<T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0.to_int(), f)
+    }
+}
+impl<T: IntegerId> FromStr for DisplayInt<T> {
+    type Err = ParseIdError<T>;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_with_affixes(s, "", "").map(DisplayInt)
+    }
+}
+
+/// An error that occurs when parsing an [`IntegerId`] from a string.
+///
+/// Use [`ParseIdError::kind`] to determine the cause of the error.
+#[derive(Clone, Eq, PartialEq)]
+pub struct ParseIdError<T: IntegerId> {
+    kind: ParseIdErrorKind,
+    marker: PhantomData<T>,
+}
+impl<T: IntegerId> ParseIdError<T> {
+    #[cold]
+    fn new(kind: ParseIdErrorKind) -> Self {
+        ParseIdError {
+            kind,
+            marker: PhantomData,
+        }
+    }
+
+    /// The cause of the error.
+    #[inline]
+    pub fn kind(&self) -> &ParseIdErrorKind {
+        &self.kind
+    }
+}
+impl<T: IntegerId> Display for ParseIdError<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let type_name = core::any::type_name::<T>();
+        match self.kind {
+            ParseIdErrorKind::InvalidPrefix => write!(f, "Missing prefix for {type_name}"),
+            ParseIdErrorKind::InvalidSuffix => write!(f, "Missing suffix for {type_name}"),
+            ParseIdErrorKind::InvalidNumber(ref cause) => {
+                write!(f, "Invalid number for {type_name}: {cause}")
+            }
+            ParseIdErrorKind::InvalidId => write!(f, "Integer is not a valid {type_name}"),
+        }
+    }
+}
+impl<T: IntegerId> Debug for ParseIdError<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParseIdError")
+            .field("kind", &self.kind)
+            .field("type_name", &core::any::type_name::<T>())
+            .finish()
+    }
+}
+
+#[rustversion::since(1.81)]
+impl<T: IntegerId> core::error::Error for ParseIdError<T> {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self.kind {
+            ParseIdErrorKind::InvalidNumber(ref cause) => Some(cause),
+            _ => None,
+        }
+    }
+}
+
+#[rustversion::before(1.81)]
+#[cfg(feature = "std")]
+impl<T: IntegerId> std::error::Error for ParseIdError<T> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self.kind {
+            ParseIdErrorKind::InvalidNumber(ref cause) => Some(cause),
+            _ => None,
+        }
+    }
+}
+
+/// The cause of a [`ParseIdError`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ParseIdErrorKind {
+    /// The string did not start with the expected prefix.
+    InvalidPrefix,
+    /// The string did not end with the expected suffix.
+    InvalidSuffix,
+    /// The text between the prefix and suffix is not a valid integer.
+    InvalidNumber(ParseIntError),
+    /// The integer was parsed successfully,
+    /// but [`IntegerId::from_int_checked`] rejected it.
+    InvalidId,
+}
+
+/// Find the index of the `{}` placeholder in a format string like `"n{}"`.
+///
+/// Panics unless the format contains exactly one `{}` and no other braces.
+/// This is a `const fn` so that invalid formats fail at compile time.
+#[doc(hidden)]
+pub const fn split_format(fmt: &str) -> usize {
+    let bytes = fmt.as_bytes();
+    let mut placeholder = None;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'{' if i + 1 < bytes.len() && bytes[i + 1] == b'}' => {
+                assert!(
+                    placeholder.is_none(),
+                    "id format must contain exactly one `{{}}`"
+                );
+                placeholder = Some(i);
+                i += 2;
+            }
+            b'{' | b'}' => panic!("id format must not contain braces besides `{{}}`"),
+            _ => i += 1,
+        }
+    }
+    match placeholder {
+        Some(index) => index,
+        None => panic!("id format must contain exactly one `{{}}`"),
+    }
+}
+
+/// Format an id as `{prefix}{int}{suffix}`.
+#[doc(hidden)]
+#[inline]
+pub fn fmt_with_affixes<T: IntegerId>(
+    id: T,
+    prefix: &str,
+    suffix: &str,
+    f: &mut Formatter<'_>,
+) -> fmt::Result {
+    write!(f, "{prefix}{}{suffix}", id.to_int())
+}
+
+/// Parse an id in the format `{prefix}{int}{suffix}`.
+#[doc(hidden)]
+pub fn parse_with_affixes<T: IntegerId>(
+    s: &str,
+    prefix: &str,
+    suffix: &str,
+) -> Result<T, ParseIdError<T>> {
+    let s = s
+        .strip_prefix(prefix)
+        .ok_or_else(|| ParseIdError::new(ParseIdErrorKind::InvalidPrefix))?;
+    let s = s
+        .strip_suffix(suffix)
+        .ok_or_else(|| ParseIdError::new(ParseIdErrorKind::InvalidSuffix))?;
+    let int = s
+        .parse::<T::Int>()
+        .map_err(|cause| ParseIdError::new(ParseIdErrorKind::InvalidNumber(cause)))?;
+    T::from_int_checked(int).ok_or_else(|| ParseIdError::new(ParseIdErrorKind::InvalidId))
+}
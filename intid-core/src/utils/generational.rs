@@ -0,0 +1,77 @@
+//! Implements [`Generational`].
+
+use core::cmp::Ordering;
+use core::fmt::{self, Debug, Formatter};
+use core::hash::{Hash, Hasher};
+
+use primint::UnsignedPrimInt;
+
+use crate::IntegerIdCounter;
+
+/// An id paired with a generation,
+/// which detects use of a stale id after its index has been reused.
+///
+/// Reusing freed ids keeps lookup tables dense,
+/// but a stale handle would then silently alias whatever object reused its index.
+/// Each time an index is freed, its generation is incremented,
+/// so stale handles can be detected by comparing generations.
+///
+/// This is only a handle, and does not track any state itself.
+/// See `GenerationalIdAllocator` in the `intid-allocator` crate,
+/// and `GenArena` in the `idmap` crate.
+///
+/// Ordering compares the integer value of the index, then the generation.
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub struct Generational<I: IntegerIdCounter, G: UnsignedPrimInt = u32> {
+    index: I,
+    generation: G,
+}
+impl<I: IntegerIdCounter, G: UnsignedPrimInt> Generational<I, G> {
+    /// Pair the specified index with a generation.
+    #[inline]
+    pub const fn new(index: I, generation: G) -> Self {
+        Generational { index, generation }
+    }
+
+    /// The index of this id, which may be reused by later generations.
+    #[inline]
+    pub fn index(self) -> I {
+        self.index
+    }
+
+    /// The generation of this id.
+    #[inline]
+    pub fn generation(self) -> G {
+        self.generation
+    }
+}
+impl<I: IntegerIdCounter, G: UnsignedPrimInt> Ord for Generational<I, G> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.index
+            .to_int()
+            .cmp(&other.index.to_int())
+            .then(self.generation.cmp(&other.generation))
+    }
+}
+impl<I: IntegerIdCounter, G: UnsignedPrimInt> PartialOrd for Generational<I, G> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<I: IntegerIdCounter, G: UnsignedPrimInt> Hash for Generational<I, G> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.to_int().hash(state);
+        self.generation.hash(state);
+    }
+}
+impl<I: IntegerIdCounter, G: UnsignedPrimInt> Debug for Generational<I, G> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Generational")
+            .field("index", &self.index)
+            .field("generation", &self.generation)
+            .finish()
+    }
+}
@@ -0,0 +1,202 @@
+//! Implements [`Narrow`].
+
+use core::cmp::Ordering;
+use core::fmt::{self, Debug, Display, Formatter};
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+
+use primint::UnsignedPrimInt;
+
+use crate::{const_int, IntegerId, IntegerIdContiguous};
+
+/// Stores an [`IntegerId`] using a narrower integer type `N`.
+///
+/// This is useful when ids use a wide integer type like `u64` for API stability,
+/// but the actual values fit in a `u32` or `u16`.
+/// Storing narrowed ids in a collection can substantially reduce memory usage.
+///
+/// Construction is checked and fails if the integer value does not fit in `N`.
+/// Implements [`IntegerId`] with `Int = N` if the wrapped type is [`IntegerIdContiguous`],
+/// so it can be used directly as a key in an id map.
+///
+/// # Example
+/// ```
+/// use intid_core::utils::Narrow;
+/// use intid_core::IntegerId;
+/// intid_core::define_newtype_counter! {
+///     struct NodeId(u64);
+/// }
+/// let small = Narrow::<NodeId, u16>::new(NodeId(300)).unwrap();
+/// assert_eq!(small.get(), NodeId(300));
+/// assert_eq!(small.to_int(), 300u16);
+/// assert_eq!(core::mem::size_of_val(&small), 2);
+/// assert!(Narrow::<NodeId, u16>::new(NodeId(70_000)).is_none());
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq)]
+#[repr(transparent)]
+pub struct Narrow<T: IntegerId, N: UnsignedPrimInt> {
+    int: N,
+    marker: PhantomData<T>,
+}
+impl<T: IntegerId, N: UnsignedPrimInt> Narrow<T, N> {
+    /// Narrow the specified id,
+    /// returning `None` if its integer value does not fit in `N`.
+    #[inline]
+    pub fn new(id: T) -> Option<Self> {
+        primint::checked_cast::<T::Int, N>(id.to_int()).map(|int| Narrow {
+            int,
+            marker: PhantomData,
+        })
+    }
+
+    /// Narrow the specified id,
+    /// returning an error if its integer value does not fit in `N`.
+    ///
+    /// # Errors
+    /// Fails if the integer value of the id does not fit in `N`.
+    #[inline]
+    pub fn try_new(id: T) -> Result<Self, NarrowError<T, N>> {
+        Self::new(id).ok_or_else(|| NarrowError::new(id))
+    }
+
+    /// Widen this back into the original id.
+    #[inline]
+    pub fn get(self) -> T {
+        // always succeeds, since the value originally came from `T::Int`
+        let wide = primint::wrapping_cast::<N, T::Int>(self.int);
+        // SAFETY: The integer either came from `T::to_int`,
+        // was validated by `T::from_int_checked`,
+        // or was guaranteed valid by the caller of `Self::from_int_unchecked`.
+        unsafe { T::from_int_unchecked(wide) }
+    }
+}
+impl<T: IntegerIdContiguous, N: UnsignedPrimInt> IntegerId for Narrow<T, N> {
+    type Int = N;
+    const MIN_ID: Option<Self> = match Self::MIN_ID_INT {
+        Some(int) => Some(Narrow {
+            int,
+            marker: PhantomData,
+        }),
+        None => None,
+    };
+    const MAX_ID: Option<Self> = match Self::MAX_ID_INT {
+        Some(int) => Some(Narrow {
+            int,
+            marker: PhantomData,
+        }),
+        None => None,
+    };
+    const MIN_ID_INT: Option<N> = match T::MIN_ID_INT {
+        // if the minimum doesn't fit, no ids fit
+        Some(min) => const_int::checked_cast(min),
+        None => None,
+    };
+    const MAX_ID_INT: Option<N> = match (Self::MIN_ID_INT, T::MAX_ID_INT) {
+        (Some(_), Some(max)) => match const_int::checked_cast(max) {
+            Some(max) => Some(max),
+            // since T is contiguous, every integer up to `N::MAX` is valid
+            None => Some(primint::max_value::<N>()),
+        },
+        _ => None,
+    };
+    const TRUSTED_RANGE: Option<crate::trusted::TrustedRangeToken<Self>> = {
+        // SAFETY: Narrowing preserves the range of `T`, clamped to the range of `N`.
+        // Values outside the range of `N` are rejected at construction.
+        unsafe { crate::trusted::TrustedRangeToken::assume_valid_if::<T>() }
+    };
+
+    #[inline]
+    fn from_int_checked(int: N) -> Option<Self> {
+        let wide = primint::checked_cast::<N, T::Int>(int)?;
+        T::from_int_checked(wide)?;
+        Some(Narrow {
+            int,
+            marker: PhantomData,
+        })
+    }
+
+    #[inline]
+    unsafe fn from_int_unchecked(int: N) -> Self {
+        Narrow {
+            int,
+            marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn to_int(self) -> N {
+        self.int
+    }
+}
+impl<T: IntegerIdContiguous, N: UnsignedPrimInt> IntegerIdContiguous for Narrow<T, N> {}
+impl<T: IntegerId, N: UnsignedPrimInt> Ord for Narrow<T, N> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.int.cmp(&other.int)
+    }
+}
+impl<T: IntegerId, N: UnsignedPrimInt> PartialOrd for Narrow<T, N> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<T: IntegerId, N: UnsignedPrimInt> Hash for Narrow<T, N> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.int.hash(state);
+    }
+}
+impl<T: IntegerId, N: UnsignedPrimInt> Debug for Narrow<T, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.get(), f)
+    }
+}
+
+/// Indicates that an id could not be stored in a [`Narrow`],
+/// because its integer value does not fit in `N`.
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub struct NarrowError<T: IntegerId, N: UnsignedPrimInt> {
+    id: T,
+    marker: PhantomData<N>,
+}
+impl<T: IntegerId, N: UnsignedPrimInt> NarrowError<T, N> {
+    #[cold]
+    fn new(id: T) -> Self {
+        NarrowError {
+            id,
+            marker: PhantomData,
+        }
+    }
+
+    /// The id which did not fit.
+    #[inline]
+    pub fn id(&self) -> T {
+        self.id
+    }
+}
+impl<T: IntegerId, N: UnsignedPrimInt> Display for NarrowError<T, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Id {:?} does not fit in {}",
+            self.id,
+            core::any::type_name::<N>()
+        )
+    }
+}
+impl<T: IntegerId, N: UnsignedPrimInt> Debug for NarrowError<T, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NarrowError")
+            .field("id", &self.id)
+            .field("narrow_type", &core::any::type_name::<N>())
+            .finish()
+    }
+}
+
+#[rustversion::since(1.81)]
+impl<T: IntegerId, N: UnsignedPrimInt> core::error::Error for NarrowError<T, N> {}
+
+#[rustversion::before(1.81)]
+#[cfg(feature = "std")]
+impl<T: IntegerId, N: UnsignedPrimInt> std::error::Error for NarrowError<T, N> {}
@@ -0,0 +1,161 @@
+//! Implements [`OptionId`].
+
+use core::fmt::{self, Debug, Formatter};
+use core::hash::{Hash, Hasher};
+
+use crate::IntegerId;
+
+/// An optional [`IntegerId`], which is the same size as the underlying integer.
+///
+/// Unlike `Option<T>`, this never needs extra space for the discriminant.
+/// Instead, `None` is represented by an integer which is never a valid id:
+/// the maximum value of [`IntegerId::Int`] if it is greater than [`IntegerId::MAX_ID_INT`],
+/// otherwise zero if it is less than [`IntegerId::MIN_ID_INT`].
+///
+/// Using this type with an id that covers the entire range of its integer type
+/// is a compile-time error.
+///
+/// # Example
+/// ```
+/// use core::num::NonZeroU32;
+/// use intid_core::utils::OptionId;
+///
+/// let mut parent = OptionId::<NonZeroU32>::NONE;
+/// assert_eq!(core::mem::size_of_val(&parent), 4);
+/// assert_eq!(parent.get(), None);
+/// parent.set(NonZeroU32::new(7));
+/// assert_eq!(parent.take(), NonZeroU32::new(7));
+/// assert!(parent.is_none());
+/// ```
+///
+/// Every `u32` is a valid id, so there is no way to represent `None`:
+/// ```compile_fail
+/// # use intid_core::utils::OptionId;
+/// let parent = OptionId::<u32>::NONE;
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq)]
+#[repr(transparent)]
+pub struct OptionId<T: IntegerId> {
+    int: T::Int,
+}
+impl<T: IntegerId> OptionId<T> {
+    /// The integer value used to represent `None`.
+    ///
+    /// Evaluating this fails at compile time if there is no integer value
+    /// outside the range `MIN_ID_INT..=MAX_ID_INT`.
+    const SENTINEL: T::Int = match (T::MIN_ID_INT, T::MAX_ID_INT) {
+        // uninhabited types never use any integer value
+        (None, _) | (_, None) => primint::max_value::<T::Int>(),
+        (Some(min), Some(max)) => {
+            if primint::NonMax::new(max).is_some() {
+                primint::max_value::<T::Int>()
+            } else if primint::NonZero::new(min).is_some() {
+                primint::zero::<T::Int>()
+            } else {
+                panic!("OptionId requires an integer value which is never a valid id")
+            }
+        }
+    };
+
+    /// An [`OptionId`] which is `None`.
+    pub const NONE: Self = OptionId {
+        int: Self::SENTINEL,
+    };
+
+    /// Create an [`OptionId`] from an [`Option`].
+    #[inline]
+    pub fn new(value: Option<T>) -> Self {
+        match value {
+            Some(id) => Self::some(id),
+            None => Self::NONE,
+        }
+    }
+
+    /// Create an [`OptionId`] which contains the specified id.
+    #[inline]
+    pub fn some(id: T) -> Self {
+        let int = id.to_int();
+        debug_assert!(
+            int != Self::SENTINEL,
+            "Invalid id {id:?} conflicts with the representation of `None`"
+        );
+        OptionId { int }
+    }
+
+    /// Get the contained id, or `None` if it is empty.
+    #[inline]
+    pub fn get(self) -> Option<T> {
+        if self.int == Self::SENTINEL {
+            None
+        } else {
+            // SAFETY: The integer came from `T::to_int`,
+            // and the IntegerId trait requires that converting it back is safe.
+            Some(unsafe { T::from_int_unchecked(self.int) })
+        }
+    }
+
+    /// Set the contained id, or clear it if `None`.
+    #[inline]
+    pub fn set(&mut self, value: Option<T>) {
+        *self = Self::new(value);
+    }
+
+    /// Take the contained id, leaving `None` in its place.
+    #[inline]
+    pub fn take(&mut self) -> Option<T> {
+        core::mem::replace(self, Self::NONE).get()
+    }
+
+    /// Replace the contained id, returning the old value.
+    #[inline]
+    pub fn replace(&mut self, id: T) -> Option<T> {
+        core::mem::replace(self, Self::some(id)).get()
+    }
+
+    /// Check if this contains an id.
+    #[inline]
+    pub fn is_some(self) -> bool {
+        self.int != Self::SENTINEL
+    }
+
+    /// Check if this is empty.
+    #[inline]
+    pub fn is_none(self) -> bool {
+        self.int == Self::SENTINEL
+    }
+}
+impl<T: IntegerId> Default for OptionId<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+impl<T: IntegerId> Hash for OptionId<T> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.int.hash(state);
+    }
+}
+impl<T: IntegerId> Debug for OptionId<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.get(), f)
+    }
+}
+impl<T: IntegerId> From<T> for OptionId<T> {
+    #[inline]
+    fn from(id: T) -> Self {
+        Self::some(id)
+    }
+}
+impl<T: IntegerId> From<Option<T>> for OptionId<T> {
+    #[inline]
+    fn from(value: Option<T>) -> Self {
+        Self::new(value)
+    }
+}
+impl<T: IntegerId> From<OptionId<T>> for Option<T> {
+    #[inline]
+    fn from(value: OptionId<T>) -> Self {
+        value.get()
+    }
+}
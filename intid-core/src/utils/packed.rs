@@ -0,0 +1,200 @@
+//! Implements [`Packed`].
+
+use core::cmp::Ordering;
+use core::fmt::{self, Debug, Formatter};
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+
+use primint::UnsignedPrimInt;
+
+use crate::const_int::{from_u128_checked, to_u128};
+use crate::{IntegerId, IntegerIdContiguous};
+
+/// A pair of ids packed into a single integer,
+/// with the first id `A` in the upper bits and the second id `B` in the lower `BITS_B` bits.
+///
+/// This replaces encoding keys like `(FunctionId, LocalIdx)` by hand with shifts and masks.
+/// The packed integer has type `I`, which defaults to `u64`.
+///
+/// Construction is checked, and fails if `B` doesn't fit in `BITS_B` bits
+/// or `A` doesn't fit in the remaining bits of `I`.
+/// Ordering is consistent with the packed integer,
+/// so sorting (or iterating over a map keyed by this type) orders by `A` then `B`.
+///
+/// Implements [`IntegerId`] with `Int = I` if both `A` and `B` are [`IntegerIdContiguous`].
+/// Note that the packed id is usually not contiguous itself,
+/// since the range of `B` may not fill all `BITS_B` bits.
+///
+/// Using a `BITS_B` which is zero or leaves no bits for `A` is a compile-time error.
+///
+/// # Example
+/// ```
+/// use intid_core::utils::Packed;
+/// use intid_core::IntegerId;
+/// intid_core::define_newtype_counter! {
+///     struct FunctionId(u32);
+/// }
+/// intid_core::define_newtype_counter! {
+///     struct LocalIdx(u16);
+/// }
+/// type LocalKey = Packed<FunctionId, LocalIdx, 16>;
+///
+/// let key = LocalKey::new(FunctionId(3), LocalIdx(7)).unwrap();
+/// assert_eq!(key.to_int(), (3 << 16) | 7);
+/// assert_eq!(key.parts(), (FunctionId(3), LocalIdx(7)));
+/// assert!(key < LocalKey::new(FunctionId(4), LocalIdx(0)).unwrap());
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub struct Packed<A: IntegerId, B: IntegerId, const BITS_B: u32, I: UnsignedPrimInt = u64> {
+    int: I,
+    marker: PhantomData<(A, B)>,
+}
+impl<A: IntegerId, B: IntegerId, const BITS_B: u32, I: UnsignedPrimInt> Packed<A, B, BITS_B, I> {
+    /// Fails to compile if `BITS_B` does not leave room for both `A` and `B`.
+    const VALID_BITS: () = assert!(
+        BITS_B > 0 && BITS_B < primint::bits::<I>(),
+        "BITS_B must be positive and less than the bits of the packed integer"
+    );
+
+    /// Pack the two integers, returning `None` if either does not fit.
+    const fn pack_ints(a: u128, b: u128) -> Option<I> {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID_BITS;
+        if (b >> BITS_B) != 0 || (a >> (u128::BITS - BITS_B)) != 0 {
+            return None;
+        }
+        from_u128_checked((a << BITS_B) | b)
+    }
+
+    /// Pack the two ids together,
+    /// returning `None` if either of them does not fit in its bits.
+    #[inline]
+    pub fn new(first: A, second: B) -> Option<Self> {
+        Self::pack_ints(to_u128(first.to_int()), to_u128(second.to_int())).map(|int| Packed {
+            int,
+            marker: PhantomData,
+        })
+    }
+
+    /// Split this into its two component ids.
+    #[inline]
+    pub fn parts(self) -> (A, B) {
+        (self.first(), self.second())
+    }
+
+    /// The first id, stored in the upper bits.
+    #[inline]
+    pub fn first(self) -> A {
+        let int = primint::wrapping_cast::<u128, A::Int>(to_u128(self.int) >> BITS_B);
+        // SAFETY: The integer came from `A::to_int` or was validated by `A::from_int_checked`,
+        // or was guaranteed valid by the caller of `Self::from_int_unchecked`.
+        unsafe { A::from_int_unchecked(int) }
+    }
+
+    /// The second id, stored in the lower `BITS_B` bits.
+    #[inline]
+    pub fn second(self) -> B {
+        let mask = (1u128 << BITS_B) - 1;
+        let int = primint::wrapping_cast::<u128, B::Int>(to_u128(self.int) & mask);
+        // SAFETY: The integer came from `B::to_int` or was validated by `B::from_int_checked`,
+        // or was guaranteed valid by the caller of `Self::from_int_unchecked`.
+        unsafe { B::from_int_unchecked(int) }
+    }
+}
+impl<A, B, const BITS_B: u32, I> IntegerId for Packed<A, B, BITS_B, I>
+where
+    A: IntegerIdContiguous,
+    B: IntegerIdContiguous,
+    I: UnsignedPrimInt,
+{
+    type Int = I;
+    const MIN_ID: Option<Self> = match Self::MIN_ID_INT {
+        Some(int) => Some(Packed {
+            int,
+            marker: PhantomData,
+        }),
+        None => None,
+    };
+    const MAX_ID: Option<Self> = match Self::MAX_ID_INT {
+        Some(int) => Some(Packed {
+            int,
+            marker: PhantomData,
+        }),
+        None => None,
+    };
+    const MIN_ID_INT: Option<I> = match (A::MIN_ID_INT, B::MIN_ID_INT) {
+        (Some(a), Some(b)) => Self::pack_ints(to_u128(a), to_u128(b)),
+        _ => None,
+    };
+    const MAX_ID_INT: Option<I> = match (Self::MIN_ID_INT, A::MAX_ID_INT, B::MAX_ID_INT) {
+        (Some(_), Some(a), Some(b)) => {
+            // clamp to the available bits,
+            // which is valid since both ids are contiguous
+            let max_a = to_u128(primint::max_value::<I>()) >> BITS_B;
+            let max_b = (1u128 << BITS_B) - 1;
+            let a = to_u128(a);
+            let b = to_u128(b);
+            Self::pack_ints(
+                if a < max_a { a } else { max_a },
+                if b < max_b { b } else { max_b },
+            )
+        }
+        _ => None,
+    };
+
+    #[inline]
+    fn from_int_checked(int: I) -> Option<Self> {
+        let int = to_u128(int);
+        let a = primint::checked_cast::<u128, A::Int>(int >> BITS_B)?;
+        let b = primint::checked_cast::<u128, B::Int>(int & ((1u128 << BITS_B) - 1))?;
+        Self::new(A::from_int_checked(a)?, B::from_int_checked(b)?)
+    }
+
+    #[inline]
+    unsafe fn from_int_unchecked(int: I) -> Self {
+        Packed {
+            int,
+            marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn to_int(self) -> I {
+        self.int
+    }
+}
+impl<A: IntegerId, B: IntegerId, const BITS_B: u32, I: UnsignedPrimInt> Ord
+    for Packed<A, B, BITS_B, I>
+{
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.int.cmp(&other.int)
+    }
+}
+impl<A: IntegerId, B: IntegerId, const BITS_B: u32, I: UnsignedPrimInt> PartialOrd
+    for Packed<A, B, BITS_B, I>
+{
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<A: IntegerId, B: IntegerId, const BITS_B: u32, I: UnsignedPrimInt> Hash
+    for Packed<A, B, BITS_B, I>
+{
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.int.hash(state);
+    }
+}
+impl<A: IntegerId, B: IntegerId, const BITS_B: u32, I: UnsignedPrimInt> Debug
+    for Packed<A, B, BITS_B, I>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (first, second) = self.parts();
+        f.debug_tuple("Packed")
+            .field(&first)
+            .field(&second)
+            .finish()
+    }
+}
@@ -0,0 +1,104 @@
+#![allow(missing_docs)]
+#![cfg(all(feature = "proptest", feature = "quickcheck"))]
+use core::num::NonZeroU8;
+
+use intid_core::proptest::{any_counter_id, any_enum_id, any_id};
+use intid_core::quickcheck::ArbitraryId;
+use intid_core::{EnumId, IntegerId};
+use proptest::prelude::*;
+use proptest::strategy::ValueTree;
+use quickcheck::{Arbitrary, Gen, QuickCheck};
+
+/// An enum with gaps between its discriminants.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Sparse {
+    A = 3,
+    B = 10,
+    C = 200,
+}
+impl IntegerId for Sparse {
+    type Int = u8;
+    const MIN_ID: Option<Self> = Some(Sparse::A);
+    const MAX_ID: Option<Self> = Some(Sparse::C);
+    const MIN_ID_INT: Option<u8> = Some(3);
+    const MAX_ID_INT: Option<u8> = Some(200);
+
+    fn from_int_checked(id: u8) -> Option<Self> {
+        match id {
+            3 => Some(Sparse::A),
+            10 => Some(Sparse::B),
+            200 => Some(Sparse::C),
+            _ => None,
+        }
+    }
+
+    fn to_int(self) -> u8 {
+        self as u8
+    }
+}
+impl EnumId for Sparse {
+    const COUNT: u32 = 3;
+    type Array<T> = [T; 201];
+    type BitSet = [u64; 4];
+}
+
+intid_core::define_newtype_counter! {
+    struct Counter(NonZeroU8);
+}
+
+proptest! {
+    #[test]
+    fn proptest_nonzero(id in any_id::<NonZeroU8>()) {
+        prop_assert_ne!(id.get(), 0);
+    }
+
+    #[test]
+    fn proptest_counter(id in any_counter_id::<Counter>()) {
+        prop_assert_eq!(Counter::from_int_checked(id.to_int()), Some(id));
+    }
+
+    #[test]
+    fn proptest_sparse(id in any_id::<Sparse>(), uniform in any_enum_id::<Sparse>()) {
+        prop_assert_eq!(Sparse::from_int_checked(id.to_int()), Some(id));
+        prop_assert_eq!(Sparse::from_int_checked(uniform.to_int()), Some(uniform));
+    }
+}
+
+#[test]
+fn proptest_boundaries() {
+    let mut runner = proptest::test_runner::TestRunner::deterministic();
+    let strategy = any_id::<u64>();
+    let values = (0..1000)
+        .map(|_| strategy.new_tree(&mut runner).unwrap().current())
+        .collect::<Vec<_>>();
+    assert!(values.contains(&0));
+    assert!(values.contains(&u64::MAX));
+}
+
+#[test]
+fn quickcheck_valid() {
+    fn nonzero(id: ArbitraryId<NonZeroU8>) -> bool {
+        id.get() != 0
+    }
+    fn sparse(id: ArbitraryId<Sparse>) -> bool {
+        Sparse::from_int_checked(id.to_int()) == Some(*id)
+    }
+    QuickCheck::new().quickcheck(nonzero as fn(_) -> bool);
+    QuickCheck::new().quickcheck(sparse as fn(_) -> bool);
+}
+
+#[test]
+fn quickcheck_shrink() {
+    let mut g = Gen::new(100);
+    for _ in 0..100 {
+        let id = ArbitraryId::<Sparse>::arbitrary(&mut g);
+        for shrunk in id.shrink() {
+            assert!((shrunk.to_int()) < id.to_int(), "{shrunk:?} >= {id:?}");
+        }
+    }
+    assert_eq!(
+        ArbitraryId(NonZeroU8::MIN).shrink().count(),
+        0,
+        "minimum should not shrink"
+    );
+}
@@ -0,0 +1,54 @@
+#![allow(missing_docs)]
+use core::num::NonZeroU16;
+
+use intid_core::utils::{DisplayInt, ParseIdError, ParseIdErrorKind};
+
+intid_core::define_newtype_id! {
+    /// The attribute can follow other attributes.
+    #[derive(Default)]
+    #[intid(display = "n{}")]
+    pub struct NodeId(u32);
+}
+
+intid_core::define_newtype_counter! {
+    #[intid(display = "<edge {}>")]
+    pub struct EdgeId(NonZeroU16);
+}
+
+#[test]
+fn display() {
+    assert_eq!(NodeId(42).to_string(), "n42");
+    assert_eq!(NodeId::default().to_string(), "n0");
+    assert_eq!(EdgeId(NonZeroU16::MIN).to_string(), "<edge 1>");
+    assert_eq!(DisplayInt(EdgeId(NonZeroU16::MAX)).to_string(), "65535");
+}
+
+#[test]
+fn parse() {
+    assert_eq!("n42".parse::<NodeId>().unwrap(), NodeId(42));
+    assert_eq!(
+        "<edge 7>".parse::<EdgeId>().unwrap(),
+        EdgeId(NonZeroU16::new(7).unwrap())
+    );
+    assert_eq!("7".parse::<DisplayInt<NodeId>>().unwrap().0, NodeId(7));
+}
+
+#[test]
+fn parse_errors() {
+    let kind = |res: Result<EdgeId, ParseIdError<EdgeId>>| res.unwrap_err().kind().clone();
+    assert_eq!(kind("edge 7>".parse()), ParseIdErrorKind::InvalidPrefix);
+    assert_eq!(kind("<edge 7".parse()), ParseIdErrorKind::InvalidSuffix);
+    assert!(matches!(
+        kind("<edge x>".parse()),
+        ParseIdErrorKind::InvalidNumber(_)
+    ));
+    assert!(matches!(
+        kind("<edge 70000>".parse()),
+        ParseIdErrorKind::InvalidNumber(_)
+    ));
+    assert_eq!(kind("<edge 0>".parse()), ParseIdErrorKind::InvalidId);
+    assert_eq!(
+        "n".parse::<NodeId>().unwrap_err().to_string(),
+        "Invalid number for display::NodeId: cannot parse integer from empty string"
+    );
+}
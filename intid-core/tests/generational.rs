@@ -0,0 +1,29 @@
+#![allow(missing_docs)]
+use intid_core::utils::Generational;
+
+intid_core::define_newtype_counter! {
+    struct SlotId(u32);
+}
+
+#[test]
+fn accessors_and_ordering() {
+    let id = Generational::<SlotId, u8>::new(SlotId(3), 7);
+    assert_eq!(id.index(), SlotId(3));
+    assert_eq!(id.generation(), 7);
+    assert_ne!(id, Generational::new(SlotId(3), 8));
+    let mut ids = [
+        Generational::<SlotId, u8>::new(SlotId(4), 0),
+        Generational::new(SlotId(3), 1),
+        Generational::new(SlotId(3), 0),
+    ];
+    ids.sort();
+    let sorted = ids
+        .into_iter()
+        .map(|id| (id.index().0, id.generation()))
+        .collect::<Vec<_>>();
+    assert_eq!(sorted, [(3, 0), (3, 1), (4, 0)]);
+    assert_eq!(
+        format!("{id:?}"),
+        "Generational { index: SlotId(3), generation: 7 }"
+    );
+}
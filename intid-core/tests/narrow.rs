@@ -0,0 +1,41 @@
+#![allow(missing_docs)]
+use core::num::NonZeroU64;
+
+use intid_core::utils::Narrow;
+use intid_core::IntegerId;
+
+intid_core::define_newtype_counter! {
+    struct Wide(u64);
+}
+intid_core::define_newtype_counter! {
+    struct WideNonZero(NonZeroU64);
+}
+
+#[test]
+fn bounds() {
+    assert_eq!(<Narrow<Wide, u16>>::MIN_ID_INT, Some(0));
+    assert_eq!(<Narrow<Wide, u16>>::MAX_ID_INT, Some(u16::MAX));
+    assert_eq!(<Narrow<WideNonZero, u8>>::MIN_ID_INT, Some(1));
+    assert_eq!(<Narrow<WideNonZero, u8>>::MAX_ID_INT, Some(u8::MAX));
+    assert_eq!(<Narrow<u8, u32>>::MAX_ID_INT, Some(255));
+    assert_eq!(
+        <Narrow<WideNonZero, u8>>::MAX_ID.map(Narrow::get),
+        Some(WideNonZero(NonZeroU64::new(255).unwrap()))
+    );
+}
+
+#[test]
+fn conversions() {
+    let narrow = Narrow::<Wide, u16>::new(Wide(65_535)).unwrap();
+    assert_eq!(narrow.get(), Wide(65_535));
+    assert_eq!(format!("{narrow:?}"), "Wide(65535)");
+    let err = Narrow::<Wide, u16>::try_new(Wide(65_536)).unwrap_err();
+    assert_eq!(err.id(), Wide(65_536));
+    assert_eq!(err.to_string(), "Id Wide(65536) does not fit in u16");
+    assert_eq!(<Narrow<WideNonZero, u8>>::from_int_checked(0), None);
+    assert_eq!(
+        <Narrow<u8, u32>>::from_int_checked(7).map(Narrow::get),
+        Some(7)
+    );
+    assert_eq!(<Narrow<u8, u32>>::from_int_checked(256), None);
+}
@@ -0,0 +1,78 @@
+#![allow(missing_docs)]
+use core::mem::size_of;
+use core::num::NonZeroU16;
+
+use intid_core::utils::OptionId;
+use intid_core::IntegerId;
+
+/// Every integer except zero is valid,
+/// so `None` must use zero rather than the maximum.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+struct Positive(u8);
+impl IntegerId for Positive {
+    type Int = u8;
+    const MIN_ID: Option<Self> = Some(Positive(1));
+    const MAX_ID: Option<Self> = Some(Positive(u8::MAX));
+    const MIN_ID_INT: Option<u8> = Some(1);
+    const MAX_ID_INT: Option<u8> = Some(u8::MAX);
+
+    fn from_int_checked(id: u8) -> Option<Self> {
+        if id == 0 {
+            None
+        } else {
+            Some(Positive(id))
+        }
+    }
+
+    fn to_int(self) -> u8 {
+        self.0
+    }
+}
+
+intid_core::define_newtype_id! {
+    struct NodeId(NonZeroU16);
+}
+
+#[test]
+fn size() {
+    assert_eq!(size_of::<OptionId<NodeId>>(), size_of::<NodeId>());
+    assert_eq!(size_of::<OptionId<Positive>>(), 1);
+    assert_eq!(size_of::<Option<NodeId>>(), size_of::<NodeId>());
+}
+
+#[test]
+fn sentinel() {
+    // NonZeroU16::MAX is a valid id, so `None` is represented by zero instead
+    let max = NodeId(NonZeroU16::MAX);
+    assert_eq!(OptionId::some(max).get(), Some(max));
+    // SAFETY: OptionId is a transparent wrapper around the integer value
+    let none = unsafe { core::mem::transmute::<OptionId<NodeId>, u16>(OptionId::NONE) };
+    assert_eq!(none, 0);
+    assert_eq!(
+        OptionId::some(Positive(u8::MAX)).get(),
+        Some(Positive(u8::MAX))
+    );
+    assert_eq!(OptionId::some(Positive(1)).get(), Some(Positive(1)));
+    assert_eq!(OptionId::<Positive>::NONE.get(), None);
+    // SAFETY: OptionId is a transparent wrapper around the integer value
+    let none = unsafe { core::mem::transmute::<OptionId<Positive>, u8>(OptionId::NONE) };
+    assert_eq!(none, 0);
+}
+
+#[test]
+fn ops() {
+    let first = NodeId(NonZeroU16::MIN);
+    let second = NodeId(NonZeroU16::new(2).unwrap());
+    let mut opt = OptionId::<NodeId>::default();
+    assert!(opt.is_none());
+    assert_eq!(opt.replace(first), None);
+    assert!(opt.is_some());
+    assert_eq!(opt.replace(second), Some(first));
+    assert_eq!(opt.take(), Some(second));
+    assert_eq!(opt.take(), None);
+    opt.set(Some(first));
+    assert_eq!(Option::from(opt), Some(first));
+    assert_eq!(OptionId::from(Some(second)), OptionId::from(second));
+    assert_eq!(OptionId::<NodeId>::from(None), OptionId::NONE);
+    assert_eq!(format!("{opt:?}"), "Some(NodeId(1))");
+}
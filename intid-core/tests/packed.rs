@@ -0,0 +1,67 @@
+#![allow(missing_docs)]
+use core::num::NonZeroU8;
+
+use intid_core::utils::{OrderByInt, Packed};
+use intid_core::IntegerId;
+
+intid_core::define_newtype_counter! {
+    struct FunctionId(u32);
+}
+intid_core::define_newtype_counter! {
+    struct LocalIdx(u16);
+}
+
+type LocalKey = Packed<FunctionId, LocalIdx, 16>;
+type SmallKey = Packed<NonZeroU8, LocalIdx, 4, u8>;
+
+#[test]
+fn construction() {
+    let key = LocalKey::new(FunctionId(3), LocalIdx(u16::MAX)).unwrap();
+    assert_eq!(key.to_int(), (3 << 16) | 0xFFFF);
+    assert_eq!(key.first(), FunctionId(3));
+    assert_eq!(key.second(), LocalIdx(u16::MAX));
+    assert_eq!(format!("{key:?}"), "Packed(FunctionId(3), LocalIdx(65535))");
+    // second id overflows its bits
+    assert_eq!(SmallKey::new(NonZeroU8::MIN, LocalIdx(16)), None);
+    // first id overflows the remaining bits
+    assert_eq!(
+        SmallKey::new(NonZeroU8::new(16).unwrap(), LocalIdx(0)),
+        None
+    );
+    let small = SmallKey::new(NonZeroU8::new(15).unwrap(), LocalIdx(15)).unwrap();
+    assert_eq!(small.to_int(), 0xFF);
+}
+
+#[test]
+fn bounds() {
+    assert_eq!(LocalKey::MIN_ID_INT, Some(0));
+    assert_eq!(
+        LocalKey::MAX_ID_INT,
+        Some((u64::from(u32::MAX) << 16) | 0xFFFF)
+    );
+    assert_eq!(SmallKey::MIN_ID_INT, Some(0x10));
+    assert_eq!(SmallKey::MAX_ID_INT, Some(0xFF));
+    assert_eq!(SmallKey::from_int_checked(0x0F), None);
+    assert_eq!(
+        SmallKey::from_int_checked(0x23).map(Packed::parts),
+        Some((NonZeroU8::new(2).unwrap(), LocalIdx(3)))
+    );
+}
+
+#[test]
+fn ordering() {
+    let mut keys = [(2, 0), (1, 5), (1, 2), (0, 9)]
+        .map(|(a, b)| LocalKey::new(FunctionId(a), LocalIdx(b)).unwrap());
+    keys.sort();
+    assert_eq!(
+        keys.map(|key| {
+            let (a, b) = key.parts();
+            (a.0, b.0)
+        }),
+        [(0, 9), (1, 2), (1, 5), (2, 0)]
+    );
+    let mut wrapped = keys.map(OrderByInt);
+    wrapped.reverse();
+    wrapped.sort();
+    assert_eq!(wrapped.map(|key| key.0), keys);
+}
@@ -0,0 +1,44 @@
+#![allow(missing_docs)]
+#![cfg(feature = "serde")]
+use core::num::NonZeroU16;
+
+use intid_core::serde::AsInt;
+use serde_derive::{Deserialize, Serialize};
+use serde_test::{assert_de_tokens_error, assert_tokens, Token};
+
+#[derive(Debug, Eq, PartialEq, Serialize, Deserialize)]
+struct Example {
+    #[serde(with = "intid_core::serde::as_int")]
+    id: NonZeroU16,
+    wrapped: AsInt<NonZeroU16>,
+}
+
+#[test]
+fn roundtrip() {
+    let example = Example {
+        id: NonZeroU16::new(7).unwrap(),
+        wrapped: AsInt(NonZeroU16::new(300).unwrap()),
+    };
+    assert_tokens(
+        &example,
+        &[
+            Token::Struct {
+                name: "Example",
+                len: 2,
+            },
+            Token::Str("id"),
+            Token::U16(7),
+            Token::Str("wrapped"),
+            Token::U16(300),
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn invalid_id() {
+    assert_de_tokens_error::<AsInt<NonZeroU16>>(
+        &[Token::U16(0)],
+        "invalid value: integer `0`, expected a valid core::num::nonzero::NonZero<u16>",
+    );
+}
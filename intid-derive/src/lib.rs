@@ -9,7 +9,7 @@ use crate::analyze::{analyze, AnalyzedType, EnumIdBounds, TargetTrait, Uninhabit
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::{quote, quote_spanned};
 use syn::spanned::Spanned;
-use syn::DeriveInput;
+use syn::{DeriveInput, LitStr};
 
 mod analyze;
 
@@ -141,18 +141,13 @@ pub fn integer_id(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 fn impl_integer_id(ast: &DeriveInput) -> syn::Result<TokenStream> {
     let options = parse_options(ast)?;
     let name = &ast.ident;
-    // TODO: Replace From<&'_ #name> with From<#wrapped_type>?
-    let from_impl = if options.from.is_none() {
-        quote!()
-    } else {
-        quote! {
-            impl From<&'_ #name> for #name {
-                #[inline]
-                fn from(this: &'_ #name) -> #name {
-                    *this
-                }
-            }
-        }
+    let serde_impl = match options.serde {
+        Some(span) => impl_serde(name, span),
+        None => quote!(),
+    };
+    let display_impl = match options.display {
+        Some(ref format) => impl_display(name, format)?,
+        None => quote!(),
     };
     const TARGET_TRAIT: TargetTrait = TargetTrait::IntegerId;
     let analyzed = analyze::analyze(ast, TARGET_TRAIT)?;
@@ -188,7 +183,35 @@ fn impl_integer_id(ast: &DeriveInput) -> syn::Result<TokenStream> {
                 }
                 None => quote!(),
             };
-            let field_name = &tp.wrapped_field_name;
+            let from_impl = match options.from {
+                Some(span) => {
+                    let construct = tp.construct(quote!(inner));
+                    // The integer type must be named directly,
+                    // since coherence can not see through `<Inner as IntegerId>::Int`.
+                    let int_from_impl = match wrapped_int_type(field_type) {
+                        Some(int_type) => impl_int_from(name, &quote!(#int_type), span),
+                        None => quote!(),
+                    };
+                    quote_spanned! { span =>
+                        #[automatically_derived]
+                        impl From<#field_type> for #name {
+                            #[inline]
+                            fn from(inner: #field_type) -> Self {
+                                #construct
+                            }
+                        }
+                        #[automatically_derived]
+                        impl From<#name> for #field_type {
+                            #[inline]
+                            fn from(id: #name) -> Self {
+                                id.#field_name
+                            }
+                        }
+                        #int_from_impl
+                    }
+                }
+                None => quote!(),
+            };
             Ok(quote! {
                 #[automatically_derived]
                 #[allow(clippy::init_numbered_fields)]
@@ -203,7 +226,7 @@ fn impl_integer_id(ast: &DeriveInput) -> syn::Result<TokenStream> {
                         None => None,
                     };
                     const MIN_ID_INT: Option<Self::Int> = #field_type_as_id::MIN_ID_INT;
-                    const MAX_ID_INT: Option<Self::Int> = #field_type_as_id::MIN_ID_INT;
+                    const MAX_ID_INT: Option<Self::Int> = #field_type_as_id::MAX_ID_INT;
                     const TRUSTED_RANGE: Option<intid::trusted::TrustedRangeToken<Self>> = {
                         // SAFETY: We simply delegate, so are valid if #field_type is
                         unsafe { intid::trusted::TrustedRangeToken::assume_valid_if::<#field_type>() }
@@ -230,6 +253,8 @@ fn impl_integer_id(ast: &DeriveInput) -> syn::Result<TokenStream> {
                     }
                 }
                 #from_impl
+                #serde_impl
+                #display_impl
             })
         }
         AnalyzedType::Enum(ref tp) => {
@@ -243,6 +268,10 @@ fn impl_integer_id(ast: &DeriveInput) -> syn::Result<TokenStream> {
                 })
                 .collect::<Vec<_>>();
             let int_type = tp.discriminant_type;
+            let from_impl = match options.from {
+                Some(span) => impl_int_from(name, &quote!(#int_type), span),
+                None => quote!(),
+            };
             let EnumIdBounds { min_id, max_id } = match tp.determine_id_bounds() {
                 Ok(bounds) => bounds.map(|src| quote!(Some(#src))),
                 Err(UninhabitedEnumError) => EnumIdBounds {
@@ -300,11 +329,126 @@ fn impl_integer_id(ast: &DeriveInput) -> syn::Result<TokenStream> {
                     }
                 }
                 #from_impl
+                #serde_impl
+                #display_impl
             })
         }
     }
 }
 
+/// Determine the integer type wrapped by a nonzero or nonmax integer,
+/// like the `u32` in `NonZeroU32`, `NonZero<u32>` or `NonMaxU32`.
+///
+/// Returns `None` for a primitive integer, which is already the integer type,
+/// and for any other type, whose integer type can not be determined syntactically.
+fn wrapped_int_type(ty: &syn::Type) -> Option<Ident> {
+    const INTS: [&str; 6] = ["u8", "u16", "u32", "u64", "u128", "usize"];
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    let name = segment.ident.to_string();
+    let int = match segment.arguments {
+        syn::PathArguments::None => name
+            .strip_prefix("NonZero")
+            .or_else(|| name.strip_prefix("NonMax"))?
+            .to_lowercase(),
+        syn::PathArguments::AngleBracketed(ref args) if name == "NonZero" => {
+            match args.args.first()? {
+                syn::GenericArgument::Type(syn::Type::Path(int)) => {
+                    int.path.get_ident()?.to_string()
+                }
+                _ => return None,
+            }
+        }
+        syn::PathArguments::AngleBracketed(_) | syn::PathArguments::Parenthesized(_) => {
+            return None
+        }
+    };
+    INTS.contains(&&*int).then(|| Ident::new(&int, ty.span()))
+}
+
+/// Implement `TryFrom<Int> for Self` and `From<Self> for Int`.
+fn impl_int_from(name: &Ident, int_type: &TokenStream, span: Span) -> TokenStream {
+    quote_spanned! { span =>
+        #[automatically_derived]
+        impl core::convert::TryFrom<#int_type> for #name {
+            type Error = intid::InvalidIdError<Self>;
+
+            #[inline]
+            fn try_from(int: #int_type) -> Result<Self, Self::Error> {
+                <Self as intid::IntegerId>::try_from_int(int)
+            }
+        }
+        #[automatically_derived]
+        impl From<#name> for #int_type {
+            #[inline]
+            fn from(id: #name) -> Self {
+                intid::IntegerId::to_int(id)
+            }
+        }
+    }
+}
+
+/// Implement `Serialize` and `Deserialize` using the underlying integer,
+/// delegating to `intid::serde::as_int`.
+fn impl_serde(name: &Ident, span: Span) -> TokenStream {
+    quote_spanned! { span =>
+        #[automatically_derived]
+        impl intid::serde::__private::Serialize for #name {
+            #[inline]
+            fn serialize<S: intid::serde::__private::Serializer>(
+                &self,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error> {
+                intid::serde::as_int::serialize(self, serializer)
+            }
+        }
+        #[automatically_derived]
+        impl<'de> intid::serde::__private::Deserialize<'de> for #name {
+            #[inline]
+            fn deserialize<D: intid::serde::__private::Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<Self, D::Error> {
+                intid::serde::as_int::deserialize(deserializer)
+            }
+        }
+    }
+}
+
+/// Implement `Display` and `FromStr` using a format string like `"n{}"`,
+/// where the `{}` is replaced by the underlying integer.
+fn impl_display(name: &Ident, format: &LitStr) -> syn::Result<TokenStream> {
+    let value = format.value();
+    let (prefix, suffix) = value
+        .split_once("{}")
+        .filter(|(prefix, suffix)| !prefix.contains(['{', '}']) && !suffix.contains(['{', '}']))
+        .ok_or_else(|| {
+            syn::Error::new(
+                format.span(),
+                "display format must contain exactly one `{}` and no other braces",
+            )
+        })?;
+    Ok(quote_spanned! { format.span() =>
+        #[automatically_derived]
+        impl core::fmt::Display for #name {
+            #[inline]
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                intid::__private::fmt_with_affixes(*self, #prefix, #suffix, f)
+            }
+        }
+        #[automatically_derived]
+        impl core::str::FromStr for #name {
+            type Err = intid::utils::ParseIdError<Self>;
+
+            #[inline]
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                intid::__private::parse_with_affixes(s, #prefix, #suffix)
+            }
+        }
+    })
+}
+
 /// See the documentation in the `intid` crate for details.
 #[proc_macro_derive(EnumId, attributes(intid))]
 pub fn enum_id(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
@@ -363,14 +507,17 @@ fn parse_options(ast: &DeriveInput) -> syn::Result<MainOptions> {
         .map_or_else(|| Ok(MainOptions::default()), MainOptions::parse_attr)
 }
 
-#[derive(Default, Debug)]
+#[derive(Default)]
 struct MainOptions {
-    /// Automatically generate a `From<&Self>` implementation.
-    ///
-    /// TODO: This should instead generate a `From<Inner>` implementation.
+    /// Generate conversions to and from the wrapped type of a newtype,
+    /// or `TryFrom<Int>` and `From<Self> for Int` for an enum.
     from: Option<Span>,
     /// Options specific to a counter.
     counter: Option<CounterOptions>,
+    /// Implement `Serialize` and `Deserialize` using the underlying integer.
+    serde: Option<Span>,
+    /// Implement `Display` and `FromStr` using the specified format string.
+    display: Option<LitStr>,
 }
 impl MainOptions {
     fn parse_attr(attr: &syn::Attribute) -> syn::Result<Self> {
@@ -379,6 +526,18 @@ impl MainOptions {
             if meta.path.is_ident("from") {
                 res.from = Some(meta.path.span());
                 Ok(())
+            } else if meta.path.is_ident("display") {
+                if res.display.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        &meta.path,
+                        "Specified display twice",
+                    ));
+                }
+                res.display = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("serde") {
+                res.serde = Some(meta.path.span());
+                Ok(())
             } else if meta.path.is_ident("counter") {
                 if res.counter.is_some() {
                     return Err(syn::Error::new_spanned(
@@ -44,3 +44,99 @@ fn assert_enum<T: intid::EnumId>() {
         T::MAX_ID_INT.map_or(0, |x| primint::to_usize_checked(x).unwrap() + 1)
     );
 }
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, intid_derive::IntegerId)]
+#[intid(serde)]
+pub struct Serialized(u16);
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, intid_derive::IntegerId)]
+#[intid(serde)]
+pub enum SerializedEnum {
+    A,
+    B,
+}
+
+#[test]
+fn derive_serde() {
+    use serde_test::{assert_de_tokens_error, assert_tokens, Token};
+    assert_tokens(&Serialized(7), &[Token::U16(7)]);
+    assert_tokens(&SerializedEnum::B, &[Token::U8(1)]);
+    assert_de_tokens_error::<SerializedEnum>(
+        &[Token::U8(2)],
+        "invalid value: integer `2`, expected a valid derive::SerializedEnum",
+    );
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, intid_derive::IntegerId)]
+#[intid(display = "node({})")]
+pub struct Displayed(u32);
+
+#[test]
+fn derive_display() {
+    use intid::utils::ParseIdErrorKind;
+    assert_eq!(Displayed(3).to_string(), "node(3)");
+    assert_eq!("node(3)".parse::<Displayed>().unwrap(), Displayed(3));
+    assert_eq!(
+        *"3".parse::<Displayed>().unwrap_err().kind(),
+        ParseIdErrorKind::InvalidPrefix
+    );
+    assert_eq!(
+        *"node(3".parse::<Displayed>().unwrap_err().kind(),
+        ParseIdErrorKind::InvalidSuffix
+    );
+    assert!(matches!(
+        "node(-3)".parse::<Displayed>().unwrap_err().kind(),
+        ParseIdErrorKind::InvalidNumber(_)
+    ));
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, intid_derive::IntegerId)]
+#[intid(from)]
+pub struct Converted(core::num::NonZeroU16);
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, intid_derive::IntegerId)]
+#[intid(from)]
+pub struct Primitive(u32);
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, intid_derive::IntegerId)]
+#[intid(from)]
+pub enum ConvertedEnum {
+    A,
+    B,
+}
+
+#[test]
+fn derive_from() {
+    use core::num::NonZeroU16;
+    use intid::IntegerId;
+    let inner = NonZeroU16::new(7).unwrap();
+    let id = Converted::from(inner);
+    assert_eq!(NonZeroU16::from(id), inner);
+    let err = Converted::try_from_int(0).unwrap_err();
+    assert_eq!(err.int(), 0);
+    // the integer type is converted as well as the inner type
+    assert_eq!(Converted::try_from(7u16), Ok(id));
+    assert_eq!(u16::from(id), 7);
+    assert_eq!(Converted::try_from(0u16).unwrap_err().int(), 0);
+    assert_eq!(Primitive::from(3u32), Primitive(3));
+    assert_eq!(u32::from(Primitive(3)), 3);
+    assert_eq!(ConvertedEnum::try_from(1u8), Ok(ConvertedEnum::B));
+    assert_eq!(u8::from(ConvertedEnum::A), 0);
+    assert_eq!(
+        ConvertedEnum::try_from(2u8).unwrap_err().to_string(),
+        "Invalid id for derive::ConvertedEnum: 2"
+    );
+}
+
+#[test]
+fn newtype_bounds() {
+    use intid::IntegerId;
+    assert_eq!(Converted::MIN_ID_INT, Some(1));
+    assert_eq!(Converted::MAX_ID_INT, Some(u16::MAX));
+    assert_eq!(Plain::MAX_ID_INT, Some(u64::MAX));
+    // the bounds agree with the ids themselves
+    assert_eq!(
+        Converted::MAX_ID.map(IntegerId::to_int),
+        Converted::MAX_ID_INT
+    );
+}
@@ -19,6 +19,72 @@ pub use intid_core::*;
 /// using the [`intid::define_newtype_id!`](crate::define_newtype_id) declarative macro.
 /// In particular, that macro also derives `Ord + Eq + Hash`,
 /// saving some boilerplate.
+///
+/// # Conversions
+/// The attribute `#[intid(from)]` generates conversions to and from the underlying value.
+/// For a newtype struct, this implements `From<Inner>` for the id and `From<Id>` for the wrapped type,
+/// which also gives an infallible `TryFrom<Inner>`.
+/// If the wrapped type is a nonzero or nonmax integer like `NonZeroU32`, `NonZero<u32>` or `NonMaxU32`,
+/// this also implements `TryFrom<Int>` for the id and `From<Id>` for the [integer type](IntegerId::Int).
+/// The macro determines the integer type from how the field type is spelled,
+/// so these are not generated for any other wrapped type,
+/// including a type alias for a nonzero integer or another id newtype.
+/// For an enum, this implements `TryFrom<Int>` for the id and `From<Id>` for the integer type.
+/// Conversion fails with an [`InvalidIdError`] if the integer is not a valid id.
+/// ```rust
+/// use core::num::NonZeroU32;
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq, intid::IntegerId)]
+/// #[intid(from)]
+/// struct NodeId(NonZeroU32);
+/// let inner = NonZeroU32::new(7).unwrap();
+/// assert_eq!(NonZeroU32::from(NodeId::from(inner)), inner);
+/// assert_eq!(NodeId::try_from(7u32), Ok(NodeId(inner)));
+/// assert_eq!(u32::from(NodeId(inner)), 7);
+/// assert!(NodeId::try_from(0u32).is_err());
+///
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq, intid::IntegerId)]
+/// #[intid(from)]
+/// enum Color {
+///     Red,
+///     Green,
+/// }
+/// assert_eq!(Color::try_from(1u8), Ok(Color::Green));
+/// assert_eq!(u8::from(Color::Red), 0);
+/// assert!(Color::try_from(2u8).is_err());
+/// ```
+///
+/// Use [`IntegerId::try_from_int`] to fallibly convert an integer into any id type.
+///
+/// # Serde
+/// With the `serde` feature enabled, the attribute `#[intid(serde)]`
+/// implements `Serialize` and `Deserialize` using the underlying integer value.
+/// Deserializing an integer that is not a valid id fails with an error.
+/// See the [`intid::serde`](crate::serde) module for details.
+#[cfg_attr(feature = "serde", doc = "```rust")]
+#[cfg_attr(not(feature = "serde"), doc = "```ignore")]
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq, intid::IntegerId)]
+/// #[intid(serde)]
+/// enum Color {
+///     Red,
+///     Green,
+/// }
+/// ```
+///
+/// # Display
+/// The attribute `#[intid(display = "n{}")]` implements [`Display`] and [`FromStr`],
+/// replacing the `{}` with the underlying integer value.
+/// Parsing fails with a [`ParseIdError`](crate::utils::ParseIdError)
+/// if the prefix or suffix is missing, the number is malformed, or the integer is not a valid id.
+/// ```rust
+/// #[derive(Copy, Clone, Debug, Eq, PartialEq, intid::IntegerId)]
+/// #[intid(display = "n{}")]
+/// struct NodeId(u32);
+/// assert_eq!(NodeId(42).to_string(), "n42");
+/// assert_eq!("n42".parse::<NodeId>().unwrap(), NodeId(42));
+/// ```
+///
+/// [`Display`]: core::fmt::Display
+/// [`FromStr`]: core::str::FromStr
 #[cfg(feature = "derive")]
 pub use intid_derive::IntegerId;
 